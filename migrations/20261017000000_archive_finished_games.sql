@@ -0,0 +1,49 @@
+-- Finished games, kept for good once their Redis keys expire.
+CREATE TABLE games (
+    game_id TEXT PRIMARY KEY NOT NULL,
+    host_id TEXT NOT NULL,
+    -- The rule set as stored with the game, e.g. {"type":"CLASSIC"}.
+    rules TEXT NOT NULL,
+    starting_max INTEGER NOT NULL,
+    wager INTEGER NOT NULL,
+    match_id TEXT,
+    tournament_id TEXT,
+    server_seed_hash TEXT,
+    server_seed TEXT,
+    forfeited INTEGER NOT NULL,
+    finished_at TEXT NOT NULL
+);
+
+-- Everyone who played and where they finished. The winning side all place first.
+CREATE TABLE game_players (
+    game_id TEXT NOT NULL REFERENCES games (game_id) ON DELETE CASCADE,
+    player_id TEXT NOT NULL,
+    seat INTEGER NOT NULL,
+    team_id INTEGER,
+    placing INTEGER NOT NULL,
+    won INTEGER NOT NULL,
+    PRIMARY KEY (game_id, player_id)
+);
+
+CREATE INDEX game_players_by_player ON game_players (player_id);
+
+-- The roll chain, one row per roll in the order they were made.
+CREATE TABLE rolls (
+    game_id TEXT NOT NULL REFERENCES games (game_id) ON DELETE CASCADE,
+    roll_number INTEGER NOT NULL,
+    player_id TEXT NOT NULL,
+    min INTEGER NOT NULL,
+    max INTEGER NOT NULL,
+    value INTEGER NOT NULL,
+    nonce INTEGER NOT NULL,
+    rolled_at TEXT NOT NULL,
+    PRIMARY KEY (game_id, roll_number)
+);
+
+-- The whole event stream as JSON, so an archived game can be rebuilt and verified.
+CREATE TABLE game_events (
+    game_id TEXT NOT NULL REFERENCES games (game_id) ON DELETE CASCADE,
+    sequence INTEGER NOT NULL,
+    event TEXT NOT NULL,
+    PRIMARY KEY (game_id, sequence)
+);
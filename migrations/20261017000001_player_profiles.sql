@@ -0,0 +1,21 @@
+-- Whether each roll knocked its roller out, so stats can pick out the rolls a player survived.
+ALTER TABLE rolls ADD COLUMN lost INTEGER NOT NULL DEFAULT 0;
+
+UPDATE rolls SET lost = (
+    SELECT CASE json_extract(games.rules, '$.type')
+        WHEN 'REVERSE' THEN rolls.value = rolls.max
+        WHEN 'THRESHOLD' THEN rolls.value <= json_extract(games.rules, '$.lose_at')
+        ELSE rolls.value = 1
+    END
+    FROM games
+    WHERE games.game_id = rolls.game_id
+);
+
+CREATE INDEX rolls_by_player ON rolls (player_id);
+
+-- Public profiles. Stats are not stored here; they are worked out from the archived games.
+CREATE TABLE players (
+    player_id TEXT PRIMARY KEY NOT NULL,
+    display_name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TEXT NOT NULL
+);
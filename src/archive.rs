@@ -0,0 +1,254 @@
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions},
+    Row,
+};
+use std::str::FromStr;
+use tokio::sync::OnceCell;
+
+use crate::data::decode_event;
+use crate::error::AppError;
+use crate::game::{
+    types::{GameEvent, RecordedEvent},
+    Game, GameId, PlayerId,
+};
+
+/// Permanent storage for finished games. Live games stay in the `GameRepository`, which lets them
+/// expire; a game is archived when it ends, so its result and rolls outlive it.
+#[async_trait]
+pub trait GameArchive: Send + Sync {
+    /// Store a finished game with its whole event stream. Archiving a game again replaces what was stored.
+    async fn archive_game(&self, game: &Game, events: &[RecordedEvent]) -> Result<(), AppError>;
+    /// An archived game's event stream, oldest first; empty if the game was never archived.
+    async fn load_archived_events(&self, game_id: GameId) -> Result<Vec<RecordedEvent>, AppError>;
+    /// Rebuild an archived game from its event stream.
+    async fn load_archived_game(&self, game_id: GameId) -> Result<Game, AppError> {
+        Game::from_events(&self.load_archived_events(game_id).await?).ok_or(AppError::GameNotFound(game_id))
+    }
+}
+
+/// A `GameArchive` in an embedded SQLite database, created and migrated on first use. It also keeps
+/// player profiles; see `players`.
+pub struct SqliteArchive {
+    options: SqliteConnectOptions,
+    pool: OnceCell<SqlitePool>,
+}
+
+impl SqliteArchive {
+    /// `url` is a SQLite URL such as `sqlite://critical-one.db`, or `sqlite::memory:` for a throwaway archive.
+    pub fn new(url: &str) -> Result<Self, AppError> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        Ok(Self { options, pool: OnceCell::new() })
+    }
+
+    pub(crate) async fn pool(&self) -> Result<&SqlitePool, AppError> {
+        self.pool
+            .get_or_try_init(|| async {
+                // SQLite takes one writer at a time anyway, and a connection that never closes also
+                // keeps an in-memory database alive.
+                let pool = SqlitePoolOptions::new()
+                    .max_connections(1)
+                    .idle_timeout(None)
+                    .max_lifetime(None)
+                    .connect_with(self.options.clone())
+                    .await?;
+                sqlx::migrate!()
+                    .run(&pool)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Archive migration failed: {}", e)))?;
+                Ok(pool)
+            })
+            .await
+    }
+}
+
+/// Timestamps are stored as fixed-width RFC 3339 text, so they sort in time order.
+pub(crate) fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// Where `player_id` finished: the winning side all place first, everyone else by their standing.
+fn placing(player_id: PlayerId, winners: &[PlayerId], standings: &[PlayerId]) -> i64 {
+    if winners.contains(&player_id) {
+        return 1;
+    }
+    standings
+        .iter()
+        .position(|p| *p == player_id)
+        .unwrap_or(standings.len()) as i64
+        + 1
+}
+
+#[async_trait]
+impl GameArchive for SqliteArchive {
+    async fn archive_game(&self, game: &Game, events: &[RecordedEvent]) -> Result<(), AppError> {
+        let game_over = events.iter().rev().find_map(|recorded| match &recorded.event {
+            GameEvent::GameOver { winning_team, standings, forfeited, .. } => {
+                Some((recorded.recorded_at, &winning_team.players, standings, *forfeited))
+            }
+            _ => None,
+        });
+        let Some((finished_at, winners, standings, forfeited)) = game_over else {
+            return Err(AppError::Internal(format!(
+                "Game {} is not over, so it cannot be archived",
+                game.get_id()
+            )));
+        };
+        let game_id = game.get_id().to_string();
+        let settings = game.get_settings();
+        let fairness = game.get_fairness();
+        // Worked out up front: the rules are not `Sync`, so they cannot be held across an await.
+        let lost: Vec<bool> = {
+            let rules = settings.rules.rules();
+            game.get_history()
+                .iter()
+                .map(|roll| rules.is_loss((roll.min, roll.max), roll.value))
+                .collect()
+        };
+
+        let mut tx = self.pool().await?.begin().await?;
+        // The other tables cascade, so this clears out an earlier archive of the game.
+        sqlx::query("DELETE FROM games WHERE game_id = ?")
+            .bind(&game_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO games (game_id, host_id, rules, starting_max, wager, match_id, tournament_id, \
+             server_seed_hash, server_seed, forfeited, finished_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&game_id)
+        .bind(game.get_host().to_string())
+        .bind(serde_json::to_string(&settings.rules)?)
+        .bind(settings.starting_max)
+        .bind(settings.wager as i64)
+        .bind(game.get_match_id().map(|id| id.to_string()))
+        .bind(game.get_tournament_id().map(|id| id.to_string()))
+        .bind(fairness.get_server_seed_hash())
+        .bind(fairness.get_revealed_server_seed())
+        .bind(forfeited)
+        .bind(timestamp(finished_at))
+        .execute(&mut *tx)
+        .await?;
+
+        for (seat, player_id) in game.get_players().iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO game_players (game_id, player_id, seat, team_id, placing, won) VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&game_id)
+            .bind(player_id.to_string())
+            .bind(seat as i64)
+            .bind(game.get_team(*player_id).map(|team_id| team_id.0 as i64))
+            .bind(placing(*player_id, winners, standings))
+            .bind(winners.contains(player_id))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for (roll_number, (roll, lost)) in game.get_history().iter().zip(lost).enumerate() {
+            sqlx::query(
+                "INSERT INTO rolls (game_id, roll_number, player_id, min, max, value, nonce, rolled_at, lost) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&game_id)
+            .bind(roll_number as i64 + 1)
+            .bind(roll.player_id.to_string())
+            .bind(roll.min)
+            .bind(roll.max)
+            .bind(roll.value)
+            .bind(roll.nonce as i64)
+            .bind(timestamp(roll.rolled_at))
+            .bind(lost)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for recorded in events {
+            sqlx::query("INSERT INTO game_events (game_id, sequence, event) VALUES (?, ?, ?)")
+                .bind(&game_id)
+                .bind(recorded.sequence as i64)
+                .bind(serde_json::to_string(recorded)?)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn load_archived_events(&self, game_id: GameId) -> Result<Vec<RecordedEvent>, AppError> {
+        let rows = sqlx::query("SELECT event FROM game_events WHERE game_id = ? ORDER BY sequence")
+            .bind(game_id.to_string())
+            .fetch_all(self.pool().await?)
+            .await?;
+        rows.iter().map(|row| decode_event(row.try_get("event")?)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{fairness::ServerSeed, roller::SeededRoller};
+
+    /// A two-player game played out to the end with a fixed roller.
+    fn finished_game() -> (Game, Vec<RecordedEvent>) {
+        let host_id = PlayerId::new();
+        let guest_id = PlayerId::new();
+        let server_seed = ServerSeed::generate();
+        let mut game = Game::new(host_id);
+        game.commit_server_seed(server_seed.commitment()).unwrap();
+        game.join(guest_id).unwrap();
+        game.set_ready(guest_id, true).unwrap();
+        let mut roller = SeededRoller::new([7; 32], 0);
+        game.start(host_id, &mut roller).unwrap();
+        while !game.is_finished() {
+            let player_id = *game.get_current_player().unwrap();
+            game.roll(player_id, &mut roller).unwrap();
+        }
+        game.reveal_server_seed(&server_seed).unwrap();
+        let events = game.take_uncommitted_events();
+        (game, events)
+    }
+
+    #[tokio::test]
+    async fn test_archived_game_is_rebuilt_from_its_events() {
+        let archive = SqliteArchive::new("sqlite::memory:").unwrap();
+        let (game, events) = finished_game();
+
+        archive.archive_game(&game, &events).await.unwrap();
+        // Archiving again replaces the first copy rather than failing on it.
+        archive.archive_game(&game, &events).await.unwrap();
+
+        assert_eq!(archive.load_archived_events(game.get_id()).await.unwrap(), events);
+        assert_eq!(archive.load_archived_game(game.get_id()).await.unwrap(), game);
+
+        let pool = archive.pool().await.unwrap();
+        let rolls: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM rolls")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(rolls as usize, game.get_history().len());
+        let winner: String = sqlx::query_scalar("SELECT player_id FROM game_players WHERE won AND placing = 1")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let loser = game.get_loser().unwrap();
+        assert_ne!(winner, loser.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_unfinished_game_is_not_archived() {
+        let archive = SqliteArchive::new("sqlite::memory:").unwrap();
+        let mut game = Game::new(PlayerId::new());
+        let events = game.take_uncommitted_events();
+
+        assert!(matches!(
+            archive.archive_game(&game, &events).await,
+            Err(AppError::Internal(_))
+        ));
+        assert!(matches!(
+            archive.load_archived_game(game.get_id()).await,
+            Err(AppError::GameNotFound(_))
+        ));
+    }
+}
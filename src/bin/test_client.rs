@@ -19,10 +19,16 @@ struct JoinGameRequest {
     player_id: Option<PlayerId>,
 }
 
+#[derive(Debug, Serialize)]
+struct StartGameRequest {
+    player_id: PlayerId,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", content = "payload", rename_all = "SCREAMING_SNAKE_CASE")]
 enum ClientMessage {
     Roll,
+    Ready { ready: bool },
 }
 
 async fn spawn_game_connection(
@@ -49,6 +55,13 @@ async fn spawn_game_connection(
             if let Err(e) = write.send(Message::Text(msg.into())).await {
                 eprintln!("....[{name}] Failed to send roll: {}", e);
             }
+        } else {
+            println!("....[{name}] Ready!");
+            let msg = serde_json::to_string(&ClientMessage::Ready { ready: true }).unwrap();
+
+            if let Err(e) = write.send(Message::Text(msg.into())).await {
+                eprintln!("....[{name}] Failed to send ready: {}", e);
+            }
         }
 
         let listen_duration = Duration::from_secs(5);
@@ -127,6 +140,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let host_handle = spawn_game_connection(game_id, host_id, "Host".to_string(), true).await?;
     let guest_handle = spawn_game_connection(game_id, guest_id, "Guest".to_string(), false).await?;
 
+    println!("\n[4] Host Starting Game...");
+    sleep(Duration::from_secs(1)).await;
+    let start_resp = client
+        .post(format!("{}/game/{}/start", base_url, game_id))
+        .json(&StartGameRequest { player_id: host_id })
+        .send()
+        .await?;
+
+    if !start_resp.status().is_success() {
+        eprintln!("Start failed: {}", start_resp.text().await?);
+    }
+
     // Wait for both tasks to complete their loops
     let _ = tokio::join!(host_handle, guest_handle);
 
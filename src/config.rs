@@ -10,6 +10,8 @@ pub struct ServerConfig {
 #[derive(Debug, Clone, Deserialize)]
 pub struct DatabaseConfig {
     pub redis_url: String,
+    /// The SQLite database finished games are archived to; created on first use.
+    pub sqlite_url: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -17,11 +19,57 @@ pub struct LoggingConfig {
     pub level: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Upper bound on the player cap a host may choose for a single game.
+    pub max_players: usize,
+    /// Bounds on the opening roll a host may choose for a single game.
+    pub min_starting_max: u32,
+    pub max_starting_max: u32,
+    /// Bounds on the per-turn timer a host may choose; zero (no timer) is always allowed.
+    pub min_turn_timeout_secs: u64,
+    pub max_turn_timeout_secs: u64,
+    /// Longest reconnect grace window a host may choose; zero (wait forever) is always allowed.
+    pub max_reconnect_grace_secs: u64,
+    /// How often the server looks for turn and reconnect deadlines that have passed.
+    pub deadline_sweep_interval_ms: u64,
+    /// Most spectators a host may let watch a single game.
+    pub max_spectators: usize,
+    /// Largest field a tournament may open registration for.
+    pub max_tournament_players: usize,
+    /// Games a player must have finished before they show up on the win rate leaderboard.
+    pub leaderboard_min_games: u64,
+    /// Largest stake a host may ask of each player. Keeps every pot well inside what the wallet
+    /// scripts and the archive can count exactly.
+    pub max_wager: u64,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            max_players: 10,
+            min_starting_max: 2,
+            max_starting_max: 1_000_000,
+            min_turn_timeout_secs: 5,
+            max_turn_timeout_secs: 600,
+            max_reconnect_grace_secs: 600,
+            deadline_sweep_interval_ms: 1000,
+            max_spectators: 500,
+            max_tournament_players: 64,
+            leaderboard_min_games: 10,
+            max_wager: 1_000_000_000,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
     pub database: DatabaseConfig,
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub game: GameConfig,
 }
 
 impl Config {
@@ -54,8 +102,19 @@ mod tests {
 
         let config = Config::load().expect("Failed to load config.");
         assert_eq!(config.database.redis_url, "redis://127.0.0.1:6379/");
+        assert_eq!(config.database.sqlite_url, "sqlite://critical-one.db");
         assert_eq!(config.logging.level, "info,critical_one=debug,tower_http=debug");
         assert_eq!(config.server.addr, "127.0.0.1:3000");
+        assert_eq!(config.game.max_players, 10);
+        assert_eq!(config.game.min_starting_max, 2);
+        assert_eq!(config.game.max_starting_max, 1_000_000);
+        assert_eq!(config.game.max_turn_timeout_secs, 600);
+        assert_eq!(config.game.max_reconnect_grace_secs, 600);
+        assert_eq!(config.game.deadline_sweep_interval_ms, 1000);
+        assert_eq!(config.game.max_spectators, 500);
+        assert_eq!(config.game.max_tournament_players, 64);
+        assert_eq!(config.game.leaderboard_min_games, 10);
+        assert_eq!(config.game.max_wager, 1_000_000_000);
     }
 
     #[test]
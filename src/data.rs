@@ -1,15 +1,41 @@
 use async_trait::async_trait;
-use redis::AsyncCommands;
+use redis::{aio::ConnectionManager, AsyncCommands, FromRedisValue};
 use serde::{Deserialize, Serialize};
 use serde_json;
 
 use crate::error::AppError;
-use crate::game::{Game, GameId, PlayerId};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::OnceCell;
+
+use crate::game::{
+    fairness::ServerSeed,
+    roller::{sample_range, Roller},
+    settings::{TimeoutAction, TurnOrder},
+    types::{GameEvent, RecordedEvent, RollRecord, EVENT_SCHEMA_VERSION},
+    Game, GameError, GameId, GameStatus, Match, MatchId, PlayerId, RuleSet, Seeding, Team, TeamId, Tournament,
+    TournamentId,
+};
+use crate::leaderboards::{LeaderboardEntry, LeaderboardKind, LeaderboardPage, LeaderboardUpdate};
+use crate::players::{PlayerProfile, PlayerStats};
+use crate::wallet::{split_pot, LedgerEntry};
 
 // --- DTOs (Data Transfer Objects) ---
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct CreateGameRequest {
     pub host_id: Option<PlayerId>,
+    pub max_players: Option<usize>,
+    pub starting_max: Option<u32>,
+    pub wager: Option<u64>,
+    pub turn_timeout_secs: Option<u64>,
+    pub on_timeout: Option<TimeoutAction>,
+    pub reconnect_grace_secs: Option<u64>,
+    pub rules: Option<RuleSet>,
+    pub turn_order: Option<TurnOrder>,
+    pub max_spectators: Option<usize>,
+    pub teams: Option<usize>,
+    pub client_seed: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -19,71 +45,1140 @@ pub struct CreateGameResponse {
     //pub invite_code: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct JoinGameRequest {
     pub player_id: Option<PlayerId>,
+    pub client_seed: Option<String>,
+    /// Must be `true` to join a table with a wager.
+    #[serde(default)]
+    pub accept_wager: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartGameRequest {
+    /// Must be the host.
+    pub player_id: PlayerId,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeaveGameRequest {
+    pub player_id: PlayerId,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KickPlayerRequest {
+    /// Must be the host.
+    pub host_id: PlayerId,
+    pub player_id: PlayerId,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignTeamRequest {
+    pub player_id: PlayerId,
+    pub team_id: TeamId,
+    /// Who is making the change, if not the player themselves. Only the host can move someone else.
+    pub assigned_by: Option<PlayerId>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpectatorLimitRequest {
+    /// Must be the host.
+    pub host_id: PlayerId,
+    /// Zero turns spectating off.
+    pub max_spectators: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateMatchRequest {
+    /// Seating for the first game; the first seat rolls first.
+    pub players: Vec<PlayerId>,
+    pub best_of: Option<u32>,
+    pub starting_max: Option<u32>,
+    pub wager: Option<u64>,
+    pub turn_timeout_secs: Option<u64>,
+    pub on_timeout: Option<TimeoutAction>,
+    pub reconnect_grace_secs: Option<u64>,
+    pub rules: Option<RuleSet>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTournamentRequest {
+    pub host_id: PlayerId,
+    pub max_players: usize,
+    #[serde(default)]
+    pub seeding: Seeding,
+    pub starting_max: Option<u32>,
+    pub turn_timeout_secs: Option<u64>,
+    pub on_timeout: Option<TimeoutAction>,
+    pub reconnect_grace_secs: Option<u64>,
+    pub rules: Option<RuleSet>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterRequest {
+    pub player_id: PlayerId,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WithdrawRequest {
+    pub player_id: PlayerId,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePlayerRequest {
+    /// Attach the profile to an id the player already plays under; a new id is made up if missing.
+    pub player_id: Option<PlayerId>,
+    pub display_name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PlayerResponse {
+    #[serde(flatten)]
+    pub profile: PlayerProfile,
+    pub stats: PlayerStats,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LeaderboardQuery {
+    pub offset: Option<u64>,
+    /// Capped at `leaderboards::MAX_LEADERBOARD_PAGE`.
+    pub limit: Option<u64>,
+    /// Also look up where this player stands, wherever that is on the board.
+    pub player_id: Option<PlayerId>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LeaderboardResponse {
+    pub kind: LeaderboardKind,
+    #[serde(flatten)]
+    pub page: LeaderboardPage,
+    /// The asked-for player's entry; missing if they were not asked for or are not on the board.
+    pub me: Option<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BalanceResponse {
+    pub player_id: PlayerId,
+    pub balance: u64,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", content = "payload", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ClientMessage {
-    Connect { player_id: PlayerId },
+    Connect {
+        player_id: PlayerId,
+    },
     Roll,
+    /// Toggle this player's ready flag in the lobby.
+    Ready {
+        ready: bool,
+    },
+    /// Give up this player's lobby seat.
+    Leave,
+    /// Host-only: remove a player from the lobby.
+    Kick {
+        player_id: PlayerId,
+    },
+    /// Switch to another team. The host may move someone else by naming them.
+    Team {
+        team_id: TeamId,
+        player_id: Option<PlayerId>,
+    },
+    /// Resign from the game.
+    Forfeit,
+    /// Ask to play the same table again once the game is over.
+    Rematch,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(tag = "type", content = "payload", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ServerMessage {
     GameState(Game),
-    Error { message: String },
-    PlayerJoined { player_id: PlayerId },
-    RollResult { player_id: PlayerId, rolled_value: u32 },
-    GameStarted { game: Game },
-    GameOver { winner_id: PlayerId, loser_id: PlayerId },
+    Error {
+        message: String,
+    },
+    PlayerJoined {
+        player_id: PlayerId,
+    },
+    PlayerReady {
+        player_id: PlayerId,
+        ready: bool,
+    },
+    /// `host_id` is who runs the lobby now, which changes if the host left.
+    PlayerLeft {
+        player_id: PlayerId,
+        host_id: PlayerId,
+    },
+    PlayerKicked {
+        player_id: PlayerId,
+    },
+    TeamChanged {
+        player_id: PlayerId,
+        team_id: TeamId,
+    },
+    RollResult {
+        player_id: PlayerId,
+        rolled_value: u32,
+    },
+    PlayerEliminated {
+        player_id: PlayerId,
+        remaining: Vec<PlayerId>,
+    },
+    /// The teammates who went out with the player who lost the game for their team.
+    TeamEliminated {
+        team_id: TeamId,
+        players: Vec<PlayerId>,
+        remaining: Vec<PlayerId>,
+    },
+    GameStarted {
+        game: Game,
+    },
+    /// How the seating was settled; `order` runs from the first player to roll.
+    TurnOrderDecided {
+        policy: TurnOrder,
+        order: Vec<PlayerId>,
+        rolls: Vec<RollRecord>,
+    },
+    PlayerForfeited {
+        player_id: PlayerId,
+        winner_id: Option<PlayerId>,
+        remaining: Vec<PlayerId>,
+    },
+    GameOver {
+        winning_team: Team,
+        losing_team: Team,
+        standings: Vec<PlayerId>,
+        forfeited: bool,
+    },
+    /// `amount` is the whole pot, split evenly between the winners.
+    PotSettled {
+        winners: Vec<PlayerId>,
+        amount: u64,
+    },
+    /// The game ended without a winner, so every stake went back to the player who put it in.
+    PotRefunded {
+        amount: u64,
+    },
+    /// A player asked for a rematch; `accepted` is everyone who has so far.
+    RematchVote {
+        player_id: PlayerId,
+        accepted: Vec<PlayerId>,
+    },
+    /// Everyone accepted; clients should switch over to `game_id`.
+    RematchReady {
+        previous_game_id: GameId,
+        game_id: GameId,
+    },
+    /// Sent whenever a turn clock starts, so clients can show a countdown.
+    TurnTimer {
+        player_id: PlayerId,
+        deadline: DateTime<Utc>,
+        remaining_ms: i64,
+    },
+    /// Sent when a player drops, so the rest of the table can show how long they have to return.
+    ReconnectTimer {
+        player_id: PlayerId,
+        deadline: DateTime<Utc>,
+        remaining_ms: i64,
+    },
+    MatchProgress {
+        match_id: MatchId,
+        wins: BTreeMap<PlayerId, u32>,
+        next_game_id: GameId,
+    },
+    MatchOver {
+        match_id: MatchId,
+        winner_id: PlayerId,
+        wins: BTreeMap<PlayerId, u32>,
+    },
+    /// The next game of the match could not be started, so the match ends here without a winner.
+    MatchVoided {
+        match_id: MatchId,
+        wins: BTreeMap<PlayerId, u32>,
+        reason: String,
+    },
+    /// The whole bracket, sent to the bracket feed whenever it changes and to a table whose
+    /// game just decided a pairing.
+    BracketState(Tournament),
 }
 
 #[async_trait]
 pub trait GameRepository: Send + Sync {
+    /// The latest snapshot, caught up with any events recorded after it. Without a snapshot the
+    /// game is rebuilt from its whole event stream.
     async fn load_game(&self, game_id: GameId) -> Result<Game, AppError>;
+    /// Append the game's uncommitted events to its stream, then store a fresh snapshot. Fails with
+    /// `AppError::VersionConflict` if another request saved the game after this copy was loaded;
+    /// reload and try the command again. The snapshot is skipped if the stream has already moved on.
     async fn save_game(&self, game: &Game) -> Result<(), AppError>;
+    /// Append events to a game's stream. Events already stored are skipped, so saving the
+    /// same game twice is harmless, but the stream must not be left with a gap. A different event
+    /// already stored at one of the sequences is an `AppError::VersionConflict`, and nothing is appended.
+    async fn append_events(&self, game_id: GameId, events: &[RecordedEvent]) -> Result<(), AppError>;
+    /// The events of a game's stream with a sequence above `after`, oldest first.
+    async fn load_events(&self, game_id: GameId, after: u64) -> Result<Vec<RecordedEvent>, AppError>;
+    /// Take `player_id`'s roll in one atomic step, returning the game after it and the events it
+    /// recorded. Only a roll the player survives can be taken this way, and only with a draw stored by
+    /// `save_roll_draws`; `None` means nothing was recorded, and the roll has to go through `Game::roll`.
+    async fn roll(
+        &self,
+        game_id: GameId,
+        player_id: PlayerId,
+        rolled_at: DateTime<Utc>,
+    ) -> Result<Option<(Game, Vec<GameEvent>)>, AppError>;
+    /// Store the generator outputs for the rolls from `first_nonce` on, for `roll` to use; see
+    /// `fairness::roll_draws`.
+    async fn save_roll_draws(&self, game_id: GameId, first_nonce: u64, draws: &[[u32; 2]]) -> Result<(), AppError>;
+    async fn save_server_seed(&self, game_id: GameId, server_seed: &ServerSeed) -> Result<(), AppError>;
+    async fn load_server_seed(&self, game_id: GameId) -> Result<ServerSeed, AppError>;
+    /// Games with a turn or reconnect deadline at or before `now`.
+    async fn load_expired_deadlines(&self, now: DateTime<Utc>) -> Result<Vec<GameId>, AppError>;
+    async fn load_match(&self, match_id: MatchId) -> Result<Match, AppError>;
+    /// Save the match and move `series` on to the stored version. Fails with `AppError::MatchConflict`
+    /// if another request saved it after this copy was loaded.
+    async fn save_match(&self, series: &mut Match) -> Result<(), AppError>;
+    async fn load_tournament(&self, tournament_id: TournamentId) -> Result<Tournament, AppError>;
+    /// Save the tournament and move `tournament` on to the stored version. Fails with
+    /// `AppError::TournamentConflict` if another request saved it after this copy was loaded.
+    async fn save_tournament(&self, tournament: &mut Tournament) -> Result<(), AppError>;
+
+    // --- Wallet ---
+    async fn get_balance(&self, player_id: PlayerId) -> Result<u64, AppError>;
+    /// Credit a balance. There is no HTTP route for this; funds come in through the operator's own tooling.
+    async fn deposit(&self, player_id: PlayerId, amount: u64) -> Result<u64, AppError>;
+    /// Move `amount` from every player's balance into the game's pot, or fail without moving anything.
+    /// Escrowing the same stakes for the same game again moves nothing and succeeds.
+    async fn escrow_stakes(&self, game_id: GameId, players: &[PlayerId], amount: u64) -> Result<(), AppError>;
+    /// Pay the whole pot out to the winners in one step, split as `wallet::split_pot` does.
+    /// Returns the amount paid (zero if already settled). Fails, leaving the pot alone, if there are no winners.
+    async fn settle_pot(&self, game_id: GameId, winners: &[PlayerId]) -> Result<u64, AppError>;
+    /// Hand every stake still in the pot back to the player who put it in, for a game that ended without
+    /// a winner. Returns the amount refunded (zero if the pot was already settled or refunded).
+    async fn refund_pot(&self, game_id: GameId) -> Result<u64, AppError>;
+    /// Games whose stakes have been in escrow since before `before`.
+    async fn load_open_pots(&self, before: DateTime<Utc>) -> Result<Vec<GameId>, AppError>;
+    async fn load_ledger(&self, player_id: PlayerId) -> Result<Vec<LedgerEntry>, AppError>;
+
+    // --- Leaderboards ---
+    /// Apply every update in one step, so no reader sees a game half counted.
+    async fn update_leaderboards(&self, updates: &[LeaderboardUpdate]) -> Result<(), AppError>;
+    /// Up to `limit` entries from the `offset`th best down.
+    async fn load_leaderboard(
+        &self,
+        kind: LeaderboardKind,
+        offset: u64,
+        limit: u64,
+    ) -> Result<LeaderboardPage, AppError>;
+    /// Where the player stands on the board; `None` if they are not on it.
+    async fn load_leaderboard_rank(
+        &self,
+        kind: LeaderboardKind,
+        player_id: PlayerId,
+    ) -> Result<Option<LeaderboardEntry>, AppError>;
 }
 
 pub struct RedisRepository {
     redis_client: redis::Client,
+    // Opened on first use and shared by every request; it reconnects by itself when Redis drops it.
+    connection: OnceCell<ConnectionManager>,
+}
+
+// KEYS: pot, open pots, then (balance, ledger) per player.
+// ARGV: amount, escrow time, game id, then (player id, ledger entry) per player.
+// The pot holds each player's stake, so it can be handed back if the game is never won.
+const ESCROW_SCRIPT: &str = r#"
+local amount = tonumber(ARGV[1])
+local players = (#KEYS - 2) / 2
+if redis.call('EXISTS', KEYS[1]) == 1 then
+    -- Escrowing the same stakes again is a no-op; anything else is a different pot.
+    if redis.call('HLEN', KEYS[1]) ~= players then
+        return {'ALREADY_ESCROWED', 0}
+    end
+    for i = 1, players do
+        if tonumber(redis.call('HGET', KEYS[1], ARGV[2 * i + 2]) or '0') ~= amount then
+            return {'ALREADY_ESCROWED', 0}
+        end
+    end
+    return {'OK', 0}
+end
+for i = 1, players do
+    local balance = tonumber(redis.call('GET', KEYS[2 * i + 1]) or '0')
+    if balance < amount then
+        return {'INSUFFICIENT_FUNDS', i}
+    end
+end
+for i = 1, players do
+    redis.call('DECRBY', KEYS[2 * i + 1], amount)
+    redis.call('RPUSH', KEYS[2 * i + 2], ARGV[2 * i + 3])
+    redis.call('HSET', KEYS[1], ARGV[2 * i + 2], amount)
+end
+redis.call('ZADD', KEYS[2], ARGV[2], ARGV[3])
+return {'OK', 0}
+"#;
+
+// KEYS: pot, open pots, then (balance, ledger) per winner. ARGV: game id, then one ledger entry per winner,
+// with the amount to fill in. The split matches `wallet::split_pot`: equal shares, with the odd coins going
+// to the first winners.
+const SETTLE_SCRIPT: &str = r#"
+if #KEYS == 2 then
+    return redis.error_reply('NO_WINNERS')
+end
+local pot = 0
+for _, stake in ipairs(redis.call('HVALS', KEYS[1])) do
+    pot = pot + tonumber(stake)
+end
+if pot == 0 then
+    return 0
+end
+redis.call('DEL', KEYS[1])
+redis.call('ZREM', KEYS[2], ARGV[1])
+local winners = (#KEYS - 2) / 2
+local share = math.floor(pot / winners)
+local extra = pot % winners
+for i = 1, winners do
+    local amount = share
+    if i <= extra then
+        amount = amount + 1
+    end
+    redis.call('INCRBY', KEYS[2 * i + 1], amount)
+    local entry = cjson.decode(ARGV[i + 1])
+    entry['amount'] = amount
+    redis.call('RPUSH', KEYS[2 * i + 2], cjson.encode(entry))
+end
+return pot
+"#;
+
+// KEYS: pot, open pots, then (balance, ledger) per staked player. ARGV: game id, then (player id, ledger entry)
+// per player, with the amount to fill in. Each player gets back exactly what they put in.
+const REFUND_SCRIPT: &str = r#"
+local refunded = 0
+local players = (#KEYS - 2) / 2
+for i = 1, players do
+    local stake = tonumber(redis.call('HGET', KEYS[1], ARGV[2 * i]) or '0')
+    if stake > 0 then
+        redis.call('HDEL', KEYS[1], ARGV[2 * i])
+        redis.call('INCRBY', KEYS[2 * i + 1], stake)
+        local entry = cjson.decode(ARGV[2 * i + 1])
+        entry['amount'] = stake
+        redis.call('RPUSH', KEYS[2 * i + 2], cjson.encode(entry))
+        refunded = refunded + stake
+    end
+end
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    redis.call('ZREM', KEYS[2], ARGV[1])
+end
+return refunded
+"#;
+
+// KEYS: tournament or match, its version. ARGV: version the copy was loaded at, the record at the next
+// version. Both are kept for a week, so a weekly tournament outlives its registration window and a match
+// outlives a slow series.
+const SAVE_VERSIONED_SCRIPT: &str = r#"
+local stored = tonumber(redis.call('GET', KEYS[2]) or '0')
+if stored ~= tonumber(ARGV[1]) then
+    return 0
+end
+redis.call('SET', KEYS[1], ARGV[2], 'EX', 7 * 86400)
+redis.call('SET', KEYS[2], stored + 1, 'EX', 7 * 86400)
+return 1
+"#;
+
+// KEYS: event stream. ARGV: sequence of the first event, then one event per argument.
+// An event already stored at a sequence must be the one being appended; anything else was written
+// by another request, and the whole append is refused.
+const APPEND_EVENTS_SCRIPT: &str = r#"
+local stored = redis.call('LLEN', KEYS[1])
+local first = tonumber(ARGV[1])
+if first > stored + 1 then
+    return {'GAP', stored}
+end
+for i = 2, #ARGV do
+    local sequence = first + i - 2
+    if sequence <= stored and redis.call('LINDEX', KEYS[1], sequence - 1) ~= ARGV[i] then
+        return {'CONFLICT', stored}
+    end
+end
+for i = 2, #ARGV do
+    if first + i - 2 > stored then
+        redis.call('RPUSH', KEYS[1], ARGV[i])
+    end
+end
+redis.call('EXPIRE', KEYS[1], 86400)
+return {'OK', stored}
+"#;
+
+// KEYS: event stream, snapshot, deadline index, turn. ARGV: game version, snapshot, game id, deadline in ms
+// ('' for none), then the turn's field/value pairs (none if the roll script cannot take the next roll).
+// A stream that has moved past the version belongs to a newer save, whose snapshot and deadline win.
+const SNAPSHOT_SCRIPT: &str = r#"
+if redis.call('LLEN', KEYS[1]) > tonumber(ARGV[1]) then
+    return 0
+end
+redis.call('SET', KEYS[2], ARGV[2], 'EX', 86400)
+if ARGV[4] == '' then
+    redis.call('ZREM', KEYS[3], ARGV[3])
+else
+    redis.call('ZADD', KEYS[3], ARGV[4], ARGV[3])
+end
+redis.call('DEL', KEYS[4])
+if #ARGV > 4 then
+    redis.call('HSET', KEYS[4], unpack(ARGV, 5))
+    redis.call('EXPIRE', KEYS[4], 86400)
+end
+return 1
+"#;
+
+// KEYS: event stream, snapshot, turn, draws, deadline index.
+// ARGV: player id, rolled_at as a JSON string, rolled_at in ms, game id, event schema.
+// Takes a roll the player survives without loading the game, following `Game::roll_at` and the
+// rules in `game::rules`. The turn must describe the stream as it stands, and the roll's draw must be
+// stored; anything else, and any losing roll, is left to the server. The range sampling is
+// `roller::sample_range`, in 16-bit halves so that no product outgrows a Lua number.
+const ROLL_SCRIPT: &str = r#"
+local function wide_mul(x, range)
+    local high = math.floor(x / 65536) * range
+    local sum = (high % 65536) * 65536 + (x % 65536) * range
+    return math.floor(high / 65536) + math.floor(sum / 4294967296), sum % 4294967296
+end
+
+local function sample(first, second, range)
+    local result, low = wide_mul(first, range)
+    if low > 4294967296 - range then
+        local carry = wide_mul(second, range)
+        if low + carry > 4294967295 then
+            result = result + 1
+        end
+    end
+    return result + 1
+end
+
+local turn = redis.call('HMGET', KEYS[3], 'version', 'snapshot', 'seats', 'turn', 'min', 'max', 'nonce',
+    'rules', 'lose_at', 'timeout_ms')
+local version = tonumber(turn[1])
+if not version or version ~= redis.call('LLEN', KEYS[1]) then
+    return {'FALLBACK'}
+end
+local seats = {}
+for seat in string.gmatch(turn[3], '[^,]+') do
+    seats[#seats + 1] = seat
+end
+local seat = tonumber(turn[4])
+if seats[seat] ~= ARGV[1] then
+    return {'NOT_YOUR_TURN'}
+end
+
+local draws = redis.call('HGET', KEYS[4], turn[7])
+local snapshot = redis.call('GET', KEYS[2])
+if not draws or not snapshot then
+    return {'FALLBACK'}
+end
+local first, second = string.match(draws, '^(%d+):(%d+)$')
+local min, max, nonce = tonumber(turn[5]), tonumber(turn[6]), tonumber(turn[7])
+local value = min - 1 + sample(tonumber(first), tonumber(second), max - min + 1)
+
+local next_min, next_max
+if turn[8] == 'REVERSE' then
+    if value == max then
+        return {'FALLBACK'}
+    end
+    next_min, next_max = value, max
+else
+    local lose_at = 1
+    if turn[8] == 'THRESHOLD' then
+        lose_at = tonumber(turn[9])
+    end
+    if value <= lose_at then
+        return {'FALLBACK'}
+    end
+    next_min, next_max = 1, value
+    if turn[8] == 'HALVING' then
+        next_max = math.max(math.min(value, math.floor(max / 2)), 1)
+    end
+end
+
+local event = string.format(
+    '{"sequence":%d,"schema":%d,"recorded_at":%s,"event":{"type":"ROLLED","player_id":"%s",'
+        .. '"min":%d,"max":%d,"value":%d,"nonce":%d,"rolled_at":%s}}',
+    version + 1, ARGV[5], ARGV[2], ARGV[1], min, max, value, nonce, ARGV[2])
+redis.call('RPUSH', KEYS[1], event)
+redis.call('EXPIRE', KEYS[1], 86400)
+redis.call('HSET', KEYS[3], 'version', version + 1, 'turn', seat % #seats + 1, 'min', next_min,
+    'max', next_max, 'nonce', nonce + 1)
+redis.call('HDEL', KEYS[4], turn[7])
+local timeout = tonumber(turn[10])
+if timeout > 0 then
+    redis.call('ZADD', KEYS[5], tonumber(ARGV[3]) + timeout, ARGV[4])
+else
+    redis.call('ZREM', KEYS[5], ARGV[4])
+end
+return {'ROLLED', snapshot, unpack(redis.call('LRANGE', KEYS[1], tonumber(turn[2]), -1))}
+"#;
+
+const DEADLINES_KEY: &str = "games:deadlines";
+
+/// Games with stakes in escrow, scored by when they were escrowed.
+const POTS_KEY: &str = "games:pots";
+
+/// A fast roll leaves the snapshot where it was; once this many events sit on top of it, a fresh one is saved.
+const SNAPSHOT_EVERY: usize = 16;
+
+fn game_key(game_id: GameId) -> String {
+    format!("game:{}", game_id)
+}
+
+fn events_key(game_id: GameId) -> String {
+    format!("game:{}:events", game_id)
+}
+
+fn turn_key(game_id: GameId) -> String {
+    format!("game:{}:turn", game_id)
+}
+
+fn draws_key(game_id: GameId) -> String {
+    format!("game:{}:draws", game_id)
+}
+
+/// KEYS and ARGV for `APPEND_EVENTS_SCRIPT`.
+fn append_args(game_id: GameId, events: &[RecordedEvent]) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let mut args = vec![events.first().map_or(0, |e| e.sequence).to_string()];
+    for recorded in events {
+        args.push(serde_json::to_string(recorded)?);
+    }
+    Ok((vec![events_key(game_id)], args))
+}
+
+fn append_reply(game_id: GameId, events: &[RecordedEvent], (status, stored): (String, u64)) -> Result<(), AppError> {
+    let first = events.first().map_or(0, |e| e.sequence);
+    match status.as_str() {
+        "OK" => Ok(()),
+        "CONFLICT" => Err(AppError::VersionConflict { game_id, version: first - 1 }),
+        _ => Err(stream_gap(game_id, stored, first)),
+    }
+}
+
+/// KEYS and ARGV for `SNAPSHOT_SCRIPT`.
+fn snapshot_args(game: &Game) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let game_id = game.get_id();
+    let keys = vec![
+        events_key(game_id),
+        game_key(game_id),
+        DEADLINES_KEY.to_string(),
+        turn_key(game_id),
+    ];
+    // Keep the deadline index in step with the game so the sweeper sees every running clock.
+    let deadline = game
+        .get_next_deadline()
+        .map(|d| d.timestamp_millis().to_string())
+        .unwrap_or_default();
+    let mut args = vec![
+        game.get_version().to_string(),
+        serde_json::to_string(game)?,
+        game_id.to_string(),
+        deadline,
+    ];
+    args.extend(scripted_turn(game)?);
+    Ok((keys, args))
+}
+
+/// The turn `ROLL_SCRIPT` plays from, as field/value pairs: the seats still in, whose turn it is
+/// (counting from 1), the range, the nonce and the rules. Only a game in progress with every player for
+/// themselves has one; team turns are left to `Game::roll`.
+fn scripted_turn(game: &Game) -> Result<Vec<String>, AppError> {
+    let seats = game.get_active_players();
+    let current = game
+        .get_current_player()
+        .and_then(|p| seats.iter().position(|seat| seat == p));
+    let (Some(current), GameStatus::InProgress, false) = (current, game.get_status(), game.is_team_game()) else {
+        return Ok(vec![]);
+    };
+    let settings = game.get_settings();
+    let rules = serde_json::to_value(settings.rules)?;
+    let (min, max) = game.get_roll_range();
+    let seats: Vec<String> = seats.iter().map(PlayerId::to_string).collect();
+    let fields = [
+        ("version", game.get_version().to_string()),
+        ("snapshot", game.get_version().to_string()),
+        ("seats", seats.join(",")),
+        ("turn", (current + 1).to_string()),
+        ("min", min.to_string()),
+        ("max", max.to_string()),
+        ("nonce", game.get_fairness().get_nonce().to_string()),
+        ("rules", rules["type"].as_str().unwrap_or_default().to_string()),
+        ("lose_at", rules["lose_at"].as_u64().unwrap_or_default().to_string()),
+        ("timeout_ms", (settings.turn_timeout_secs * 1000).to_string()),
+    ];
+    Ok(fields
+        .into_iter()
+        .flat_map(|(field, value)| [field.to_string(), value])
+        .collect())
+}
+
+/// KEYS and ARGV for `ROLL_SCRIPT`.
+fn roll_args(
+    game_id: GameId,
+    player_id: PlayerId,
+    rolled_at: DateTime<Utc>,
+) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let keys = vec![
+        events_key(game_id),
+        game_key(game_id),
+        turn_key(game_id),
+        draws_key(game_id),
+        DEADLINES_KEY.to_string(),
+    ];
+    let args = vec![
+        player_id.to_string(),
+        serde_json::to_string(&rolled_at)?,
+        rolled_at.timestamp_millis().to_string(),
+        game_id.to_string(),
+        EVENT_SCHEMA_VERSION.to_string(),
+    ];
+    Ok((keys, args))
+}
+
+/// Rebuild the game from what `ROLL_SCRIPT` handed back: the snapshot, then every event after it,
+/// ending with the roll. Also returns how many events the snapshot is behind by.
+fn roll_reply(game_id: GameId, reply: &[String]) -> Result<Option<(Game, Vec<GameEvent>, usize)>, AppError> {
+    match reply {
+        [status, snapshot, tail @ ..] if status == "ROLLED" => {
+            let mut game: Game = serde_json::from_str(snapshot)?;
+            let tail = tail.iter().map(|e| decode_event(e)).collect::<Result<Vec<_>, _>>()?;
+            game.catch_up(&tail);
+            let rolled = tail.last().map(|e| e.event.clone());
+            Ok(Some((game, rolled.into_iter().collect(), tail.len())))
+        }
+        [status] if status == "NOT_YOUR_TURN" => Err(GameError::NotYourTurn.into()),
+        [status] if status == "FALLBACK" => Ok(None),
+        _ => Err(AppError::Internal(format!(
+            "Unexpected roll script reply for game {}: {:?}",
+            game_id, reply
+        ))),
+    }
+}
+
+/// Field/value pairs for the draws hash: each nonce, with its two outputs joined by `:`.
+fn draw_fields(first_nonce: u64, draws: &[[u32; 2]]) -> Vec<(String, String)> {
+    (first_nonce..)
+        .zip(draws)
+        .map(|(nonce, [first, second])| (nonce.to_string(), format!("{}:{}", first, second)))
+        .collect()
+}
+
+/// Run `script` with the given KEYS and ARGV.
+async fn run_script<T: FromRedisValue>(
+    conn: &mut ConnectionManager,
+    script: &str,
+    (keys, args): (Vec<String>, Vec<String>),
+) -> Result<T, AppError> {
+    let script = redis::Script::new(script);
+    let mut invocation = script.prepare_invoke();
+    for key in keys {
+        invocation.key(key);
+    }
+    for arg in args {
+        invocation.arg(arg);
+    }
+    Ok(invocation.invoke_async(conn).await?)
+}
+
+fn stream_gap(game_id: GameId, stored: u64, first: u64) -> AppError {
+    AppError::Internal(format!(
+        "Event stream for game {} holds {} events; cannot append from sequence {}",
+        game_id, stored, first
+    ))
+}
+
+/// Decode a stored event, refusing any written by a newer schema than this server understands.
+pub(crate) fn decode_event(json: &str) -> Result<RecordedEvent, AppError> {
+    let recorded: RecordedEvent = serde_json::from_str(json)?;
+    if recorded.schema > EVENT_SCHEMA_VERSION {
+        return Err(AppError::Internal(format!(
+            "Event {} uses schema {}, newer than {}",
+            recorded.sequence, recorded.schema, EVENT_SCHEMA_VERSION
+        )));
+    }
+    Ok(recorded)
+}
+
+fn balance_key(player_id: PlayerId) -> String {
+    format!("wallet:{}:balance", player_id)
+}
+
+fn ledger_key(player_id: PlayerId) -> String {
+    format!("wallet:{}:ledger", player_id)
+}
+
+fn tournament_key(tournament_id: TournamentId) -> String {
+    format!("tournament:{}", tournament_id)
+}
+
+fn match_key(match_id: MatchId) -> String {
+    format!("match:{}", match_id)
+}
+
+/// KEYS and ARGV for `SAVE_VERSIONED_SCRIPT`: `next` is stored under `key` if `version` is still current.
+fn save_versioned_args(
+    key: String,
+    version: u64,
+    next: &impl Serialize,
+) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let args = vec![version.to_string(), serde_json::to_string(next)?];
+    Ok((vec![key.clone(), format!("{}:version", key)], args))
+}
+
+fn save_tournament_args(tournament: &Tournament) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let mut next = tournament.clone();
+    next.bump_version();
+    save_versioned_args(tournament_key(tournament.get_id()), tournament.get_version(), &next)
+}
+
+fn save_match_args(series: &Match) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let mut next = series.clone();
+    next.bump_version();
+    save_versioned_args(match_key(series.get_id()), series.get_version(), &next)
+}
+
+fn pot_key(game_id: GameId) -> String {
+    format!("game:{}:pot", game_id)
+}
+
+/// KEYS and ARGV for `ESCROW_SCRIPT`.
+fn escrow_args(
+    game_id: GameId,
+    players: &[PlayerId],
+    amount: u64,
+    escrowed_at: DateTime<Utc>,
+) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let mut keys = vec![pot_key(game_id), POTS_KEY.to_string()];
+    let mut args = vec![
+        amount.to_string(),
+        escrowed_at.timestamp_millis().to_string(),
+        game_id.to_string(),
+    ];
+    for player_id in players {
+        keys.extend([balance_key(*player_id), ledger_key(*player_id)]);
+        args.push(player_id.to_string());
+        args.push(serde_json::to_string(&LedgerEntry::escrow(
+            *player_id, game_id, amount,
+        ))?);
+    }
+    Ok((keys, args))
+}
+
+fn escrow_reply(game_id: GameId, players: &[PlayerId], (status, index): (String, usize)) -> Result<(), AppError> {
+    match status.as_str() {
+        "OK" => Ok(()),
+        "INSUFFICIENT_FUNDS" => Err(GameError::InsufficientFunds { player_id: players[index - 1] }.into()),
+        _ => Err(AppError::Internal(format!(
+            "Stakes for game {} are already escrowed",
+            game_id
+        ))),
+    }
+}
+
+/// KEYS and ARGV for `SETTLE_SCRIPT`.
+fn settle_args(game_id: GameId, winners: &[PlayerId]) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let mut keys = vec![pot_key(game_id), POTS_KEY.to_string()];
+    let mut args = vec![game_id.to_string()];
+    for winner_id in winners {
+        keys.extend([balance_key(*winner_id), ledger_key(*winner_id)]);
+        args.push(serde_json::to_string(&LedgerEntry::payout(*winner_id, game_id, 0))?);
+    }
+    Ok((keys, args))
+}
+
+/// KEYS and ARGV for `REFUND_SCRIPT`.
+fn refund_args(game_id: GameId, players: &[PlayerId]) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let mut keys = vec![pot_key(game_id), POTS_KEY.to_string()];
+    let mut args = vec![game_id.to_string()];
+    for player_id in players {
+        keys.extend([balance_key(*player_id), ledger_key(*player_id)]);
+        args.push(player_id.to_string());
+        args.push(serde_json::to_string(&LedgerEntry::refund(*player_id, game_id, 0))?);
+    }
+    Ok((keys, args))
+}
+
+fn leaderboard_key(kind: LeaderboardKind) -> String {
+    format!("leaderboard:{}", kind.as_str())
+}
+
+fn parse_player_id(id: &str) -> Result<PlayerId, AppError> {
+    id.parse()
+        .map_err(|e| AppError::Internal(format!("Bad player id {} on a leaderboard: {}", id, e)))
 }
 
 impl RedisRepository {
     pub fn new(redis_client: redis::Client) -> Self {
-        Self { redis_client }
+        Self { redis_client, connection: OnceCell::new() }
+    }
+
+    async fn connection(&self) -> Result<ConnectionManager, AppError> {
+        let connection = self
+            .connection
+            .get_or_try_init(|| ConnectionManager::new(self.redis_client.clone()))
+            .await?;
+        Ok(connection.clone())
     }
 }
 
 #[async_trait]
 impl GameRepository for RedisRepository {
     async fn load_game(&self, game_id: GameId) -> Result<Game, AppError> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = format!("game:{}", game_id);
-
-        let game_json: String = conn.get(&key).await.map_err(|e| {
-            if e.kind() == redis::ErrorKind::ResponseError && format!("{:?}", e).contains("nil") {
-                AppError::GameNotFound(game_id)
-            } else {
-                AppError::Redis(e)
+        let mut conn = self.connection().await?;
+        let game_json: Option<String> = conn.get(game_key(game_id)).await?;
+        match game_json {
+            Some(game_json) => {
+                let mut game: Game = serde_json::from_str(&game_json)?;
+                game.catch_up(&self.load_events(game_id, game.get_version()).await?);
+                Ok(game)
             }
-        })?;
-
-        let game: Game = serde_json::from_str(&game_json)?;
-        Ok(game)
+            None => Game::from_events(&self.load_events(game_id, 0).await?).ok_or(AppError::GameNotFound(game_id)),
+        }
     }
 
     async fn save_game(&self, game: &Game) -> Result<(), AppError> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = format!("game:{}", game.get_id());
-        let game_json = serde_json::to_string(game)?;
+        // The stream comes first: a snapshot is only a shortcut through it.
+        self.append_events(game.get_id(), game.get_uncommitted_events()).await?;
+
+        let mut conn = self.connection().await?;
+        run_script::<()>(&mut conn, SNAPSHOT_SCRIPT, snapshot_args(game)?).await
+    }
+
+    async fn append_events(&self, game_id: GameId, events: &[RecordedEvent]) -> Result<(), AppError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.connection().await?;
+        let reply = run_script(&mut conn, APPEND_EVENTS_SCRIPT, append_args(game_id, events)?).await?;
+        append_reply(game_id, events, reply)
+    }
+
+    async fn load_events(&self, game_id: GameId, after: u64) -> Result<Vec<RecordedEvent>, AppError> {
+        let mut conn = self.connection().await?;
+        // Sequences start at 1, so the event after `after` sits at index `after`.
+        let events: Vec<String> = conn.lrange(events_key(game_id), after as isize, -1).await?;
+        events.iter().map(|e| decode_event(e)).collect()
+    }
+
+    async fn roll(
+        &self,
+        game_id: GameId,
+        player_id: PlayerId,
+        rolled_at: DateTime<Utc>,
+    ) -> Result<Option<(Game, Vec<GameEvent>)>, AppError> {
+        let mut conn = self.connection().await?;
+        let reply: Vec<String> = run_script(&mut conn, ROLL_SCRIPT, roll_args(game_id, player_id, rolled_at)?).await?;
+        let Some((game, events, behind)) = roll_reply(game_id, &reply)? else {
+            return Ok(None);
+        };
+        // The roll is already recorded, so a snapshot that fails to save only makes the next load longer.
+        if behind >= SNAPSHOT_EVERY {
+            if let Err(e) = self.save_game(&game).await {
+                tracing::warn!(game_id = %game_id, "Failed to refresh snapshot: {}", e);
+            }
+        }
+        Ok(Some((game, events)))
+    }
+
+    async fn save_roll_draws(&self, game_id: GameId, first_nonce: u64, draws: &[[u32; 2]]) -> Result<(), AppError> {
+        if draws.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.connection().await?;
+        redis::pipe()
+            .atomic()
+            .hset_multiple(draws_key(game_id), &draw_fields(first_nonce, draws))
+            .expire(draws_key(game_id), 86400)
+            .query_async::<()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn save_server_seed(&self, game_id: GameId, server_seed: &ServerSeed) -> Result<(), AppError> {
+        let mut conn = self.connection().await?;
+        let key = format!("game:{}:server_seed", game_id);
+
+        conn.set_ex::<_, _, ()>(&key, server_seed.as_str(), 86400).await?;
+        Ok(())
+    }
+
+    async fn load_server_seed(&self, game_id: GameId) -> Result<ServerSeed, AppError> {
+        let mut conn = self.connection().await?;
+        let key = format!("game:{}:server_seed", game_id);
 
-        conn.set_ex::<_, _, ()>(&key, game_json, 86400).await?;
+        let seed: Option<String> = conn.get(&key).await?;
+        seed.map(ServerSeed::from).ok_or(AppError::GameNotFound(game_id))
+    }
+
+    async fn load_expired_deadlines(&self, now: DateTime<Utc>) -> Result<Vec<GameId>, AppError> {
+        let mut conn = self.connection().await?;
+        let ids: Vec<String> = conn
+            .zrangebyscore(DEADLINES_KEY, "-inf", now.timestamp_millis())
+            .await?;
+        ids.iter()
+            .map(|id| {
+                id.parse()
+                    .map_err(|e| AppError::Internal(format!("Bad game id {} in deadline index: {}", id, e)))
+            })
+            .collect()
+    }
+
+    async fn load_match(&self, match_id: MatchId) -> Result<Match, AppError> {
+        let mut conn = self.connection().await?;
+        let match_json: Option<String> = conn.get(match_key(match_id)).await?;
+        let match_json = match_json.ok_or(AppError::MatchNotFound(match_id))?;
+        Ok(serde_json::from_str(&match_json)?)
+    }
+
+    async fn save_match(&self, series: &mut Match) -> Result<(), AppError> {
+        let mut conn = self.connection().await?;
+        let saved: bool = run_script(&mut conn, SAVE_VERSIONED_SCRIPT, save_match_args(series)?).await?;
+        if !saved {
+            return Err(AppError::MatchConflict { match_id: series.get_id(), version: series.get_version() });
+        }
+        series.bump_version();
+        Ok(())
+    }
+
+    async fn load_tournament(&self, tournament_id: TournamentId) -> Result<Tournament, AppError> {
+        let mut conn = self.connection().await?;
+        let tournament_json: Option<String> = conn.get(tournament_key(tournament_id)).await?;
+        let tournament_json = tournament_json.ok_or(AppError::TournamentNotFound(tournament_id))?;
+        Ok(serde_json::from_str(&tournament_json)?)
+    }
+
+    async fn save_tournament(&self, tournament: &mut Tournament) -> Result<(), AppError> {
+        let mut conn = self.connection().await?;
+        let saved: bool = run_script(&mut conn, SAVE_VERSIONED_SCRIPT, save_tournament_args(tournament)?).await?;
+        if !saved {
+            return Err(AppError::TournamentConflict {
+                tournament_id: tournament.get_id(),
+                version: tournament.get_version(),
+            });
+        }
+        tournament.bump_version();
+        Ok(())
+    }
+
+    async fn get_balance(&self, player_id: PlayerId) -> Result<u64, AppError> {
+        let mut conn = self.connection().await?;
+        let balance: Option<u64> = conn.get(balance_key(player_id)).await?;
+        Ok(balance.unwrap_or(0))
+    }
+
+    async fn deposit(&self, player_id: PlayerId, amount: u64) -> Result<u64, AppError> {
+        let mut conn = self.connection().await?;
+        let entry = serde_json::to_string(&LedgerEntry::deposit(player_id, amount))?;
+
+        let (balance, _): (u64, u64) = redis::pipe()
+            .atomic()
+            .incr(balance_key(player_id), amount)
+            .rpush(ledger_key(player_id), entry)
+            .query_async(&mut conn)
+            .await?;
+        Ok(balance)
+    }
+
+    async fn escrow_stakes(&self, game_id: GameId, players: &[PlayerId], amount: u64) -> Result<(), AppError> {
+        let mut conn = self.connection().await?;
+        let args = escrow_args(game_id, players, amount, Utc::now())?;
+        escrow_reply(game_id, players, run_script(&mut conn, ESCROW_SCRIPT, args).await?)
+    }
+
+    async fn settle_pot(&self, game_id: GameId, winners: &[PlayerId]) -> Result<u64, AppError> {
+        let mut conn = self.connection().await?;
+        run_script(&mut conn, SETTLE_SCRIPT, settle_args(game_id, winners)?).await
+    }
+
+    async fn refund_pot(&self, game_id: GameId) -> Result<u64, AppError> {
+        let mut conn = self.connection().await?;
+        let staked: Vec<String> = conn.hkeys(pot_key(game_id)).await?;
+        let players = staked
+            .iter()
+            .map(|id| {
+                id.parse().map_err(|e| {
+                    AppError::Internal(format!("Bad player id {} in the pot of game {}: {}", id, game_id, e))
+                })
+            })
+            .collect::<Result<Vec<PlayerId>, _>>()?;
+        run_script(&mut conn, REFUND_SCRIPT, refund_args(game_id, &players)?).await
+    }
+
+    async fn load_open_pots(&self, before: DateTime<Utc>) -> Result<Vec<GameId>, AppError> {
+        let mut conn = self.connection().await?;
+        let ids: Vec<String> = conn.zrangebyscore(POTS_KEY, "-inf", before.timestamp_millis()).await?;
+        ids.iter()
+            .map(|id| {
+                id.parse()
+                    .map_err(|e| AppError::Internal(format!("Bad game id {} in pot index: {}", id, e)))
+            })
+            .collect()
+    }
+
+    async fn load_ledger(&self, player_id: PlayerId) -> Result<Vec<LedgerEntry>, AppError> {
+        let mut conn = self.connection().await?;
+        let entries: Vec<String> = conn.lrange(ledger_key(player_id), 0, -1).await?;
+        entries
+            .iter()
+            .map(|e| serde_json::from_str(e).map_err(AppError::from))
+            .collect()
+    }
+
+    async fn update_leaderboards(&self, updates: &[LeaderboardUpdate]) -> Result<(), AppError> {
+        let mut conn = self.connection().await?;
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for update in updates {
+            let key = leaderboard_key(update.kind);
+            match update.score {
+                Some(score) => pipe.zadd(key, update.player_id.to_string(), score).ignore(),
+                None => pipe.zrem(key, update.player_id.to_string()).ignore(),
+            };
+        }
+        pipe.query_async::<()>(&mut conn).await?;
         Ok(())
     }
+
+    async fn load_leaderboard(
+        &self,
+        kind: LeaderboardKind,
+        offset: u64,
+        limit: u64,
+    ) -> Result<LeaderboardPage, AppError> {
+        let mut conn = self.connection().await?;
+        let key = leaderboard_key(kind);
+        let total: u64 = conn.zcard(&key).await?;
+        if limit == 0 || offset >= total {
+            return Ok(LeaderboardPage { entries: vec![], total });
+        }
+        let stop = offset.saturating_add(limit - 1).min(total - 1);
+        let page: Vec<(String, f64)> = conn.zrevrange_withscores(&key, offset as isize, stop as isize).await?;
+
+        let entries = page
+            .into_iter()
+            .zip(offset + 1..)
+            .map(|((id, score), rank)| Ok(LeaderboardEntry { rank, player_id: parse_player_id(&id)?, score }))
+            .collect::<Result<_, AppError>>()?;
+        Ok(LeaderboardPage { entries, total })
+    }
+
+    async fn load_leaderboard_rank(
+        &self,
+        kind: LeaderboardKind,
+        player_id: PlayerId,
+    ) -> Result<Option<LeaderboardEntry>, AppError> {
+        let mut conn = self.connection().await?;
+        let key = leaderboard_key(kind);
+        let (rank, score): (Option<u64>, Option<f64>) = redis::pipe()
+            .zrevrank(&key, player_id.to_string())
+            .zscore(&key, player_id.to_string())
+            .query_async(&mut conn)
+            .await?;
+        Ok(rank
+            .zip(score)
+            .map(|(rank, score)| LeaderboardEntry { rank: rank + 1, player_id, score }))
+    }
 }
 
 // --- Mock Implementation (For Tests) ---
@@ -91,26 +1186,765 @@ impl GameRepository for RedisRepository {
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 
+#[derive(Default)]
+struct MockWallet {
+    balances: HashMap<PlayerId, u64>,
+    ledgers: HashMap<PlayerId, Vec<LedgerEntry>>,
+    pots: HashMap<GameId, MockPot>,
+}
+
+struct MockPot {
+    stakes: Vec<(PlayerId, u64)>,
+    escrowed_at: DateTime<Utc>,
+}
+
+/// Rolls the one prepared draw it holds, the way `ROLL_SCRIPT` does.
+struct PreparedRoller([u32; 2]);
+
+impl Roller for PreparedRoller {
+    fn roll_in_range(&mut self, max: u32) -> u32 {
+        sample_range(self.0, max)
+    }
+}
+
 pub struct MockGameRepository {
     storage: RwLock<HashMap<GameId, Game>>,
+    events: RwLock<HashMap<GameId, Vec<RecordedEvent>>>,
+    draws: RwLock<HashMap<GameId, BTreeMap<u64, [u32; 2]>>>,
+    server_seeds: RwLock<HashMap<GameId, ServerSeed>>,
+    matches: RwLock<HashMap<MatchId, Match>>,
+    tournaments: RwLock<HashMap<TournamentId, Tournament>>,
+    // One lock for all wallet state, so escrow and settlement are atomic.
+    wallet: RwLock<MockWallet>,
+    leaderboards: RwLock<HashMap<LeaderboardKind, HashMap<PlayerId, f64>>>,
 }
 
 impl MockGameRepository {
     pub fn new() -> Self {
-        Self { storage: RwLock::new(HashMap::new()) }
+        Self {
+            storage: RwLock::new(HashMap::new()),
+            events: RwLock::new(HashMap::new()),
+            draws: RwLock::new(HashMap::new()),
+            server_seeds: RwLock::new(HashMap::new()),
+            matches: RwLock::new(HashMap::new()),
+            tournaments: RwLock::new(HashMap::new()),
+            wallet: RwLock::new(MockWallet::default()),
+            leaderboards: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// A board best first, breaking ties the way a Redis sorted set read in reverse does.
+    async fn ranked(&self, kind: LeaderboardKind) -> Vec<(PlayerId, f64)> {
+        let boards = self.leaderboards.read().await;
+        let mut board: Vec<(PlayerId, f64)> = boards
+            .get(&kind)
+            .map(|board| board.iter().map(|(id, score)| (*id, *score)).collect())
+            .unwrap_or_default();
+        board.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.0.cmp(&a.0)));
+        board
+    }
+}
+
+impl Default for MockGameRepository {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait]
 impl GameRepository for MockGameRepository {
     async fn load_game(&self, game_id: GameId) -> Result<Game, AppError> {
-        let store = self.storage.read().await;
-        store.get(&game_id).cloned().ok_or(AppError::GameNotFound(game_id))
+        let snapshot = self.storage.read().await.get(&game_id).cloned();
+        match snapshot {
+            Some(mut game) => {
+                game.catch_up(&self.load_events(game_id, game.get_version()).await?);
+                Ok(game)
+            }
+            None => Game::from_events(&self.load_events(game_id, 0).await?).ok_or(AppError::GameNotFound(game_id)),
+        }
     }
 
     async fn save_game(&self, game: &Game) -> Result<(), AppError> {
+        // Held across the append, so the snapshot check sees the stream as this save left it.
         let mut store = self.storage.write().await;
-        store.insert(game.get_id(), game.clone());
+        self.append_events(game.get_id(), game.get_uncommitted_events()).await?;
+        if self.events.read().await.get(&game.get_id()).map_or(0, Vec::len) as u64 > game.get_version() {
+            return Ok(());
+        }
+        // Like a snapshot read back from Redis, the stored copy has nothing left to commit.
+        let mut snapshot = game.clone();
+        snapshot.take_uncommitted_events();
+        store.insert(game.get_id(), snapshot);
         Ok(())
     }
+
+    async fn append_events(&self, game_id: GameId, events: &[RecordedEvent]) -> Result<(), AppError> {
+        let mut streams = self.events.write().await;
+        let stream = streams.entry(game_id).or_default();
+        let stored = stream.len() as u64;
+        let Some(first) = events.first() else {
+            return Ok(());
+        };
+        if first.sequence > stored + 1 {
+            return Err(stream_gap(game_id, stored, first.sequence));
+        }
+        let mut overlap = events.iter().filter(|e| e.sequence <= stored);
+        if overlap.any(|e| stream[e.sequence as usize - 1] != *e) {
+            return Err(AppError::VersionConflict { game_id, version: first.sequence - 1 });
+        }
+        stream.extend(events.iter().filter(|e| e.sequence > stored).cloned());
+        Ok(())
+    }
+
+    async fn load_events(&self, game_id: GameId, after: u64) -> Result<Vec<RecordedEvent>, AppError> {
+        let streams = self.events.read().await;
+        let stream = streams.get(&game_id).map(Vec::as_slice).unwrap_or_default();
+        Ok(stream.iter().filter(|e| e.sequence > after).cloned().collect())
+    }
+
+    async fn roll(
+        &self,
+        game_id: GameId,
+        player_id: PlayerId,
+        rolled_at: DateTime<Utc>,
+    ) -> Result<Option<(Game, Vec<GameEvent>)>, AppError> {
+        // Like the Redis script, only a roll with its draw prepared that leaves the game going is taken.
+        let Ok(mut game) = self.load_game(game_id).await else {
+            return Ok(None);
+        };
+        let nonce = game.get_fairness().get_nonce();
+        let Some(draw) = self
+            .draws
+            .read()
+            .await
+            .get(&game_id)
+            .and_then(|draws| draws.get(&nonce).copied())
+        else {
+            return Ok(None);
+        };
+        let events = game.roll_at(player_id, &mut PreparedRoller(draw), rolled_at)?;
+        if game.is_finished() {
+            return Ok(None);
+        }
+        self.save_game(&game).await?;
+        game.take_uncommitted_events();
+        Ok(Some((game, events)))
+    }
+
+    async fn save_roll_draws(&self, game_id: GameId, first_nonce: u64, draws: &[[u32; 2]]) -> Result<(), AppError> {
+        let mut stored = self.draws.write().await;
+        stored
+            .entry(game_id)
+            .or_default()
+            .extend((first_nonce..).zip(draws.iter().copied()));
+        Ok(())
+    }
+
+    async fn save_server_seed(&self, game_id: GameId, server_seed: &ServerSeed) -> Result<(), AppError> {
+        self.server_seeds.write().await.insert(game_id, server_seed.clone());
+        Ok(())
+    }
+
+    async fn load_server_seed(&self, game_id: GameId) -> Result<ServerSeed, AppError> {
+        let seeds = self.server_seeds.read().await;
+        seeds.get(&game_id).cloned().ok_or(AppError::GameNotFound(game_id))
+    }
+
+    async fn load_expired_deadlines(&self, now: DateTime<Utc>) -> Result<Vec<GameId>, AppError> {
+        let store = self.storage.read().await;
+        Ok(store
+            .values()
+            .filter(|game| game.get_next_deadline().is_some_and(|deadline| deadline <= now))
+            .map(|game| game.get_id())
+            .collect())
+    }
+
+    async fn load_match(&self, match_id: MatchId) -> Result<Match, AppError> {
+        let matches = self.matches.read().await;
+        matches.get(&match_id).cloned().ok_or(AppError::MatchNotFound(match_id))
+    }
+
+    async fn save_match(&self, series: &mut Match) -> Result<(), AppError> {
+        let mut matches = self.matches.write().await;
+        let stored = matches.get(&series.get_id()).map_or(0, Match::get_version);
+        if stored != series.get_version() {
+            return Err(AppError::MatchConflict { match_id: series.get_id(), version: series.get_version() });
+        }
+        series.bump_version();
+        matches.insert(series.get_id(), series.clone());
+        Ok(())
+    }
+
+    async fn load_tournament(&self, tournament_id: TournamentId) -> Result<Tournament, AppError> {
+        let tournaments = self.tournaments.read().await;
+        tournaments
+            .get(&tournament_id)
+            .cloned()
+            .ok_or(AppError::TournamentNotFound(tournament_id))
+    }
+
+    async fn save_tournament(&self, tournament: &mut Tournament) -> Result<(), AppError> {
+        let mut tournaments = self.tournaments.write().await;
+        let stored = tournaments.get(&tournament.get_id()).map_or(0, Tournament::get_version);
+        if stored != tournament.get_version() {
+            return Err(AppError::TournamentConflict {
+                tournament_id: tournament.get_id(),
+                version: tournament.get_version(),
+            });
+        }
+        tournament.bump_version();
+        tournaments.insert(tournament.get_id(), tournament.clone());
+        Ok(())
+    }
+
+    async fn get_balance(&self, player_id: PlayerId) -> Result<u64, AppError> {
+        Ok(self.wallet.read().await.balances.get(&player_id).copied().unwrap_or(0))
+    }
+
+    async fn deposit(&self, player_id: PlayerId, amount: u64) -> Result<u64, AppError> {
+        let mut wallet = self.wallet.write().await;
+        let balance = wallet.balances.entry(player_id).or_default();
+        *balance += amount;
+        let balance = *balance;
+        wallet
+            .ledgers
+            .entry(player_id)
+            .or_default()
+            .push(LedgerEntry::deposit(player_id, amount));
+        Ok(balance)
+    }
+
+    async fn escrow_stakes(&self, game_id: GameId, players: &[PlayerId], amount: u64) -> Result<(), AppError> {
+        let mut wallet = self.wallet.write().await;
+        if let Some(pot) = wallet.pots.get(&game_id) {
+            let same_stakes = pot.stakes.len() == players.len()
+                && players
+                    .iter()
+                    .all(|player_id| pot.stakes.contains(&(*player_id, amount)));
+            if same_stakes {
+                return Ok(());
+            }
+            return Err(AppError::Internal(format!(
+                "Stakes for game {} are already escrowed",
+                game_id
+            )));
+        }
+        if let Some(player_id) = players
+            .iter()
+            .find(|p| wallet.balances.get(p).copied().unwrap_or(0) < amount)
+        {
+            return Err(GameError::InsufficientFunds { player_id: *player_id }.into());
+        }
+
+        for player_id in players {
+            *wallet.balances.entry(*player_id).or_default() -= amount;
+            wallet
+                .ledgers
+                .entry(*player_id)
+                .or_default()
+                .push(LedgerEntry::escrow(*player_id, game_id, amount));
+        }
+        let stakes = players.iter().map(|player_id| (*player_id, amount)).collect();
+        wallet.pots.insert(game_id, MockPot { stakes, escrowed_at: Utc::now() });
+        Ok(())
+    }
+
+    async fn settle_pot(&self, game_id: GameId, winners: &[PlayerId]) -> Result<u64, AppError> {
+        if winners.is_empty() {
+            return Err(AppError::Internal(format!(
+                "No winners to pay the pot of game {} to",
+                game_id
+            )));
+        }
+        let mut wallet = self.wallet.write().await;
+        let Some(pot) = wallet.pots.remove(&game_id) else {
+            return Ok(0);
+        };
+        let pot = pot.stakes.iter().map(|(_, stake)| stake).sum();
+
+        for (winner_id, share) in winners.iter().zip(split_pot(pot, winners.len())) {
+            *wallet.balances.entry(*winner_id).or_default() += share;
+            wallet
+                .ledgers
+                .entry(*winner_id)
+                .or_default()
+                .push(LedgerEntry::payout(*winner_id, game_id, share));
+        }
+        Ok(pot)
+    }
+
+    async fn refund_pot(&self, game_id: GameId) -> Result<u64, AppError> {
+        let mut wallet = self.wallet.write().await;
+        let Some(pot) = wallet.pots.remove(&game_id) else {
+            return Ok(0);
+        };
+
+        for (player_id, stake) in &pot.stakes {
+            *wallet.balances.entry(*player_id).or_default() += stake;
+            wallet
+                .ledgers
+                .entry(*player_id)
+                .or_default()
+                .push(LedgerEntry::refund(*player_id, game_id, *stake));
+        }
+        Ok(pot.stakes.iter().map(|(_, stake)| stake).sum())
+    }
+
+    async fn load_open_pots(&self, before: DateTime<Utc>) -> Result<Vec<GameId>, AppError> {
+        let wallet = self.wallet.read().await;
+        Ok(wallet
+            .pots
+            .iter()
+            .filter(|(_, pot)| pot.escrowed_at <= before)
+            .map(|(game_id, _)| *game_id)
+            .collect())
+    }
+
+    async fn load_ledger(&self, player_id: PlayerId) -> Result<Vec<LedgerEntry>, AppError> {
+        Ok(self
+            .wallet
+            .read()
+            .await
+            .ledgers
+            .get(&player_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn update_leaderboards(&self, updates: &[LeaderboardUpdate]) -> Result<(), AppError> {
+        let mut boards = self.leaderboards.write().await;
+        for update in updates {
+            let board = boards.entry(update.kind).or_default();
+            match update.score {
+                Some(score) => board.insert(update.player_id, score),
+                None => board.remove(&update.player_id),
+            };
+        }
+        Ok(())
+    }
+
+    async fn load_leaderboard(
+        &self,
+        kind: LeaderboardKind,
+        offset: u64,
+        limit: u64,
+    ) -> Result<LeaderboardPage, AppError> {
+        let board = self.ranked(kind).await;
+        let entries = board
+            .iter()
+            .zip(1..)
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|((player_id, score), rank)| LeaderboardEntry { rank, player_id: *player_id, score: *score })
+            .collect();
+        Ok(LeaderboardPage { entries, total: board.len() as u64 })
+    }
+
+    async fn load_leaderboard_rank(
+        &self,
+        kind: LeaderboardKind,
+        player_id: PlayerId,
+    ) -> Result<Option<LeaderboardEntry>, AppError> {
+        let board = self.ranked(kind).await;
+        Ok(board
+            .iter()
+            .zip(1..)
+            .find(|((id, _), _)| *id == player_id)
+            .map(|((_, score), rank)| LeaderboardEntry { rank, player_id, score: *score }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        fairness::{roll_draws, FairRoller},
+        roller::sample_range,
+        rules::Threshold,
+        GameSettings,
+    };
+    use mlua::{Lua, Table};
+
+    // Just enough of Redis for the game scripts, with `false` standing in for nil replies as in Redis.
+    const FAKE_REDIS: &str = r#"
+local strings, lists, hashes, zsets = {}, {}, {}, {}
+redis = {}
+function redis.call(command, key, ...)
+    local args = {...}
+    if command == 'GET' then
+        return strings[key] or false
+    elseif command == 'SET' then
+        strings[key] = args[1]
+        return 'OK'
+    elseif command == 'DEL' then
+        strings[key], lists[key], hashes[key] = nil, nil, nil
+        return 1
+    elseif command == 'EXPIRE' then
+        return 1
+    elseif command == 'LLEN' then
+        return #(lists[key] or {})
+    elseif command == 'LINDEX' then
+        return (lists[key] or {})[tonumber(args[1]) + 1] or false
+    elseif command == 'LRANGE' then
+        local list, range = lists[key] or {}, {}
+        local stop = tonumber(args[2])
+        if stop < 0 then
+            stop = #list + stop
+        end
+        for i = tonumber(args[1]) + 1, stop + 1 do
+            range[#range + 1] = list[i]
+        end
+        return range
+    elseif command == 'RPUSH' then
+        lists[key] = lists[key] or {}
+        table.insert(lists[key], args[1])
+        return #lists[key]
+    elseif command == 'HGET' then
+        return (hashes[key] or {})[args[1]] or false
+    elseif command == 'HMGET' then
+        local hash, values = hashes[key] or {}, {}
+        for i, field in ipairs(args) do
+            values[i] = hash[field] or false
+        end
+        return values
+    elseif command == 'HSET' then
+        hashes[key] = hashes[key] or {}
+        for i = 1, #args, 2 do
+            hashes[key][args[i]] = tostring(args[i + 1])
+        end
+        return 1
+    elseif command == 'HDEL' then
+        (hashes[key] or {})[args[1]] = nil
+        return 1
+    elseif command == 'ZADD' then
+        zsets[key] = zsets[key] or {}
+        zsets[key][args[2]] = tonumber(args[1])
+        return 1
+    elseif command == 'ZREM' then
+        (zsets[key] or {})[args[1]] = nil
+        return 1
+    elseif command == 'ZSCORE' then
+        return (zsets[key] or {})[args[1]] or false
+    end
+    error('unsupported command ' .. command)
+end
+"#;
+
+    fn fake_redis() -> Lua {
+        let lua = Lua::new();
+        lua.load(FAKE_REDIS).exec().unwrap();
+        lua
+    }
+
+    /// Run a script as Redis would, flattening its reply to strings.
+    fn run(lua: &Lua, script: &str, (keys, args): (Vec<String>, Vec<String>)) -> Vec<String> {
+        lua.globals().set("KEYS", keys).unwrap();
+        lua.globals().set("ARGV", args).unwrap();
+        match lua.load(script).eval::<mlua::Value>().unwrap() {
+            mlua::Value::Table(reply) => reply.sequence_values::<String>().map(Result::unwrap).collect(),
+            other => vec![lua.coerce_string(other).unwrap().unwrap().to_str().unwrap().to_string()],
+        }
+    }
+
+    fn call<'lua>(lua: &'lua Lua, command: &str, args: &[String]) -> mlua::Value<'lua> {
+        let redis: Table = lua.globals().get("redis").unwrap();
+        let call: mlua::Function = redis.get("call").unwrap();
+        let mut call_args = vec![command.to_string()];
+        call_args.extend_from_slice(args);
+        call.call(mlua::MultiValue::from_vec(
+            call_args
+                .into_iter()
+                .map(|a| mlua::Value::String(lua.create_string(&a).unwrap()))
+                .collect(),
+        ))
+        .unwrap()
+    }
+
+    /// Save the game through the production scripts, then store its next draws as `roll_for` does.
+    fn save(lua: &Lua, game: &mut Game, server_seed: &ServerSeed) {
+        let events = game.take_uncommitted_events();
+        let reply = run(lua, APPEND_EVENTS_SCRIPT, append_args(game.get_id(), &events).unwrap());
+        append_reply(game.get_id(), &events, (reply[0].clone(), reply[1].parse().unwrap())).unwrap();
+        run(lua, SNAPSHOT_SCRIPT, snapshot_args(game).unwrap());
+
+        let fairness = game.get_fairness();
+        let first_nonce = fairness.get_nonce();
+        let draws: Vec<[u32; 2]> = (first_nonce..first_nonce + 64)
+            .map(|nonce| roll_draws(server_seed, fairness.get_client_seeds(), nonce))
+            .collect();
+        let mut args = vec![draws_key(game.get_id())];
+        args.extend(
+            draw_fields(first_nonce, &draws)
+                .into_iter()
+                .flat_map(|(nonce, draw)| [nonce, draw]),
+        );
+        call(lua, "HSET", &args);
+    }
+
+    #[test]
+    fn test_roll_script_matches_the_domain() {
+        let threshold = RuleSet::Threshold(Threshold { lose_at: 50 });
+        for rules in [RuleSet::Classic, threshold, RuleSet::Reverse, RuleSet::Halving] {
+            let lua = fake_redis();
+            let server_seed = ServerSeed::generate();
+            let host_id = PlayerId::new();
+            let settings = GameSettings { max_players: 3, rules, turn_timeout_secs: 30, ..Default::default() };
+            let mut game = Game::with_settings(host_id, settings);
+            game.commit_server_seed(server_seed.commitment()).unwrap();
+            for _ in 0..2 {
+                let guest_id = PlayerId::new();
+                game.join(guest_id).unwrap();
+                game.set_client_seed(guest_id, guest_id.to_string()).unwrap();
+                game.set_ready(guest_id, true).unwrap();
+            }
+            game.start(host_id, &mut FairRoller::new(server_seed.clone(), game.get_fairness()))
+                .unwrap();
+            save(&lua, &mut game, &server_seed);
+
+            let mut scripted = 0;
+            while !game.is_finished() {
+                let player_id = *game.get_current_player().unwrap();
+                let rolled_at = Utc::now();
+                let mut reference = game.clone();
+                let mut roller = FairRoller::new(server_seed.clone(), reference.get_fairness());
+                let expected = reference.roll_at(player_id, &mut roller, rolled_at).unwrap();
+
+                let other_id = game.get_active_players().into_iter().find(|p| *p != player_id).unwrap();
+                let reply = run(
+                    &lua,
+                    ROLL_SCRIPT,
+                    roll_args(game.get_id(), other_id, rolled_at).unwrap(),
+                );
+                assert!(matches!(
+                    roll_reply(game.get_id(), &reply),
+                    Err(AppError::Game(GameError::NotYourTurn))
+                ));
+
+                let reply = run(
+                    &lua,
+                    ROLL_SCRIPT,
+                    roll_args(game.get_id(), player_id, rolled_at).unwrap(),
+                );
+                match roll_reply(game.get_id(), &reply).unwrap() {
+                    Some((rolled, events, _)) => {
+                        assert_eq!(events, expected, "{:?}", rules);
+                        assert_eq!(rolled, reference, "{:?}", rules);
+                        let deadline = call(&lua, "ZSCORE", &[DEADLINES_KEY.into(), game.get_id().to_string()]);
+                        let expected_deadline = reference.get_next_deadline().unwrap().timestamp_millis();
+                        assert_eq!(lua.coerce_number(deadline).unwrap(), Some(expected_deadline as f64));
+                        scripted += 1;
+                        game = rolled;
+                    }
+                    // Only a losing roll is handed back to the server.
+                    None => {
+                        assert!(expected.len() > 1, "{:?} fell back on a surviving roll", rules);
+                        game = reference;
+                        save(&lua, &mut game, &server_seed);
+                    }
+                }
+            }
+            assert!(scripted > 0, "{:?}", rules);
+        }
+    }
+
+    #[test]
+    fn test_roll_script_samples_like_the_roller() {
+        // Draws whose first output lands in the biased tail of a 1000-wide range, where the second decides.
+        let tail = (0..=u32::MAX)
+            .rev()
+            .find(|x| (*x as u64 * 1000) as u32 > u32::MAX - 1000 + 1)
+            .unwrap();
+        let draws = [
+            [0, 0],
+            [u32::MAX, u32::MAX],
+            [tail, 0],
+            [tail, u32::MAX],
+            [123_456_789, 42],
+        ];
+        for draw in draws {
+            let lua = fake_redis();
+            let server_seed = ServerSeed::generate();
+            let host_id = PlayerId::new();
+            let mut game = Game::new(host_id);
+            let guest_id = PlayerId::new();
+            game.join(guest_id).unwrap();
+            game.set_ready(guest_id, true).unwrap();
+            game.start(host_id, &mut FairRoller::new(server_seed.clone(), game.get_fairness()))
+                .unwrap();
+            save(&lua, &mut game, &server_seed);
+            let nonce = game.get_fairness().get_nonce();
+            let mut args = vec![draws_key(game.get_id())];
+            args.extend(
+                draw_fields(nonce, &[draw])
+                    .into_iter()
+                    .flat_map(|(nonce, draw)| [nonce, draw]),
+            );
+            call(&lua, "HSET", &args);
+
+            let expected = sample_range(draw, game.get_current_max());
+            let reply = run(
+                &lua,
+                ROLL_SCRIPT,
+                roll_args(game.get_id(), host_id, Utc::now()).unwrap(),
+            );
+            match roll_reply(game.get_id(), &reply).unwrap() {
+                Some((_, events, _)) => {
+                    assert!(
+                        matches!(&events[..], [GameEvent::Rolled(roll)] if roll.value == expected),
+                        "{:?}",
+                        draw
+                    )
+                }
+                None => assert_eq!(expected, 1, "{:?}", draw),
+            }
+        }
+    }
+
+    #[test]
+    fn test_roll_script_leaves_a_stale_turn_to_the_server() {
+        let lua = fake_redis();
+        let server_seed = ServerSeed::generate();
+        let host_id = PlayerId::new();
+        let mut game = Game::new(host_id);
+        let guest_id = PlayerId::new();
+        game.join(guest_id).unwrap();
+        game.set_ready(guest_id, true).unwrap();
+        game.start(host_id, &mut FairRoller::new(server_seed.clone(), game.get_fairness()))
+            .unwrap();
+        save(&lua, &mut game, &server_seed);
+
+        // Another request appends an event without its snapshot having landed yet.
+        game.pause_game(guest_id).unwrap();
+        let events = game.take_uncommitted_events();
+        run(&lua, APPEND_EVENTS_SCRIPT, append_args(game.get_id(), &events).unwrap());
+
+        let reply = run(
+            &lua,
+            ROLL_SCRIPT,
+            roll_args(game.get_id(), host_id, Utc::now()).unwrap(),
+        );
+        assert_eq!(reply, vec!["FALLBACK".to_string()]);
+    }
+
+    fn tournament() -> Tournament {
+        let settings = GameSettings { max_players: 2, ..Default::default() };
+        Tournament::new(PlayerId::new(), 4, Seeding::Random, settings).unwrap()
+    }
+
+    #[test]
+    fn test_save_tournament_script_rejects_stale_copies() {
+        let lua = fake_redis();
+        let mut tournament = tournament();
+        let stale = tournament.clone();
+
+        let reply = run(&lua, SAVE_VERSIONED_SCRIPT, save_tournament_args(&tournament).unwrap());
+        assert_eq!(reply, vec!["1"]);
+        tournament.bump_version();
+        let reply = run(&lua, SAVE_VERSIONED_SCRIPT, save_tournament_args(&stale).unwrap());
+        assert_eq!(reply, vec!["0"]);
+        let reply = run(&lua, SAVE_VERSIONED_SCRIPT, save_tournament_args(&tournament).unwrap());
+        assert_eq!(reply, vec!["1"]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_save_tournament_rejects_stale_copies() {
+        let repository = MockGameRepository::new();
+        let mut tournament = tournament();
+        repository.save_tournament(&mut tournament).await.unwrap();
+
+        let mut first = repository.load_tournament(tournament.get_id()).await.unwrap();
+        let mut second = first.clone();
+        first.register(PlayerId::new(), None).unwrap();
+        second.register(PlayerId::new(), None).unwrap();
+        repository.save_tournament(&mut first).await.unwrap();
+        assert!(matches!(
+            repository.save_tournament(&mut second).await,
+            Err(AppError::TournamentConflict { version: 1, .. })
+        ));
+        assert_eq!(repository.load_tournament(tournament.get_id()).await.unwrap(), first);
+    }
+
+    #[test]
+    fn test_save_match_script_rejects_stale_copies() {
+        let lua = fake_redis();
+        let settings = GameSettings::default();
+        let mut series = Match::new(vec![PlayerId::new(), PlayerId::new()], 3, settings).unwrap();
+        let stale = series.clone();
+
+        let reply = run(&lua, SAVE_VERSIONED_SCRIPT, save_match_args(&series).unwrap());
+        assert_eq!(reply, vec!["1"]);
+        series.bump_version();
+        let reply = run(&lua, SAVE_VERSIONED_SCRIPT, save_match_args(&stale).unwrap());
+        assert_eq!(reply, vec!["0"]);
+        let reply = run(&lua, SAVE_VERSIONED_SCRIPT, save_match_args(&series).unwrap());
+        assert_eq!(reply, vec!["1"]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_save_match_rejects_stale_copies() {
+        let repository = MockGameRepository::new();
+        let mut series = Match::new(vec![PlayerId::new(), PlayerId::new()], 3, GameSettings::default()).unwrap();
+        repository.save_match(&mut series).await.unwrap();
+
+        let mut first = repository.load_match(series.get_id()).await.unwrap();
+        let mut second = first.clone();
+        first.add_game(GameId::new()).unwrap();
+        second.add_game(GameId::new()).unwrap();
+        repository.save_match(&mut first).await.unwrap();
+        assert!(matches!(
+            repository.save_match(&mut second).await,
+            Err(AppError::MatchConflict { version: 1, .. })
+        ));
+        assert_eq!(repository.load_match(series.get_id()).await.unwrap(), first);
+    }
+
+    #[tokio::test]
+    async fn test_mock_leaderboards_page_and_rank() {
+        let repository = MockGameRepository::new();
+        let mut players: Vec<PlayerId> = (0..4).map(|_| PlayerId::new()).collect();
+        players.sort();
+        let scores = [3.0, 5.0, 3.0, 1.0];
+        let updates: Vec<LeaderboardUpdate> = players
+            .iter()
+            .zip(scores)
+            .map(|(player_id, score)| LeaderboardUpdate {
+                kind: LeaderboardKind::Wins,
+                player_id: *player_id,
+                score: Some(score),
+            })
+            .collect();
+        repository.update_leaderboards(&updates).await.unwrap();
+
+        // Ties go to the higher player id, as in a Redis sorted set read in reverse.
+        let page = repository.load_leaderboard(LeaderboardKind::Wins, 1, 2).await.unwrap();
+        assert_eq!(page.total, 4);
+        let ranked: Vec<(u64, PlayerId)> = page.entries.iter().map(|e| (e.rank, e.player_id)).collect();
+        assert_eq!(ranked, vec![(2, players[2]), (3, players[0])]);
+
+        let rank = repository
+            .load_leaderboard_rank(LeaderboardKind::Wins, players[3])
+            .await
+            .unwrap();
+        assert_eq!(
+            rank,
+            Some(LeaderboardEntry { rank: 4, player_id: players[3], score: 1.0 })
+        );
+
+        let removal = LeaderboardUpdate { kind: LeaderboardKind::Wins, player_id: players[1], score: None };
+        repository.update_leaderboards(&[removal]).await.unwrap();
+        assert_eq!(
+            repository
+                .load_leaderboard_rank(LeaderboardKind::Wins, players[1])
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            repository
+                .load_leaderboard(LeaderboardKind::Streak, 0, 10)
+                .await
+                .unwrap(),
+            Default::default()
+        );
+    }
 }
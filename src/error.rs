@@ -1,4 +1,4 @@
-use crate::game::types::{GameError, GameId};
+use crate::game::types::{GameError, GameId, MatchId, PlayerId, TournamentId};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -12,15 +12,45 @@ pub enum AppError {
     #[error("Redis error: {0}")]
     Redis(#[from] redis::RedisError),
 
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
     #[error("Serialization error: {0}")]
     Serde(#[from] serde_json::Error),
 
     #[error("Game with ID {0} not found")]
     GameNotFound(GameId),
 
+    #[error("Match with ID {0} not found")]
+    MatchNotFound(MatchId),
+
+    #[error("Tournament with ID {0} not found")]
+    TournamentNotFound(TournamentId),
+
+    #[error("Player with ID {0} not found")]
+    PlayerNotFound(PlayerId),
+
+    #[error("No player is called {0}")]
+    PlayerNameNotFound(String),
+
     #[error("Game logic violation: {0}")]
     Game(#[from] GameError),
 
+    #[error("Game {game_id} was changed by another request at version {version}")]
+    VersionConflict { game_id: GameId, version: u64 },
+
+    #[error("Tournament {tournament_id} was changed by another request at version {version}")]
+    TournamentConflict { tournament_id: TournamentId, version: u64 },
+
+    #[error("Match {match_id} was changed by another request at version {version}")]
+    MatchConflict { match_id: MatchId, version: u64 },
+
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(String),
+
+    #[error("Profile conflict: {0}")]
+    ProfileConflict(String),
+
     #[error("Access denied: {0}")]
     Forbidden(String),
 
@@ -38,6 +68,13 @@ impl IntoResponse for AppError {
                     "An internal database error occurred".to_string(),
                 )
             }
+            AppError::Database(e) => {
+                tracing::error!("Database error: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "An internal database error occurred".to_string(),
+                )
+            }
             AppError::Serde(e) => {
                 tracing::error!("Serialization error: {}", e);
                 (
@@ -46,10 +83,37 @@ impl IntoResponse for AppError {
                 )
             }
             AppError::GameNotFound(id) => (StatusCode::NOT_FOUND, format!("Game with id {} not found", id)),
+            AppError::MatchNotFound(id) => (StatusCode::NOT_FOUND, format!("Match with id {} not found", id)),
+            AppError::TournamentNotFound(id) => (StatusCode::NOT_FOUND, format!("Tournament with id {} not found", id)),
+            AppError::PlayerNotFound(id) => (StatusCode::NOT_FOUND, format!("Player with id {} not found", id)),
+            AppError::PlayerNameNotFound(name) => (StatusCode::NOT_FOUND, format!("No player is called {}", name)),
             AppError::Game(e) => {
                 tracing::warn!("Game logic violation: {}", e);
                 (StatusCode::BAD_REQUEST, format!("Game rule violation: {}", e))
             }
+            AppError::VersionConflict { game_id, version } => {
+                tracing::warn!(game_id = %game_id, version = version, "Version conflict");
+                (
+                    StatusCode::CONFLICT,
+                    "The game changed while your request was being handled; please try again".to_string(),
+                )
+            }
+            AppError::TournamentConflict { tournament_id, version } => {
+                tracing::warn!(tournament_id = %tournament_id, version = version, "Version conflict");
+                (
+                    StatusCode::CONFLICT,
+                    "The tournament changed while your request was being handled; please try again".to_string(),
+                )
+            }
+            AppError::MatchConflict { match_id, version } => {
+                tracing::warn!(match_id = %match_id, version = version, "Version conflict");
+                (
+                    StatusCode::CONFLICT,
+                    "The match changed while your request was being handled; please try again".to_string(),
+                )
+            }
+            AppError::InvalidProfile(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::ProfileConflict(msg) => (StatusCode::CONFLICT, msg),
             AppError::Forbidden(msg) => {
                 tracing::warn!("Access denied: {}", msg);
                 (StatusCode::FORBIDDEN, msg)
@@ -118,6 +182,19 @@ mod tests {
         assert_eq!(status, StatusCode::BAD_REQUEST);
         assert!(message.contains("The game is full"));
     }
+
+    #[tokio::test]
+    async fn test_version_conflict_response() {
+        let test_game_id = GameIdTestExt::from_uuid(Uuid::new_v4());
+        let error = AppError::VersionConflict { game_id: test_game_id, version: 7 };
+
+        let response = error.into_response();
+        let (status, message) = check_response(response).await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(message.contains("try again"));
+    }
+
     #[tokio::test]
     async fn test_internal_server_error_response() {
         let error = AppError::Internal("Something blew up".to_string());
@@ -1,27 +1,121 @@
-use crate::game::types::GameEvent;
+use std::collections::{BTreeMap, BTreeSet};
 
+use chrono::{DateTime, Duration, Utc};
+
+use crate::game::types::{GameEvent, RecordedEvent, EVENT_SCHEMA_VERSION};
+
+use super::fairness::{Fairness, FairnessReport, ServerSeed};
 use super::roller::Roller;
-use super::types::{GameError, GameId, GameStatus, PlayerId};
+use super::settings::{GameSettings, TurnOrder, MIN_PLAYERS};
+use super::types::{
+    GameCommand, GameError, GameId, GameStatus, MatchId, PlayerAction, PlayerId, RollRecord, Team, TeamId,
+    TournamentId, TurnOrderOutcome,
+};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Game {
     id: GameId,
+    // Hosting passes down the seats if the host leaves the lobby.
+    host_id: PlayerId,
+    created_by: PlayerId,
+    // Seating order; the game starts with the first seat.
     players: Vec<PlayerId>,
+    // Lobby players who have marked themselves ready.
+    ready: BTreeSet<PlayerId>,
+    // Players the host removed from the lobby; they cannot join again.
+    kicked: BTreeSet<PlayerId>,
+    // Each player's team in a team game; empty when everyone plays for themselves.
+    teams: BTreeMap<PlayerId, TeamId>,
+    // Players who rolled a 1, in the order they went out.
+    eliminated: Vec<PlayerId>,
+    settings: GameSettings,
+    current_min: u32,
     current_max: u32,
     turn_index: usize,
     status: GameStatus,
+    fairness: Fairness,
+    history: Vec<RollRecord>,
+    // How the seating was settled when the game started.
+    turn_order: Option<TurnOrderOutcome>,
+    // When the current player runs out of time; `None` while the clock is stopped.
+    turn_deadline: Option<DateTime<Utc>>,
+    // When a disconnected player forfeits if they have not come back.
+    reconnect_deadline: Option<DateTime<Utc>>,
+    // Set when the game is one leg of a best-of-N series.
+    match_id: Option<MatchId>,
+    // Set when the game is a pairing in a tournament bracket.
+    tournament_id: Option<TournamentId>,
+    // How many people are watching right now. The connections are the record of that, not the
+    // event stream, so it is filled in just before the state goes out and never read back.
+    #[serde(skip_deserializing)]
+    spectators: usize,
+    // Players who asked to play again once the game ended.
+    rematch_votes: BTreeSet<PlayerId>,
+    // The game this one is a rematch of, and the rematch that followed it.
+    rematch_of: Option<GameId>,
+    rematch_id: Option<GameId>,
+    // Every accepted player action, in order; see `game::replay`.
+    actions: Vec<PlayerAction>,
+    // How many events have been folded into this state.
+    version: u64,
+    #[serde(skip)]
+    uncommitted: Uncommitted,
 }
 
 impl Game {
     #[tracing::instrument]
     pub fn new(host_id: PlayerId) -> Self {
+        Self::with_settings(host_id, GameSettings::default())
+    }
+
+    #[tracing::instrument]
+    pub fn with_settings(host_id: PlayerId, settings: GameSettings) -> Self {
+        Self::restore(GameId::new(), host_id, settings)
+    }
+
+    /// Recreate a game's opening state under a known id.
+    pub(crate) fn restore(id: GameId, host_id: PlayerId, settings: GameSettings) -> Self {
+        let mut game = Self::opening(id, host_id, settings.clone());
+        game.record(GameEvent::Created { game_id: id, host_id, settings });
+        game
+    }
+
+    /// The state before any event, including `Created`, has been applied.
+    fn opening(id: GameId, host_id: PlayerId, settings: GameSettings) -> Self {
+        let (current_min, current_max) = settings.rules.rules().opening_range(settings.starting_max);
+        let mut teams = BTreeMap::new();
+        if settings.teams > 0 {
+            teams.insert(host_id, TeamId(0));
+        }
         Self {
-            id: GameId::new(),
+            id,
+            host_id,
+            created_by: host_id,
             players: vec![host_id],
-            current_max: 1000, // TODO: make configurable
-            turn_index: 0,     // TODO: is there a better way to handle this?
+            ready: BTreeSet::new(),
+            kicked: BTreeSet::new(),
+            teams,
+            eliminated: vec![],
+            current_min,
+            current_max,
+            settings,
+            turn_index: 0,
             status: GameStatus::WaitingForPlayers,
+            fairness: Fairness::default(),
+            history: vec![],
+            turn_order: None,
+            turn_deadline: None,
+            reconnect_deadline: None,
+            match_id: None,
+            tournament_id: None,
+            spectators: 0,
+            rematch_votes: BTreeSet::new(),
+            rematch_of: None,
+            rematch_id: None,
+            actions: vec![],
+            version: 0,
+            uncommitted: Uncommitted::default(),
         }
     }
 
@@ -38,110 +132,989 @@ impl Game {
         self.current_max
     }
 
+    pub fn get_starting_max(&self) -> u32 {
+        self.settings.starting_max
+    }
+
+    /// The inclusive range the current player will roll in.
+    pub fn get_roll_range(&self) -> (u32, u32) {
+        (self.current_min, self.current_max)
+    }
+
     pub fn get_players(&self) -> &[PlayerId] {
         &self.players
     }
 
+    pub fn get_settings(&self) -> &GameSettings {
+        &self.settings
+    }
+
+    pub fn get_max_players(&self) -> usize {
+        self.settings.max_players
+    }
+
+    pub fn is_ready(&self, player_id: PlayerId) -> bool {
+        self.ready.contains(&player_id)
+    }
+
+    /// Whether every player besides the host is ready; the host's start counts as their ready.
+    pub fn all_ready(&self) -> bool {
+        self.players
+            .iter()
+            .filter(|p| **p != self.host_id)
+            .all(|p| self.ready.contains(p))
+    }
+
+    pub fn is_team_game(&self) -> bool {
+        self.settings.teams > 0
+    }
+
+    pub fn get_teams(&self) -> &BTreeMap<PlayerId, TeamId> {
+        &self.teams
+    }
+
+    pub fn get_team(&self, player_id: PlayerId) -> Option<TeamId> {
+        self.teams.get(&player_id).copied()
+    }
+
+    /// A team's players, in seating order.
+    pub fn get_team_members(&self, team_id: TeamId) -> Vec<PlayerId> {
+        self.players
+            .iter()
+            .filter(|p| self.teams.get(p) == Some(&team_id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_eliminated(&self) -> &[PlayerId] {
+        &self.eliminated
+    }
+
+    /// Players who have not been eliminated yet, in turn order.
+    pub fn get_active_players(&self) -> Vec<PlayerId> {
+        self.players
+            .iter()
+            .filter(|p| !self.eliminated.contains(p))
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_eliminated(&self, player_id: PlayerId) -> bool {
+        self.eliminated.contains(&player_id)
+    }
+
     pub fn get_current_player(&self) -> Option<&PlayerId> {
         self.players.get(self.turn_index)
     }
 
+    pub fn get_fairness(&self) -> &Fairness {
+        &self.fairness
+    }
+
+    /// Every roll made so far, oldest first.
+    pub fn get_history(&self) -> &[RollRecord] {
+        &self.history
+    }
+
+    pub fn get_turn_order(&self) -> Option<&TurnOrderOutcome> {
+        self.turn_order.as_ref()
+    }
+
+    /// Recompute the seating draws and the roll history from the revealed server seed.
+    pub fn verify_rolls(&self) -> Result<FairnessReport, GameError> {
+        let opening = self.turn_order.iter().flat_map(|outcome| outcome.rolls.iter().cloned());
+        let rolls: Vec<RollRecord> = opening.chain(self.history.iter().cloned()).collect();
+        self.fairness.verify(&rolls)
+    }
+
+    pub fn get_turn_deadline(&self) -> Option<DateTime<Utc>> {
+        self.turn_deadline
+    }
+
+    /// Time left on the current turn, or `None` if the clock is stopped.
+    pub fn get_turn_time_remaining(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.turn_deadline
+            .map(|deadline| (deadline - now).max(Duration::zero()))
+    }
+
+    pub fn is_turn_expired(&self, now: DateTime<Utc>) -> bool {
+        self.turn_deadline.is_some_and(|deadline| deadline <= now)
+    }
+
+    pub fn get_reconnect_deadline(&self) -> Option<DateTime<Utc>> {
+        self.reconnect_deadline
+    }
+
+    /// The disconnected player whose grace window has run out, if any.
+    pub fn get_expired_reconnect(&self, now: DateTime<Utc>) -> Option<PlayerId> {
+        match self.status {
+            GameStatus::PausedForReconnect(player_id) if self.reconnect_deadline.is_some_and(|d| d <= now) => {
+                Some(player_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// The earliest deadline the server has to act on.
+    pub fn get_next_deadline(&self) -> Option<DateTime<Utc>> {
+        self.turn_deadline.into_iter().chain(self.reconnect_deadline).min()
+    }
+
+    pub fn get_match_id(&self) -> Option<MatchId> {
+        self.match_id
+    }
+
+    pub fn get_tournament_id(&self) -> Option<TournamentId> {
+        self.tournament_id
+    }
+
+    pub fn get_spectator_count(&self) -> usize {
+        self.spectators
+    }
+
+    pub fn get_rematch_votes(&self) -> &BTreeSet<PlayerId> {
+        &self.rematch_votes
+    }
+
+    pub fn get_rematch_of(&self) -> Option<GameId> {
+        self.rematch_of
+    }
+
+    pub fn get_rematch_id(&self) -> Option<GameId> {
+        self.rematch_id
+    }
+
+    /// The player who lost, once the game is over.
+    pub fn get_loser(&self) -> Option<PlayerId> {
+        match self.status {
+            GameStatus::PlayerLost(player_id) | GameStatus::Forfeited(player_id) => Some(player_id),
+            _ => None,
+        }
+    }
+
+    /// Seating for a rematch: the same rotation, starting with the loser.
+    pub fn get_rematch_order(&self) -> Vec<PlayerId> {
+        let mut order = self.players.clone();
+        if let Some(seat) = self
+            .get_loser()
+            .and_then(|loser| order.iter().position(|p| *p == loser))
+        {
+            order.rotate_left(seat);
+        }
+        order
+    }
+
+    pub fn get_actions(&self) -> &[PlayerAction] {
+        &self.actions
+    }
+
+    pub fn get_version(&self) -> u64 {
+        self.version
+    }
+
+    /// Events recorded since the game was loaded or created, oldest first.
+    pub fn get_uncommitted_events(&self) -> &[RecordedEvent] {
+        &self.uncommitted.0
+    }
+
+    /// Hand over the uncommitted events, e.g. once they have been stored.
+    pub fn take_uncommitted_events(&mut self) -> Vec<RecordedEvent> {
+        std::mem::take(&mut self.uncommitted.0)
+    }
+
+    pub fn get_host(&self) -> PlayerId {
+        self.host_id
+    }
+
+    /// The player who opened the game; hosting may have passed to someone else since.
+    pub fn get_created_by(&self) -> PlayerId {
+        self.created_by
+    }
+
+    pub fn is_kicked(&self, player_id: PlayerId) -> bool {
+        self.kicked.contains(&player_id)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, GameStatus::PlayerLost(_) | GameStatus::Forfeited(_))
+    }
+
     //  --- Public mutators ---
+    // Each command checks the rules, then records the events describing its outcome; only
+    // `apply` changes the game's state.
     #[tracing::instrument(skip(self))]
     pub fn join(&mut self, player_id: PlayerId) -> Result<(), GameError> {
-        if self.status != GameStatus::WaitingForPlayers {
+        self.status.check(GameCommand::Join)?;
+        if self.players.contains(&player_id) {
+            return Err(GameError::AlreadyJoined);
+        }
+        if self.kicked.contains(&player_id) {
+            return Err(GameError::Kicked);
+        }
+        if self.players.len() >= self.settings.max_players {
             return Err(GameError::GameFull);
         }
-        self.players.push(player_id);
+        self.record(GameEvent::PlayerJoined { player_id });
+        if self.is_team_game() {
+            let team_id = self.smallest_team();
+            self.record(GameEvent::TeamAssigned { player_id, team_id, assigned_by: None });
+        }
+        Ok(())
+    }
 
-        if self.players.len() == 2 {
-            self.status = GameStatus::InProgress;
-        } else {
-            tracing::warn!("join called when game is not waiting for players");
+    /// Move a lobby player to another team. Players pick their own team; the host can place anyone.
+    #[tracing::instrument(skip(self))]
+    pub fn assign_team(
+        &mut self,
+        assigned_by: PlayerId,
+        player_id: PlayerId,
+        team_id: TeamId,
+    ) -> Result<(), GameError> {
+        self.status.check(GameCommand::ChooseTeam)?;
+        if !self.is_team_game() {
+            return Err(GameError::NotATeamGame);
+        }
+        if assigned_by != player_id && assigned_by != self.host_id {
+            return Err(GameError::NotHost);
+        }
+        if !self.players.contains(&player_id) {
+            return Err(GameError::NotAParticipant);
         }
+        if team_id.0 >= self.settings.teams {
+            return Err(GameError::NoSuchTeam { team_id, max: self.settings.teams - 1 });
+        }
+        self.record(GameEvent::TeamAssigned { player_id, team_id, assigned_by: Some(assigned_by) });
+        Ok(())
+    }
+
+    /// Give up a lobby seat. If the host leaves, hosting passes to the next seat.
+    #[tracing::instrument(skip(self))]
+    pub fn leave(&mut self, player_id: PlayerId) -> Result<(), GameError> {
+        self.status.check(GameCommand::Leave)?;
+        if !self.players.contains(&player_id) {
+            return Err(GameError::NotAParticipant);
+        }
+        self.record(GameEvent::PlayerLeft { player_id });
+        Ok(())
+    }
+
+    /// Host-only: remove a player from the lobby and bar them from joining again.
+    #[tracing::instrument(skip(self))]
+    pub fn kick(&mut self, host_id: PlayerId, player_id: PlayerId) -> Result<(), GameError> {
+        self.status.check(GameCommand::Kick)?;
+        if host_id != self.host_id {
+            return Err(GameError::NotHost);
+        }
+        if player_id == host_id {
+            return Err(GameError::CannotKickSelf);
+        }
+        if !self.players.contains(&player_id) {
+            return Err(GameError::NotAParticipant);
+        }
+        self.record(GameEvent::PlayerKicked { host_id, player_id });
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn set_ready(&mut self, player_id: PlayerId, ready: bool) -> Result<(), GameError> {
+        self.status.check(GameCommand::Ready)?;
+        if !self.players.contains(&player_id) {
+            return Err(GameError::NotAParticipant);
+        }
+        self.record(GameEvent::ReadyChanged { player_id, ready });
+        Ok(())
+    }
+
+    /// Close the lobby and begin play. Only the host can start, and only once everyone is ready.
+    /// The table's `TurnOrder` decides who rolls first, drawing from `roller` if it needs to.
+    #[tracing::instrument(skip(self, roller))]
+    pub fn start(&mut self, player_id: PlayerId, roller: &mut impl Roller) -> Result<(), GameError> {
+        self.start_at(player_id, roller, Utc::now())
+    }
+
+    /// `start` with an explicit timestamp, so replays reproduce the seating draws exactly.
+    #[tracing::instrument(skip(self, roller))]
+    pub fn start_at(
+        &mut self,
+        player_id: PlayerId,
+        roller: &mut impl Roller,
+        started_at: DateTime<Utc>,
+    ) -> Result<(), GameError> {
+        self.status.check(GameCommand::Start)?;
+        if player_id != self.get_host() {
+            return Err(GameError::NotHost);
+        }
+        if self.players.len() < MIN_PLAYERS {
+            return Err(GameError::NotEnoughPlayers);
+        }
+        if !self.all_ready() {
+            return Err(GameError::PlayersNotReady);
+        }
+        if let Some(team_id) = (0..self.settings.teams)
+            .map(TeamId)
+            .find(|team_id| self.get_team_members(*team_id).is_empty())
+        {
+            return Err(GameError::EmptyTeam { team_id });
+        }
+
+        let policy = self.settings.turn_order;
+        let (mut order, rolls) = match policy {
+            TurnOrder::HostFirst => (self.players.clone(), vec![]),
+            TurnOrder::Random => self.shuffle_seats(roller, started_at),
+            TurnOrder::RollOff => self.roll_off(roller, started_at),
+        };
+        if self.is_team_game() {
+            order = self.alternate_teams(&order);
+        }
+        let turn_order = TurnOrderOutcome { policy, order, rolls };
+        self.record(GameEvent::GameStarted { player_id, turn_order, started_at });
+        Ok(())
+    }
+
+    pub fn set_match_id(&mut self, match_id: MatchId) {
+        self.record(GameEvent::MatchLinked { match_id });
+    }
+
+    pub fn set_tournament_id(&mut self, tournament_id: TournamentId) {
+        self.record(GameEvent::TournamentLinked { tournament_id });
+    }
+
+    /// Whether one more spectator may join the `watching` already here.
+    pub fn check_spectator_seat(&self, watching: usize) -> Result<(), GameError> {
+        let max = self.settings.max_spectators;
+        if max == 0 {
+            return Err(GameError::SpectatingDisabled);
+        }
+        if watching >= max {
+            return Err(GameError::SpectatorsFull);
+        }
+        Ok(())
+    }
+
+    pub fn set_spectator_count(&mut self, watching: usize) {
+        self.spectators = watching;
+    }
+
+    /// Host-only: change how many people may watch. Lowering the limit keeps whoever is already
+    /// watching, but zero turns spectating off and sends everyone away.
+    #[tracing::instrument(skip(self))]
+    pub fn set_max_spectators(&mut self, host_id: PlayerId, max_spectators: usize) -> Result<(), GameError> {
+        if host_id != self.host_id {
+            return Err(GameError::NotHost);
+        }
+        self.record(GameEvent::SpectatorLimitChanged { max_spectators });
+        Ok(())
+    }
+
+    /// Accept a rematch of a finished game. Returns true only when this vote is the one that completes the set,
+    /// so a repeated vote never opens a second rematch.
+    #[tracing::instrument(skip(self))]
+    pub fn accept_rematch(&mut self, player_id: PlayerId) -> Result<bool, GameError> {
+        self.status.check(GameCommand::Rematch)?;
+        if self.match_id.is_some() || self.tournament_id.is_some() {
+            return Err(GameError::RematchInMatch);
+        }
+        if let Some(game_id) = self.rematch_id {
+            return Err(GameError::RematchAlreadyCreated { game_id });
+        }
+        if !self.players.contains(&player_id) {
+            return Err(GameError::NotAParticipant);
+        }
+        if self.rematch_votes.contains(&player_id) {
+            return Ok(false);
+        }
+        self.record(GameEvent::RematchAccepted { player_id });
+        Ok(self.players.iter().all(|p| self.rematch_votes.contains(p)))
+    }
+
+    /// Link this finished game to the rematch created from it.
+    pub fn set_rematch_id(&mut self, game_id: GameId) {
+        self.record(GameEvent::RematchCreated { game_id });
+    }
+
+    /// Drop the link to a rematch that could not be opened, along with the votes for it.
+    pub fn cancel_rematch(&mut self, game_id: GameId) -> Result<(), GameError> {
+        if self.rematch_id != Some(game_id) {
+            return Err(GameError::NotAParticipant);
+        }
+        self.record(GameEvent::RematchCancelled { game_id });
+        Ok(())
+    }
+
+    pub fn set_rematch_of(&mut self, game_id: GameId) {
+        self.record(GameEvent::RematchOf { game_id });
+    }
+
+    /// Publish the hash of the server seed before any roll is made.
+    #[tracing::instrument(skip(self))]
+    pub fn commit_server_seed(&mut self, commitment: String) -> Result<(), GameError> {
+        self.fairness.check_commit()?;
+        self.record(GameEvent::ServerSeedCommitted { commitment });
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn set_client_seed(&mut self, player_id: PlayerId, client_seed: String) -> Result<(), GameError> {
+        if !self.players.contains(&player_id) {
+            return Err(GameError::NotAParticipant);
+        }
+        self.fairness.check_client_seed()?;
+        self.record(GameEvent::ClientSeedSet { player_id, client_seed });
+        Ok(())
+    }
+
+    /// Publish the server seed so every roll of the finished game can be checked.
+    #[tracing::instrument(skip(self, server_seed))]
+    pub fn reveal_server_seed(&mut self, server_seed: &ServerSeed) -> Result<(), GameError> {
+        if !self.is_finished() {
+            return Err(GameError::SeedNotRevealed);
+        }
+        self.fairness.check_reveal(server_seed)?;
+        self.record(GameEvent::ServerSeedRevealed { server_seed: server_seed.clone() });
         Ok(())
     }
 
     #[tracing::instrument(skip(self))]
     pub fn reconnect(&mut self, player_id: PlayerId) -> Result<(), GameError> {
+        self.reconnect_at(player_id, Utc::now())
+    }
+
+    /// `reconnect` with an explicit timestamp for the turn clock it restarts.
+    #[tracing::instrument(skip(self))]
+    pub fn reconnect_at(&mut self, player_id: PlayerId, reconnected_at: DateTime<Utc>) -> Result<(), GameError> {
+        if !self.players.contains(&player_id) {
+            return Err(GameError::NotAParticipant);
+        }
         match self.status {
-            GameStatus::PausedForReconnect(disconnected_player) => {
-                if disconnected_player == player_id {
-                    tracing::info!(game_id = %self.id, player_id = %player_id, "Player reconnected. Resuming game.");
-                    self.status = GameStatus::InProgress;
-                    Ok(())
-                } else {
-                    Err(GameError::GameFull)
-                }
-            }
+            // Nothing is holding the game up, so there is nothing to resume.
             GameStatus::InProgress => Ok(()),
-            _ => Err(GameError::GamePaused),
+            GameStatus::PausedForReconnect(disconnected_player) if disconnected_player != player_id => {
+                Err(GameError::NotDisconnected)
+            }
+            _ => {
+                self.status.check(GameCommand::Reconnect)?;
+                tracing::info!(game_id = %self.id, player_id = %player_id, "Player reconnected. Resuming game.");
+                self.record(GameEvent::PlayerReconnected { player_id, reconnected_at });
+                Ok(())
+            }
         }
     }
 
     #[tracing::instrument(skip(self))]
     pub fn pause_game(&mut self, disconnected_player: PlayerId) -> Result<(), GameError> {
-        if self.status != GameStatus::InProgress {
-            return Err(GameError::GameFinished);
+        let grace = self.settings.reconnect_grace_secs;
+        let reconnect_deadline = (grace > 0).then(|| Utc::now() + Duration::seconds(grace as i64));
+        self.pause_game_until(disconnected_player, reconnect_deadline)
+    }
+
+    /// `pause_game` with the reconnect deadline already worked out, so replays reproduce it.
+    #[tracing::instrument(skip(self))]
+    pub fn pause_game_until(
+        &mut self,
+        disconnected_player: PlayerId,
+        reconnect_deadline: Option<DateTime<Utc>>,
+    ) -> Result<(), GameError> {
+        self.status.check(GameCommand::Pause)?;
+        if !self.players.contains(&disconnected_player) || self.is_eliminated(disconnected_player) {
+            return Err(GameError::NotAParticipant);
         }
 
-        self.status = GameStatus::PausedForReconnect(disconnected_player);
+        self.record(GameEvent::GamePaused { player_id: disconnected_player, reconnect_deadline });
         tracing::warn!(game_id = %self.id, player = %disconnected_player, "Game paused due to player disconnect.");
         Ok(())
     }
 
     #[tracing::instrument(skip(self, roller))]
     pub fn roll(&mut self, player_id: PlayerId, roller: &mut impl Roller) -> Result<Vec<GameEvent>, GameError> {
-        match self.status {
-            GameStatus::InProgress => {} // OK to proceed
-            GameStatus::WaitingForPlayers => return Err(GameError::NotEnoughPlayers),
-            GameStatus::PausedForReconnect(_) => return Err(GameError::GamePaused),
-            GameStatus::PlayerLost(_) => return Err(GameError::GameFinished),
-        }
+        self.roll_at(player_id, roller, Utc::now())
+    }
+
+    /// `roll` with an explicit timestamp, so replays reproduce the recorded history exactly.
+    #[tracing::instrument(skip(self, roller))]
+    pub fn roll_at(
+        &mut self,
+        player_id: PlayerId,
+        roller: &mut impl Roller,
+        rolled_at: DateTime<Utc>,
+    ) -> Result<Vec<GameEvent>, GameError> {
+        self.status.check(GameCommand::Roll)?;
 
         // check if roll is by current player!
         if self.get_current_player() != Some(&player_id) {
             return Err(GameError::NotYourTurn);
         }
 
-        let roll_result = roller.roll_in_range(self.current_max);
-        let mut events = vec![];
+        let (min, max) = self.get_roll_range();
+        let value = roller.roll_between(min, max);
+        let nonce = self.fairness.get_nonce();
+        let mut events = vec![GameEvent::Rolled(RollRecord {
+            player_id,
+            min,
+            max,
+            value,
+            nonce,
+            rolled_at,
+        })];
 
-        self.handle_roll(player_id, roll_result, &mut events);
+        // Elimination Logic
+        if self.settings.rules.rules().is_loss((min, max), value) {
+            events.extend(self.elimination_events(player_id, None));
+        }
+        for event in &events {
+            self.record(event.clone());
+        }
 
         Ok(events)
     }
 
-    //  --- Private helpers ---
-    fn next_turn(&mut self) {
-        self.turn_index = (self.turn_index + 1) % self.players.len();
+    /// Knock a player out without a roll: they resigned, or their turn timer or reconnect grace ran out.
+    #[tracing::instrument(skip(self))]
+    pub fn forfeit(&mut self, player_id: PlayerId) -> Result<Vec<GameEvent>, GameError> {
+        self.forfeit_at(player_id, Utc::now())
     }
 
+    /// `forfeit` with an explicit timestamp for the turn clock it restarts.
     #[tracing::instrument(skip(self))]
-    fn handle_roll(&mut self, player_id: PlayerId, roll_result: u32, events: &mut Vec<GameEvent>) {
-        if self.status != GameStatus::InProgress {
-            return;
+    pub fn forfeit_at(
+        &mut self,
+        player_id: PlayerId,
+        forfeited_at: DateTime<Utc>,
+    ) -> Result<Vec<GameEvent>, GameError> {
+        self.status.check(GameCommand::Forfeit)?;
+        if !self.players.contains(&player_id) || self.is_eliminated(player_id) {
+            return Err(GameError::NotAParticipant);
         }
 
-        events.push(GameEvent::Rolled { player_id, value: roll_result });
+        let events = self.elimination_events(player_id, Some(forfeited_at));
+        for event in &events {
+            self.record(event.clone());
+        }
 
-        // Game Over Logic
-        if roll_result == 1 {
-            self.status = GameStatus::PlayerLost(player_id);
+        Ok(events)
+    }
+
+    //  --- Event sourcing ---
+    /// Rebuild a game by folding its event stream, which must start with `GameEvent::Created`.
+    pub fn from_events(events: &[RecordedEvent]) -> Option<Self> {
+        let GameEvent::Created { game_id, host_id, settings } = &events.first()?.event else {
+            return None;
+        };
+        let mut game = Self::opening(*game_id, *host_id, settings.clone());
+        game.catch_up(events);
+        Some(game)
+    }
 
-            // Calculate winner (the other player)
-            let winner_id = self
-                .players
+    /// Fold the events this game has not seen yet, e.g. the ones recorded after a snapshot.
+    pub fn catch_up(&mut self, events: &[RecordedEvent]) {
+        let seen = self.version;
+        for recorded in events.iter().filter(|e| e.sequence > seen) {
+            self.apply(&recorded.event);
+        }
+    }
+
+    /// Fold one event into the game. Events were validated when they were recorded, so this
+    /// never fails; `Created` starts the game over from its opening state.
+    pub fn apply(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::Created { game_id, host_id, settings } => {
+                *self = Self::opening(*game_id, *host_id, settings.clone());
+            }
+            GameEvent::ServerSeedCommitted { commitment } => self.fairness.commit(commitment.clone()),
+            GameEvent::ClientSeedSet { player_id, client_seed } => {
+                self.fairness.set_client_seed(*player_id, client_seed.clone());
+                self.actions
+                    .push(PlayerAction::ClientSeed { player_id: *player_id, client_seed: client_seed.clone() });
+            }
+            GameEvent::PlayerJoined { player_id } => {
+                // Whoever walks into an empty lobby runs it.
+                if self.players.is_empty() {
+                    self.host_id = *player_id;
+                }
+                self.players.push(*player_id);
+                self.actions.push(PlayerAction::Join { player_id: *player_id });
+            }
+            GameEvent::PlayerLeft { player_id } => {
+                self.remove_from_lobby(*player_id);
+                self.actions.push(PlayerAction::Leave { player_id: *player_id });
+            }
+            GameEvent::PlayerKicked { host_id, player_id } => {
+                self.remove_from_lobby(*player_id);
+                self.kicked.insert(*player_id);
+                self.actions
+                    .push(PlayerAction::Kick { host_id: *host_id, player_id: *player_id });
+            }
+            GameEvent::ReadyChanged { player_id, ready } => {
+                if *ready {
+                    self.ready.insert(*player_id);
+                } else {
+                    self.ready.remove(player_id);
+                }
+                self.actions
+                    .push(PlayerAction::Ready { player_id: *player_id, ready: *ready });
+            }
+            GameEvent::TeamAssigned { player_id, team_id, assigned_by } => {
+                self.teams.insert(*player_id, *team_id);
+                // Placing a joining player is part of the join, so only a choice is replayed.
+                if let Some(assigned_by) = assigned_by {
+                    self.actions.push(PlayerAction::Team {
+                        assigned_by: *assigned_by,
+                        player_id: *player_id,
+                        team_id: *team_id,
+                    });
+                }
+            }
+            GameEvent::GameStarted { player_id, turn_order, started_at } => {
+                self.players = turn_order.order.clone();
+                if let Some(last) = turn_order.rolls.last() {
+                    self.fairness.consume(last.nonce);
+                }
+                self.turn_order = Some(turn_order.clone());
+                self.status = GameStatus::InProgress;
+                self.start_turn_timer(*started_at);
+                self.actions
+                    .push(PlayerAction::Start { player_id: *player_id, started_at: *started_at });
+            }
+            GameEvent::Rolled(roll) => {
+                self.fairness.consume(roll.nonce);
+                self.history.push(roll.clone());
+                self.actions
+                    .push(PlayerAction::Roll { player_id: roll.player_id, rolled_at: roll.rolled_at });
+
+                // A losing roll is followed by the elimination that deals with it.
+                let rules = self.settings.rules.rules();
+                let range = (roll.min, roll.max);
+                if !rules.is_loss(range, roll.value) {
+                    (self.current_min, self.current_max) = rules.next_range(range, roll.value);
+                    self.next_turn();
+                }
+                self.start_turn_timer(roll.rolled_at);
+            }
+            GameEvent::PlayerEliminated { player_id, .. } => self.knock_out(*player_id),
+            GameEvent::TeamEliminated { players, .. } => {
+                for player_id in players {
+                    self.knock_out(*player_id);
+                }
+            }
+            GameEvent::PlayerForfeited { player_id, forfeited_at, .. } => {
+                let was_current = self.get_current_player() == Some(player_id);
+                // Once the disconnected player is out, nobody is holding the game up.
+                let resumed = self.status == GameStatus::PausedForReconnect(*player_id);
+                if resumed {
+                    self.status = GameStatus::InProgress;
+                    self.reconnect_deadline = None;
+                }
+                self.actions
+                    .push(PlayerAction::Forfeit { player_id: *player_id, forfeited_at: *forfeited_at });
+                self.knock_out(*player_id);
+                if was_current || resumed {
+                    self.start_turn_timer(*forfeited_at);
+                }
+            }
+            GameEvent::GameOver { losing_team, forfeited, .. } => {
+                let loser_id = losing_team.players[0];
+                self.status = if *forfeited {
+                    GameStatus::Forfeited(loser_id)
+                } else {
+                    GameStatus::PlayerLost(loser_id)
+                };
+                self.turn_deadline = None;
+                self.reconnect_deadline = None;
+            }
+            GameEvent::GamePaused { player_id, reconnect_deadline } => {
+                self.status = GameStatus::PausedForReconnect(*player_id);
+                self.turn_deadline = None;
+                self.reconnect_deadline = *reconnect_deadline;
+                self.actions
+                    .push(PlayerAction::Disconnect { player_id: *player_id, reconnect_deadline: *reconnect_deadline });
+            }
+            GameEvent::PlayerReconnected { player_id, reconnected_at } => {
+                self.status = GameStatus::InProgress;
+                self.reconnect_deadline = None;
+                self.start_turn_timer(*reconnected_at);
+                self.actions
+                    .push(PlayerAction::Reconnect { player_id: *player_id, reconnected_at: *reconnected_at });
+            }
+            GameEvent::ServerSeedRevealed { server_seed } => self.fairness.reveal(server_seed),
+            GameEvent::SpectatorLimitChanged { max_spectators } => {
+                self.settings.max_spectators = *max_spectators;
+                self.actions
+                    .push(PlayerAction::SpectatorLimit { host_id: self.host_id, max_spectators: *max_spectators });
+            }
+            GameEvent::MatchLinked { match_id } => {
+                self.match_id = Some(*match_id);
+                self.actions.push(PlayerAction::MatchLinked { match_id: *match_id });
+            }
+            GameEvent::TournamentLinked { tournament_id } => {
+                self.tournament_id = Some(*tournament_id);
+                self.actions
+                    .push(PlayerAction::TournamentLinked { tournament_id: *tournament_id });
+            }
+            GameEvent::RematchAccepted { player_id } => {
+                self.rematch_votes.insert(*player_id);
+                self.actions.push(PlayerAction::Rematch { player_id: *player_id });
+            }
+            GameEvent::RematchCreated { game_id } => {
+                self.rematch_id = Some(*game_id);
+                self.actions.push(PlayerAction::RematchCreated { game_id: *game_id });
+            }
+            GameEvent::RematchCancelled { game_id } => {
+                self.rematch_id = None;
+                self.rematch_votes.clear();
+                self.actions.push(PlayerAction::RematchCancelled { game_id: *game_id });
+            }
+            GameEvent::RematchOf { game_id } => {
+                self.rematch_of = Some(*game_id);
+                self.actions.push(PlayerAction::RematchOf { game_id: *game_id });
+            }
+        }
+        self.version += 1;
+    }
+
+    //  --- Private helpers ---
+    /// Apply `event` and queue it for the repository to append to the stream.
+    fn record(&mut self, event: GameEvent) {
+        self.apply(&event);
+        self.uncommitted.0.push(RecordedEvent {
+            sequence: self.version,
+            schema: EVENT_SCHEMA_VERSION,
+            recorded_at: Utc::now(),
+            event,
+        });
+    }
+
+    fn remove_from_lobby(&mut self, player_id: PlayerId) {
+        self.players.retain(|p| *p != player_id);
+        self.ready.remove(&player_id);
+        self.teams.remove(&player_id);
+        if player_id == self.host_id {
+            if let Some(&next_host) = self.players.first() {
+                tracing::info!(game_id = %self.id, host_id = %next_host, "Host left; hosting passed on.");
+                self.host_id = next_host;
+                // The new host starts the game rather than readying up.
+                self.ready.remove(&next_host);
+            }
+        }
+    }
+
+    /// Draw a seating roll in `1..=max` from the game's roll stream, so it can be verified later.
+    fn draw_for_seating(
+        player_id: PlayerId,
+        max: u32,
+        nonce: &mut u64,
+        roller: &mut impl Roller,
+        rolled_at: DateTime<Utc>,
+    ) -> RollRecord {
+        let value = roller.roll_between(1, max);
+        *nonce += 1;
+        RollRecord { player_id, min: 1, max, value, nonce: *nonce - 1, rolled_at }
+    }
+
+    /// Fisher-Yates over the seats; each draw is recorded against the seat being filled.
+    fn shuffle_seats(&self, roller: &mut impl Roller, rolled_at: DateTime<Utc>) -> (Vec<PlayerId>, Vec<RollRecord>) {
+        let mut order = self.players.clone();
+        let mut nonce = self.fairness.get_nonce();
+        let mut rolls = vec![];
+        for seat in (1..order.len()).rev() {
+            let draw = Self::draw_for_seating(order[seat], seat as u32 + 1, &mut nonce, roller, rolled_at);
+            order.swap(seat, draw.value as usize - 1);
+            rolls.push(draw);
+        }
+        (order, rolls)
+    }
+
+    /// Everyone rolls in the opening range and the tied leaders roll again until one is left.
+    /// The seating then rotates so the winner goes first.
+    fn roll_off(&self, roller: &mut impl Roller, rolled_at: DateTime<Utc>) -> (Vec<PlayerId>, Vec<RollRecord>) {
+        let max = self.settings.starting_max;
+        let mut nonce = self.fairness.get_nonce();
+        let mut rolls = vec![];
+        let mut contenders = self.players.clone();
+        while contenders.len() > 1 {
+            let round: Vec<RollRecord> = contenders
+                .iter()
+                .map(|&player_id| Self::draw_for_seating(player_id, max, &mut nonce, roller, rolled_at))
+                .collect();
+            let best = round.iter().map(|roll| roll.value).max().unwrap_or_default();
+            contenders = round
                 .iter()
-                .find(|&p| *p != player_id)
-                .cloned()
-                .unwrap_or(player_id); // Fallback should never happen in 2p game
+                .filter(|roll| roll.value == best)
+                .map(|roll| roll.player_id)
+                .collect();
+            rolls.extend(round);
+        }
+
+        let mut order = self.players.clone();
+        let first = order.iter().position(|p| *p == contenders[0]).unwrap_or(0);
+        order.rotate_left(first);
+        (order, rolls)
+    }
+
+    /// The team with the fewest players, lowest number first, for placing a joining player.
+    fn smallest_team(&self) -> TeamId {
+        (0..self.settings.teams)
+            .map(TeamId)
+            .min_by_key(|team_id| self.teams.values().filter(|t| *t == team_id).count())
+            .unwrap_or(TeamId(0))
+    }
+
+    /// Seat the teams alternately, keeping each team's members in `order`. The team holding the
+    /// first seat stays first.
+    fn alternate_teams(&self, order: &[PlayerId]) -> Vec<PlayerId> {
+        let mut sides: Vec<Vec<PlayerId>> = vec![];
+        let mut seen = vec![];
+        for player_id in order {
+            let team_id = self.get_team(*player_id);
+            match seen.iter().position(|t| *t == team_id) {
+                Some(side) => sides[side].push(*player_id),
+                None => {
+                    seen.push(team_id);
+                    sides.push(vec![*player_id]);
+                }
+            }
+        }
+        let rounds = sides.iter().map(Vec::len).max().unwrap_or_default();
+        (0..rounds)
+            .flat_map(|round| sides.iter().filter_map(move |side| side.get(round)).cloned())
+            .collect()
+    }
+
+    /// Advance to the next player who is still in the game.
+    fn next_turn(&mut self) {
+        if self.is_team_game() {
+            self.turn_index = self.next_team_turn();
+            return;
+        }
+        let active: Vec<bool> = self.players.iter().map(|p| !self.eliminated.contains(p)).collect();
+        self.turn_index = self.settings.rules.rules().next_turn(self.turn_index, &active);
+    }
+
+    /// In a team game the turn passes to the next team still in, in seating order, and to the
+    /// member of that team after whoever last rolled for it.
+    fn next_team_turn(&self) -> usize {
+        let mut team_order: Vec<TeamId> = vec![];
+        for team_id in self.players.iter().filter_map(|p| self.get_team(*p)) {
+            if !team_order.contains(&team_id) {
+                team_order.push(team_id);
+            }
+        }
+        let current = self.get_current_player().and_then(|p| self.get_team(*p));
+        let from = team_order.iter().position(|t| Some(*t) == current).unwrap_or(0);
+
+        let next_team = (1..=team_order.len())
+            .map(|step| team_order[(from + step) % team_order.len()])
+            .find(|team_id| self.get_team_members(*team_id).iter().any(|p| !self.is_eliminated(*p)));
+        let Some(team_id) = next_team else {
+            return self.turn_index;
+        };
+
+        let members: Vec<PlayerId> = self
+            .get_team_members(team_id)
+            .into_iter()
+            .filter(|p| !self.is_eliminated(*p))
+            .collect();
+        let last = self
+            .history
+            .iter()
+            .rev()
+            .find_map(|roll| members.iter().position(|p| *p == roll.player_id));
+        let next = members[last.map_or(0, |seat| (seat + 1) % members.len())];
+        self.players.iter().position(|p| *p == next).unwrap_or(self.turn_index)
+    }
+
+    /// The players a loss takes out: the whole team in a team game, or just the player.
+    fn losing_side(&self, player_id: PlayerId) -> Team {
+        match self.get_team(player_id) {
+            Some(team_id) => {
+                let mut players = vec![player_id];
+                players.extend(
+                    self.get_team_members(team_id)
+                        .into_iter()
+                        .filter(|p| *p != player_id && !self.is_eliminated(*p)),
+                );
+                Team { team_id: Some(team_id), players }
+            }
+            None => Team { team_id: None, players: vec![player_id] },
+        }
+    }
+
+    /// The events for taking a player out of the game, ending it if only one side is left.
+    /// `forfeited_at` is set when they forfeited rather than rolling out.
+    fn elimination_events(&self, player_id: PlayerId, forfeited_at: Option<DateTime<Utc>>) -> Vec<GameEvent> {
+        let losing_team = self.losing_side(player_id);
+        let remaining: Vec<PlayerId> = self
+            .get_active_players()
+            .into_iter()
+            .filter(|p| !losing_team.players.contains(p))
+            .collect();
+
+        let mut sides: Vec<Option<TeamId>> = remaining.iter().map(|p| self.get_team(*p)).collect();
+        sides.dedup();
+        let winning_team = match (&remaining[..], &sides[..]) {
+            ([winner_id], _) => Some(Team { team_id: self.get_team(*winner_id), players: remaining.clone() }),
+            (_, [Some(team_id)]) => Some(Team { team_id: Some(*team_id), players: remaining.clone() }),
+            _ => None,
+        };
+        let winner_id = winning_team
+            .as_ref()
+            .filter(|team| team.team_id.is_none())
+            .map(|team| team.players[0]);
+
+        let mut events = vec![match forfeited_at {
+            Some(forfeited_at) => {
+                GameEvent::PlayerForfeited { player_id, winner_id, remaining: remaining.clone(), forfeited_at }
+            }
+            None => GameEvent::PlayerEliminated { player_id, remaining: remaining.clone() },
+        }];
+        if let (Some(team_id), [_, teammates @ ..]) = (losing_team.team_id, &losing_team.players[..]) {
+            if !teammates.is_empty() {
+                events.push(GameEvent::TeamEliminated { team_id, players: teammates.to_vec(), remaining });
+            }
+        }
+
+        if let Some(winning_team) = winning_team {
+            let mut standings = winning_team.players.clone();
+            standings.extend(losing_team.players.iter());
+            standings.extend(self.eliminated.iter().rev());
 
             // Event: Game Over
-            events.push(GameEvent::GameOver { winner_id, loser_id: player_id });
-        } else {
-            self.current_max = roll_result;
+            events.push(GameEvent::GameOver {
+                winning_team,
+                losing_team,
+                standings,
+                forfeited: forfeited_at.is_some(),
+            });
+        }
+        events
+    }
+
+    /// Mark a player as out. If it was their turn and the game goes on, the survivors start a
+    /// fresh round from the top.
+    fn knock_out(&mut self, player_id: PlayerId) {
+        let was_current = self.get_current_player() == Some(&player_id);
+        self.eliminated.push(player_id);
+        if was_current && self.get_active_players().len() > 1 {
+            (self.current_min, self.current_max) =
+                self.settings.rules.rules().opening_range(self.settings.starting_max);
             self.next_turn();
         }
     }
+
+    /// Restart the clock for whoever's turn it is now, or stop it if nobody can roll.
+    fn start_turn_timer(&mut self, now: DateTime<Utc>) {
+        let timeout = self.settings.turn_timeout_secs;
+        self.turn_deadline =
+            (self.status == GameStatus::InProgress && timeout > 0).then(|| now + Duration::seconds(timeout as i64));
+    }
+}
+
+/// Events recorded since the game was loaded, waiting for `GameRepository::save_game` to append
+/// them to the stream. They are bookkeeping rather than game state, so two games compare equal
+/// whatever is queued here.
+#[derive(Debug, Clone, Default)]
+struct Uncommitted(Vec<RecordedEvent>);
+
+impl PartialEq for Uncommitted {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::roller::{Roller, SeededRoller};
+use super::types::{GameError, PlayerId, RollRecord};
+
+/// Secret seed the server commits to when a game is created.
+/// It is stored next to the game, never inside it, until the game is over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerSeed(String);
+
+impl ServerSeed {
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+        rand::rng().fill_bytes(&mut bytes);
+        Self(hex::encode(bytes))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The public commitment: hex-encoded SHA-256 of the seed.
+    pub fn commitment(&self) -> String {
+        commitment_for(&self.0)
+    }
+}
+
+impl From<String> for ServerSeed {
+    fn from(seed: String) -> Self {
+        Self(seed)
+    }
+}
+
+pub fn commitment_for(server_seed: &str) -> String {
+    hex::encode(Sha256::digest(server_seed.as_bytes()))
+}
+
+/// The per-game ChaCha key: SHA-256 over the server seed and then each client seed in player id
+/// order, each prefixed with its length in bytes as a big-endian `u64`. The prefixes keep the mix
+/// unambiguous, whatever characters the seeds contain.
+pub fn game_seed(server_seed: &str, client_seeds: &BTreeMap<PlayerId, String>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for seed in std::iter::once(server_seed).chain(client_seeds.values().map(String::as_str)) {
+        hasher.update((seed.len() as u64).to_be_bytes());
+        hasher.update(seed.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Derive a roll in `1..=max` from the server seed, the client seeds and the nonce.
+pub fn derive_roll(server_seed: &str, client_seeds: &BTreeMap<PlayerId, String>, nonce: u64, max: u32) -> u32 {
+    SeededRoller::new(game_seed(server_seed, client_seeds), nonce).roll_in_range(max)
+}
+
+/// The generator outputs behind the roll at `nonce`; see `SeededRoller::draws`.
+pub fn roll_draws(server_seed: &ServerSeed, client_seeds: &BTreeMap<PlayerId, String>, nonce: u64) -> [u32; 2] {
+    SeededRoller::new(game_seed(server_seed.as_str(), client_seeds), nonce).draws()
+}
+
+/// A `Roller` whose every result can be recomputed once the server seed is revealed.
+pub struct FairRoller(SeededRoller);
+
+impl FairRoller {
+    /// Picks up where the game left off, so the next roll uses the game's current nonce.
+    pub fn new(server_seed: ServerSeed, fairness: &Fairness) -> Self {
+        Self(SeededRoller::new(
+            game_seed(server_seed.as_str(), &fairness.client_seeds),
+            fairness.nonce,
+        ))
+    }
+}
+
+impl Roller for FairRoller {
+    fn roll_in_range(&mut self, max: u32) -> u32 {
+        self.0.roll_in_range(max)
+    }
+}
+
+/// The public half of the commit-reveal scheme, carried on the `Game`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fairness {
+    server_seed_hash: Option<String>,
+    client_seeds: BTreeMap<PlayerId, String>,
+    nonce: u64,
+    revealed_server_seed: Option<String>,
+}
+
+impl Fairness {
+    pub fn get_server_seed_hash(&self) -> Option<&str> {
+        self.server_seed_hash.as_deref()
+    }
+
+    pub fn get_client_seeds(&self) -> &BTreeMap<PlayerId, String> {
+        &self.client_seeds
+    }
+
+    pub fn get_nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    pub fn get_revealed_server_seed(&self) -> Option<&str> {
+        self.revealed_server_seed.as_deref()
+    }
+
+    // The `check_*` methods validate a change; the setters below them only apply it, so the
+    // game's event fold can replay changes that were validated when they were recorded.
+    pub(crate) fn check_commit(&self) -> Result<(), GameError> {
+        if self.server_seed_hash.is_some() || self.nonce > 0 {
+            return Err(GameError::SeedsLocked);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_client_seed(&self) -> Result<(), GameError> {
+        if self.nonce > 0 {
+            return Err(GameError::SeedsLocked);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_reveal(&self, server_seed: &ServerSeed) -> Result<(), GameError> {
+        if self.server_seed_hash.as_deref() != Some(server_seed.commitment().as_str()) {
+            return Err(GameError::SeedMismatch);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn commit(&mut self, commitment: String) {
+        self.server_seed_hash = Some(commitment);
+    }
+
+    pub(crate) fn set_client_seed(&mut self, player_id: PlayerId, seed: String) {
+        self.client_seeds.insert(player_id, seed);
+    }
+
+    /// Mark `nonce` as used, so the next roll draws from the one after it.
+    pub(crate) fn consume(&mut self, nonce: u64) {
+        self.nonce = self.nonce.max(nonce + 1);
+    }
+
+    pub(crate) fn reveal(&mut self, server_seed: &ServerSeed) {
+        self.revealed_server_seed = Some(server_seed.as_str().to_string());
+    }
+
+    /// Recompute every recorded roll from the revealed server seed.
+    pub fn verify(&self, history: &[RollRecord]) -> Result<FairnessReport, GameError> {
+        let server_seed = self.revealed_server_seed.clone().ok_or(GameError::SeedNotRevealed)?;
+        let server_seed_hash = self.server_seed_hash.clone().unwrap_or_default();
+        let commitment_valid = commitment_for(&server_seed) == server_seed_hash;
+
+        let rolls: Vec<VerifiedRoll> = history
+            .iter()
+            .map(|roll| {
+                let span = roll.max - roll.min + 1;
+                let expected = roll.min - 1 + derive_roll(&server_seed, &self.client_seeds, roll.nonce, span);
+                VerifiedRoll {
+                    nonce: roll.nonce,
+                    max: roll.max,
+                    value: roll.value,
+                    expected,
+                    valid: expected == roll.value,
+                }
+            })
+            .collect();
+        let valid = commitment_valid && rolls.iter().all(|r| r.valid);
+
+        Ok(FairnessReport {
+            server_seed,
+            server_seed_hash,
+            commitment_valid,
+            client_seeds: self.client_seeds.clone(),
+            rolls,
+            valid,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiedRoll {
+    pub nonce: u64,
+    pub max: u32,
+    pub value: u32,
+    pub expected: u32,
+    pub valid: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FairnessReport {
+    pub server_seed: String,
+    pub server_seed_hash: String,
+    pub commitment_valid: bool,
+    pub client_seeds: BTreeMap<PlayerId, String>,
+    pub rolls: Vec<VerifiedRoll>,
+    pub valid: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn record(fairness: &mut Fairness, history: &mut Vec<RollRecord>, max: u32, value: u32) {
+        let nonce = fairness.get_nonce();
+        fairness.consume(nonce);
+        history.push(RollRecord { player_id: PlayerId::new(), min: 1, max, value, nonce, rolled_at: Utc::now() });
+    }
+
+    #[test]
+    fn test_commitment_is_sha256_of_seed() {
+        let seed = ServerSeed::from("abc".to_string());
+        assert_eq!(
+            seed.commitment(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_derive_roll_is_deterministic_and_in_range() {
+        let mut client_seeds = BTreeMap::new();
+        client_seeds.insert(PlayerId::new(), "lucky".to_string());
+
+        for nonce in 0..200 {
+            let a = derive_roll("seed", &client_seeds, nonce, 100);
+            let b = derive_roll("seed", &client_seeds, nonce, 100);
+            assert_eq!(a, b);
+            assert!((1..=100).contains(&a));
+        }
+        assert_eq!(derive_roll("seed", &client_seeds, 0, 1), 1);
+    }
+
+    #[test]
+    fn test_game_seed_keeps_client_seeds_apart() {
+        let mut players = [PlayerId::new(), PlayerId::new()];
+        players.sort();
+        let seeds = |first: &str, second: &str| {
+            BTreeMap::from([(players[0], first.to_string()), (players[1], second.to_string())])
+        };
+
+        assert_ne!(
+            game_seed("seed", &seeds("a,b", "c")),
+            game_seed("seed", &seeds("a", "b,c"))
+        );
+        assert_ne!(game_seed("seed", &seeds("ab", "")), game_seed("seed", &seeds("a", "b")));
+    }
+
+    #[test]
+    fn test_fair_roller_advances_nonce() {
+        let seed = ServerSeed::generate();
+        let mut fairness = Fairness::default();
+        let mut history = vec![];
+        fairness.commit(seed.commitment());
+
+        let mut roller = FairRoller::new(seed.clone(), &fairness);
+        let first = roller.roll_in_range(1000);
+        record(&mut fairness, &mut history, 1000, first);
+
+        let mut roller = FairRoller::new(seed.clone(), &fairness);
+        let second = roller.roll_in_range(first);
+        record(&mut fairness, &mut history, first, second);
+
+        fairness.reveal(&seed);
+        let report = fairness.verify(&history).unwrap();
+        assert!(report.valid);
+        assert_eq!(report.rolls.len(), 2);
+        assert_eq!(report.rolls[1].nonce, 1);
+    }
+
+    #[test]
+    fn test_verify_detects_tampered_roll() {
+        let seed = ServerSeed::generate();
+        let mut fairness = Fairness::default();
+        let mut history = vec![];
+        fairness.commit(seed.commitment());
+
+        let honest = derive_roll(seed.as_str(), &BTreeMap::new(), 0, 1000);
+        record(
+            &mut fairness,
+            &mut history,
+            1000,
+            if honest == 1 { 2 } else { honest - 1 },
+        );
+        fairness.reveal(&seed);
+
+        let report = fairness.verify(&history).unwrap();
+        assert!(report.commitment_valid);
+        assert!(!report.rolls[0].valid);
+        assert!(!report.valid);
+    }
+
+    #[test]
+    fn test_reveal_rejects_wrong_seed() {
+        let mut fairness = Fairness::default();
+        fairness.commit(ServerSeed::generate().commitment());
+
+        assert_eq!(
+            fairness.check_reveal(&ServerSeed::generate()),
+            Err(GameError::SeedMismatch)
+        );
+        assert_eq!(fairness.verify(&[]), Err(GameError::SeedNotRevealed));
+    }
+
+    #[test]
+    fn test_client_seeds_lock_after_first_roll() {
+        let mut fairness = Fairness::default();
+        let player_id = PlayerId::new();
+        fairness.check_client_seed().unwrap();
+        fairness.set_client_seed(player_id, "one".into());
+        record(&mut fairness, &mut vec![], 1000, 500);
+
+        assert_eq!(fairness.check_client_seed(), Err(GameError::SeedsLocked));
+    }
+
+    #[test]
+    fn test_verify_shifted_range() {
+        let seed = ServerSeed::generate();
+        let mut fairness = Fairness::default();
+        fairness.commit(seed.commitment());
+
+        let value = FairRoller::new(seed.clone(), &fairness).roll_between(40, 100);
+        assert!((40..=100).contains(&value));
+        let nonce = fairness.get_nonce();
+        fairness.consume(nonce);
+        let history =
+            vec![RollRecord { player_id: PlayerId::new(), min: 40, max: 100, value, nonce, rolled_at: Utc::now() }];
+
+        fairness.reveal(&seed);
+        assert!(fairness.verify(&history).unwrap().valid);
+    }
+}
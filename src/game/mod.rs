@@ -1,9 +1,21 @@
 pub mod domain;
+pub mod fairness;
+pub mod replay;
 pub mod roller;
+pub mod rules;
+pub mod series;
+pub mod settings;
+pub mod tournament;
 pub mod types;
 
 #[cfg(test)]
 mod tests;
 
 pub use domain::Game;
-pub use types::{GameError, GameId, GameStatus, PlayerId};
+pub use rules::{GameRules, RuleSet};
+pub use series::{Match, MatchStatus};
+pub use settings::GameSettings;
+pub use tournament::{Seeding, Tournament, TournamentStatus};
+pub use types::{
+    GameCommand, GameError, GameId, GameStatus, MatchId, PlayerAction, PlayerId, Team, TeamId, TournamentId,
+};
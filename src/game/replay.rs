@@ -0,0 +1,211 @@
+use super::domain::Game;
+use super::fairness::{FairRoller, ServerSeed};
+use super::settings::GameSettings;
+use super::types::{GameError, GameEvent, GameId, PlayerAction, PlayerId};
+
+/// One step of a replay: the action applied, the events it produced and the state after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayStep {
+    pub action: PlayerAction,
+    pub events: Vec<GameEvent>,
+    pub state: Game,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Replay diverged at step {step}: {source}")]
+pub struct ReplayError {
+    pub step: usize,
+    pub source: GameError,
+}
+
+/// Rebuild every intermediate state of a game from its seed and the ordered player actions.
+///
+/// Rolls are drawn exactly as the server draws them, so the final state matches the
+/// recorded game, including the revealed seed once the game is over.
+pub fn replay(
+    game_id: GameId,
+    host_id: PlayerId,
+    settings: GameSettings,
+    server_seed: &ServerSeed,
+    actions: &[PlayerAction],
+) -> Result<Vec<ReplayStep>, ReplayError> {
+    let mut game = Game::restore(game_id, host_id, settings);
+    game.commit_server_seed(server_seed.commitment())
+        .map_err(|source| ReplayError { step: 0, source })?;
+    // The opening events come before any action.
+    game.take_uncommitted_events();
+
+    let mut steps = Vec::with_capacity(actions.len());
+    for (step, action) in actions.iter().enumerate() {
+        apply(&mut game, server_seed, action.clone()).map_err(|source| ReplayError { step, source })?;
+        let events = game
+            .take_uncommitted_events()
+            .into_iter()
+            .map(|recorded| recorded.event)
+            .collect();
+        steps.push(ReplayStep { action: action.clone(), events, state: game.clone() });
+    }
+    Ok(steps)
+}
+
+/// Replay a recorded game from its own action log.
+pub fn replay_game(recorded: &Game, server_seed: &ServerSeed) -> Result<Vec<ReplayStep>, ReplayError> {
+    replay(
+        recorded.get_id(),
+        recorded.get_created_by(),
+        recorded.get_settings().clone(),
+        server_seed,
+        recorded.get_actions(),
+    )
+}
+
+fn apply(game: &mut Game, server_seed: &ServerSeed, action: PlayerAction) -> Result<(), GameError> {
+    match action {
+        PlayerAction::Join { player_id } => game.join(player_id)?,
+        PlayerAction::ClientSeed { player_id, client_seed } => game.set_client_seed(player_id, client_seed)?,
+        PlayerAction::Ready { player_id, ready } => game.set_ready(player_id, ready)?,
+        PlayerAction::Team { assigned_by, player_id, team_id } => game.assign_team(assigned_by, player_id, team_id)?,
+        PlayerAction::Start { player_id, started_at } => {
+            let mut roller = FairRoller::new(server_seed.clone(), game.get_fairness());
+            game.start_at(player_id, &mut roller, started_at)?;
+        }
+        PlayerAction::Leave { player_id } => game.leave(player_id)?,
+        PlayerAction::Kick { host_id, player_id } => game.kick(host_id, player_id)?,
+        PlayerAction::Disconnect { player_id, reconnect_deadline } => {
+            game.pause_game_until(player_id, reconnect_deadline)?
+        }
+        PlayerAction::Reconnect { player_id, reconnected_at } => game.reconnect_at(player_id, reconnected_at)?,
+        PlayerAction::Forfeit { player_id, forfeited_at } => {
+            game.forfeit_at(player_id, forfeited_at)?;
+        }
+        PlayerAction::Rematch { player_id } => {
+            game.accept_rematch(player_id)?;
+        }
+        PlayerAction::Roll { player_id, rolled_at } => {
+            let mut roller = FairRoller::new(server_seed.clone(), game.get_fairness());
+            game.roll_at(player_id, &mut roller, rolled_at)?;
+        }
+        PlayerAction::SpectatorLimit { host_id, max_spectators } => game.set_max_spectators(host_id, max_spectators)?,
+        PlayerAction::MatchLinked { match_id } => game.set_match_id(match_id),
+        PlayerAction::TournamentLinked { tournament_id } => game.set_tournament_id(tournament_id),
+        PlayerAction::RematchCreated { game_id } => game.set_rematch_id(game_id),
+        PlayerAction::RematchCancelled { game_id } => game.cancel_rematch(game_id)?,
+        PlayerAction::RematchOf { game_id } => game.set_rematch_of(game_id),
+    }
+    // The server reveals the seed as soon as a game is over.
+    if game.is_finished() && game.get_fairness().get_revealed_server_seed().is_none() {
+        game.reveal_server_seed(server_seed)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{settings::TurnOrder, MatchId};
+
+    fn player(n: u8) -> PlayerId {
+        format!("00000000-0000-0000-0000-{:012}", n).parse().unwrap()
+    }
+
+    /// Fixed players, so the only thing that differs between games with the same seed is the clock.
+    fn play_recorded_game(server_seed: &ServerSeed) -> Game {
+        let host_id = player(1);
+        let settings = GameSettings { max_players: 3, turn_order: TurnOrder::RollOff, ..Default::default() };
+        let mut game = Game::with_settings(host_id, settings);
+        game.commit_server_seed(server_seed.commitment()).unwrap();
+        game.set_client_seed(host_id, "host".into()).unwrap();
+
+        let drifter = player(2);
+        game.join(drifter).unwrap();
+        game.leave(drifter).unwrap();
+
+        let guests = [player(3), player(4)];
+        for guest in guests {
+            game.join(guest).unwrap();
+            game.set_ready(guest, true).unwrap();
+        }
+        game.set_client_seed(guests[0], "guest".into()).unwrap();
+        let mut roller = FairRoller::new(server_seed.clone(), game.get_fairness());
+        game.start(host_id, &mut roller).unwrap();
+        game.pause_game(guests[1]).unwrap();
+        game.reconnect(guests[1]).unwrap();
+
+        while !game.is_finished() {
+            let current = *game.get_current_player().unwrap();
+            let mut roller = FairRoller::new(server_seed.clone(), game.get_fairness());
+            game.roll(current, &mut roller).unwrap();
+        }
+        game.reveal_server_seed(server_seed).unwrap();
+        game.set_match_id(MatchId::new());
+        game.set_max_spectators(host_id, 0).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_replay_reproduces_recorded_game() {
+        let server_seed = ServerSeed::generate();
+        let recorded = play_recorded_game(&server_seed);
+
+        let steps = replay_game(&recorded, &server_seed).unwrap();
+
+        assert_eq!(steps.len(), recorded.get_actions().len());
+        assert_eq!(steps.last().unwrap().state, recorded);
+        // The roll-off draws are checked alongside the game's rolls.
+        let report = recorded.verify_rolls().unwrap();
+        assert!(report.valid);
+        assert!(report.rolls.len() > recorded.get_history().len());
+
+        // Every event after the opening `Created` and seed commitment comes back, in order.
+        let recorded_events: Vec<GameEvent> = recorded
+            .clone()
+            .take_uncommitted_events()
+            .into_iter()
+            .skip(2)
+            .map(|r| r.event)
+            .collect();
+        let replayed_events: Vec<GameEvent> = steps.into_iter().flat_map(|s| s.events).collect();
+        assert_eq!(replayed_events, recorded_events);
+    }
+
+    #[test]
+    fn test_replay_with_wrong_seed_diverges() {
+        let server_seed = ServerSeed::from("recorded".to_string());
+        let recorded = play_recorded_game(&server_seed);
+
+        let result = replay_game(&recorded, &ServerSeed::from("forged".to_string()));
+
+        // The forged seed draws different rolls, so play moves on differently and the recorded roll at
+        // step 11 comes out of turn.
+        assert_eq!(result, Err(ReplayError { step: 11, source: GameError::NotYourTurn }));
+        assert!(matches!(recorded.get_actions()[11], PlayerAction::Roll { .. }));
+    }
+
+    #[test]
+    fn test_replay_keeps_forfeit_times() {
+        let server_seed = ServerSeed::from("recorded".to_string());
+        let host_id = player(1);
+        let settings = GameSettings { max_players: 3, ..Default::default() };
+        let mut game = Game::with_settings(host_id, settings);
+        game.commit_server_seed(server_seed.commitment()).unwrap();
+        for guest in [player(2), player(3)] {
+            game.join(guest).unwrap();
+            game.set_ready(guest, true).unwrap();
+        }
+        let mut roller = FairRoller::new(server_seed.clone(), game.get_fairness());
+        game.start(host_id, &mut roller).unwrap();
+        // Long enough ago that a replay stamping the forfeit with the current time cannot match.
+        let forfeited_at = "2020-01-01T00:00:00Z".parse().unwrap();
+        let recorded_events = game.forfeit_at(host_id, forfeited_at).unwrap();
+
+        let steps = replay_game(&game, &server_seed).unwrap();
+
+        let forfeit = steps.last().unwrap();
+        assert_eq!(
+            forfeit.action,
+            PlayerAction::Forfeit { player_id: host_id, forfeited_at }
+        );
+        assert_eq!(forfeit.events, recorded_events);
+        assert_eq!(forfeit.state, game);
+    }
+}
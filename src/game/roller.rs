@@ -1,5 +1,13 @@
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
 pub trait Roller {
     fn roll_in_range(&mut self, max: u32) -> u32;
+
+    /// Roll in `min..=max` by offsetting a roll in `1..=(max - min + 1)`.
+    fn roll_between(&mut self, min: u32, max: u32) -> u32 {
+        min - 1 + self.roll_in_range(max - min + 1)
+    }
 }
 
 #[derive(Default)]
@@ -13,11 +21,59 @@ impl ThreadRngRoller {
 
 impl Roller for ThreadRngRoller {
     fn roll_in_range(&mut self, max: u32) -> u32 {
-        use rand::Rng;
         rand::rng().random_range(1..=max)
     }
 }
 
+/// A deterministic `Roller` backed by ChaCha20 keyed with a per-game seed.
+/// Roll `n` of a game is drawn from stream `n`, so any roll can be reproduced
+/// from the seed and its nonce alone.
+pub struct SeededRoller {
+    seed: [u8; 32],
+    nonce: u64,
+}
+
+impl SeededRoller {
+    pub fn new(seed: [u8; 32], nonce: u64) -> Self {
+        Self { seed, nonce }
+    }
+
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// The first two outputs of the next roll's stream: everything `roll_in_range` draws, so
+    /// `sample_range` turns them into the same roll. Code that cannot run ChaCha, like the Redis
+    /// roll script, is handed these instead.
+    pub fn draws(&self) -> [u32; 2] {
+        let mut rng = ChaCha20Rng::from_seed(self.seed);
+        rng.set_stream(self.nonce);
+        [rng.next_u32(), rng.next_u32()]
+    }
+}
+
+/// A roll in `1..=max` from two outputs of the generator, as `rand` samples a `u32` range: a
+/// widening multiply, with the second output settling the few results that would be biased.
+pub fn sample_range([first, second]: [u32; 2], max: u32) -> u32 {
+    let range = max.max(1) as u64;
+    let product = first as u64 * range;
+    let (mut result, low) = (product >> 32, product as u32);
+    if low as u64 > (1 << 32) - range {
+        let carry = (second as u64 * range) >> 32;
+        result += u64::from(low as u64 + carry > u32::MAX as u64);
+    }
+    result as u32 + 1
+}
+
+impl Roller for SeededRoller {
+    fn roll_in_range(&mut self, max: u32) -> u32 {
+        let mut rng = ChaCha20Rng::from_seed(self.seed);
+        rng.set_stream(self.nonce);
+        self.nonce += 1;
+        rng.random_range(1..=max.max(1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -29,7 +85,7 @@ mod tests {
         // Test roll_in_range returns a value between 1 and max (inclusive)
         for _ in 0..100 {
             let roll = roller.roll_in_range(10);
-            assert!(roll >= 1 && roll <= 10);
+            assert!((1..=10).contains(&roll));
         }
 
         // Test roll_in_range returns the same value when max is 1
@@ -37,4 +93,49 @@ mod tests {
         let roll = roller.roll_in_range(1);
         assert_eq!(roll, 1);
     }
+
+    #[test]
+    fn test_seeded_roller_is_reproducible() {
+        let mut first = SeededRoller::new([7; 32], 0);
+        let mut second = SeededRoller::new([7; 32], 0);
+
+        let a: Vec<u32> = (0..50).map(|_| first.roll_in_range(1000)).collect();
+        let b: Vec<u32> = (0..50).map(|_| second.roll_in_range(1000)).collect();
+        assert_eq!(a, b);
+        assert!(a.iter().all(|r| (1..=1000).contains(r)));
+        assert_eq!(first.nonce(), 50);
+
+        // Starting at a later nonce lands on the same roll as stepping there.
+        let mut resumed = SeededRoller::new([7; 32], 10);
+        assert_eq!(resumed.roll_in_range(1000), a[10]);
+
+        let mut other = SeededRoller::new([8; 32], 0);
+        let c: Vec<u32> = (0..50).map(|_| other.roll_in_range(1000)).collect();
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_sample_range_matches_the_roller() {
+        for nonce in 0..500 {
+            for max in [1, 2, 3, 7, 100, 1000, 999_983, u32::MAX / 3, u32::MAX] {
+                let roller = SeededRoller::new([3; 32], nonce);
+                let expected = SeededRoller::new([3; 32], nonce).roll_in_range(max);
+                assert_eq!(
+                    sample_range(roller.draws(), max),
+                    expected,
+                    "nonce {} max {}",
+                    nonce,
+                    max
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeded_roller_pinned_sequence() {
+        // Pinned so a change to the derivation breaks recorded games loudly.
+        let mut roller = SeededRoller::new([0; 32], 0);
+        let rolls: Vec<u32> = (0..5).map(|_| roller.roll_in_range(1000)).collect();
+        assert_eq!(rolls, vec![680, 840, 718, 939, 850]);
+    }
 }
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+use super::types::GameError;
+
+/// The rules of a death-roll variant. Ranges are inclusive `(min, max)` pairs.
+pub trait GameRules {
+    /// The range the first roll of each round is made in.
+    fn opening_range(&self, starting_max: u32) -> (u32, u32);
+
+    /// Whether rolling `value` in `range` knocks the roller out.
+    fn is_loss(&self, range: (u32, u32), value: u32) -> bool;
+
+    /// The range for the next roll after a surviving `value`.
+    fn next_range(&self, range: (u32, u32), value: u32) -> (u32, u32);
+
+    /// The seat that rolls after `current`. `active[i]` is false for eliminated seats.
+    fn next_turn(&self, current: usize, active: &[bool]) -> usize {
+        (1..=active.len())
+            .map(|step| (current + step) % active.len())
+            .find(|&seat| active[seat])
+            .unwrap_or(current)
+    }
+
+    /// Reject settings this variant cannot be played with.
+    fn validate(&self, _starting_max: u32) -> Result<(), GameError> {
+        Ok(())
+    }
+}
+
+/// Roll a 1 and you lose; otherwise your roll is the next player's max.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Classic;
+
+impl GameRules for Classic {
+    fn opening_range(&self, starting_max: u32) -> (u32, u32) {
+        (1, starting_max)
+    }
+
+    fn is_loss(&self, _range: (u32, u32), value: u32) -> bool {
+        value == 1
+    }
+
+    fn next_range(&self, _range: (u32, u32), value: u32) -> (u32, u32) {
+        (1, value)
+    }
+}
+
+/// Like classic, but any roll at or below `lose_at` loses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Threshold {
+    pub lose_at: u32,
+}
+
+impl GameRules for Threshold {
+    fn opening_range(&self, starting_max: u32) -> (u32, u32) {
+        (1, starting_max)
+    }
+
+    fn is_loss(&self, _range: (u32, u32), value: u32) -> bool {
+        value <= self.lose_at
+    }
+
+    fn next_range(&self, _range: (u32, u32), value: u32) -> (u32, u32) {
+        (1, value)
+    }
+
+    fn validate(&self, starting_max: u32) -> Result<(), GameError> {
+        if self.lose_at == 0 || self.lose_at >= starting_max {
+            return Err(GameError::InvalidThreshold { max: starting_max.saturating_sub(1) });
+        }
+        Ok(())
+    }
+}
+
+/// Climb instead of fall: your roll is the next player's min, and hitting the ceiling loses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reverse;
+
+impl GameRules for Reverse {
+    fn opening_range(&self, starting_max: u32) -> (u32, u32) {
+        (1, starting_max)
+    }
+
+    fn is_loss(&self, (_, ceiling): (u32, u32), value: u32) -> bool {
+        value == ceiling
+    }
+
+    fn next_range(&self, (_, ceiling): (u32, u32), value: u32) -> (u32, u32) {
+        (value, ceiling)
+    }
+}
+
+/// Classic, except the range at least halves on every roll.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Halving;
+
+impl GameRules for Halving {
+    fn opening_range(&self, starting_max: u32) -> (u32, u32) {
+        (1, starting_max)
+    }
+
+    fn is_loss(&self, _range: (u32, u32), value: u32) -> bool {
+        value == 1
+    }
+
+    fn next_range(&self, (_, max): (u32, u32), value: u32) -> (u32, u32) {
+        (1, value.min(max / 2).max(1))
+    }
+}
+
+/// The built-in rule sets a table can pick, in a form that can be stored with the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RuleSet {
+    #[default]
+    Classic,
+    Threshold(Threshold),
+    Reverse,
+    Halving,
+}
+
+impl RuleSet {
+    pub fn rules(&self) -> &dyn GameRules {
+        match self {
+            RuleSet::Classic => &Classic,
+            RuleSet::Threshold(rules) => rules,
+            RuleSet::Reverse => &Reverse,
+            RuleSet::Halving => &Halving,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic_rules() {
+        let rules = RuleSet::Classic.rules();
+        assert_eq!(rules.opening_range(1000), (1, 1000));
+        assert!(rules.is_loss((1, 1000), 1));
+        assert!(!rules.is_loss((1, 1000), 2));
+        assert_eq!(rules.next_range((1, 1000), 400), (1, 400));
+    }
+
+    #[test]
+    fn test_threshold_rules() {
+        let rule_set = RuleSet::Threshold(Threshold { lose_at: 10 });
+        let rules = rule_set.rules();
+        assert!(rules.is_loss((1, 1000), 10));
+        assert!(!rules.is_loss((1, 1000), 11));
+        assert_eq!(rules.next_range((1, 1000), 11), (1, 11));
+
+        assert!(rules.validate(11).is_ok());
+        assert_eq!(rules.validate(10), Err(GameError::InvalidThreshold { max: 9 }));
+        assert!(Threshold { lose_at: 0 }.validate(1000).is_err());
+    }
+
+    #[test]
+    fn test_reverse_rules_climb_to_ceiling() {
+        let rules = RuleSet::Reverse.rules();
+        assert_eq!(rules.next_range((1, 100), 40), (40, 100));
+        assert_eq!(rules.next_range((40, 100), 90), (90, 100));
+        assert!(rules.is_loss((90, 100), 100));
+        assert!(!rules.is_loss((90, 100), 90));
+    }
+
+    #[test]
+    fn test_halving_rules_at_least_halve() {
+        let rules = RuleSet::Halving.rules();
+        assert_eq!(rules.next_range((1, 1000), 900), (1, 500));
+        assert_eq!(rules.next_range((1, 1000), 100), (1, 100));
+        assert_eq!(rules.next_range((1, 3), 3), (1, 1));
+    }
+
+    #[test]
+    fn test_next_turn_skips_inactive_seats() {
+        let rules = RuleSet::Classic.rules();
+        assert_eq!(rules.next_turn(0, &[true, false, true]), 2);
+        assert_eq!(rules.next_turn(2, &[true, false, true]), 0);
+    }
+
+    #[test]
+    fn test_rule_set_serialization() {
+        let json = serde_json::to_value(RuleSet::Threshold(Threshold { lose_at: 5 })).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "THRESHOLD", "lose_at": 5 }));
+        let parsed: RuleSet = serde_json::from_value(serde_json::json!({ "type": "HALVING" })).unwrap();
+        assert_eq!(parsed, RuleSet::Halving);
+    }
+}
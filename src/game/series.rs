@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::settings::GameSettings;
+use super::types::{GameError, GameId, MatchId, PlayerId};
+
+pub const DEFAULT_BEST_OF: u32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MatchStatus {
+    InProgress,
+    Finished(PlayerId),
+    /// Called off without a winner because the next game could not be started.
+    Voided,
+}
+
+/// A best-of-N series: a sequence of games between the same players.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Match {
+    id: MatchId,
+    players: Vec<PlayerId>,
+    best_of: u32,
+    settings: GameSettings,
+    wins: BTreeMap<PlayerId, u32>,
+    games: Vec<GameId>,
+    status: MatchStatus,
+    // Saves so far; a save only goes through from the version it was loaded at.
+    #[serde(default)]
+    version: u64,
+}
+
+impl Match {
+    #[tracing::instrument]
+    pub fn new(players: Vec<PlayerId>, best_of: u32, settings: GameSettings) -> Result<Self, GameError> {
+        if best_of.is_multiple_of(2) {
+            return Err(GameError::InvalidBestOf);
+        }
+        if settings.teams > 0 {
+            return Err(GameError::TeamsInMatch);
+        }
+        let mut unique = players.clone();
+        unique.sort();
+        unique.dedup();
+        if players.len() < 2 || unique.len() != players.len() {
+            return Err(GameError::NotEnoughPlayers);
+        }
+
+        let wins = players.iter().map(|p| (*p, 0)).collect();
+        Ok(Self {
+            id: MatchId::new(),
+            settings: GameSettings { max_players: players.len(), ..settings },
+            players,
+            best_of,
+            wins,
+            games: vec![],
+            status: MatchStatus::InProgress,
+            version: 0,
+        })
+    }
+
+    // Getters
+    pub fn get_id(&self) -> MatchId {
+        self.id
+    }
+
+    pub fn get_players(&self) -> &[PlayerId] {
+        &self.players
+    }
+
+    pub fn get_best_of(&self) -> u32 {
+        self.best_of
+    }
+
+    pub fn get_settings(&self) -> &GameSettings {
+        &self.settings
+    }
+
+    pub fn get_wins(&self) -> &BTreeMap<PlayerId, u32> {
+        &self.wins
+    }
+
+    pub fn get_games(&self) -> &[GameId] {
+        &self.games
+    }
+
+    pub fn get_current_game(&self) -> Option<GameId> {
+        self.games.last().copied()
+    }
+
+    pub fn get_status(&self) -> &MatchStatus {
+        &self.status
+    }
+
+    pub fn get_version(&self) -> u64 {
+        self.version
+    }
+
+    /// Move the copy in hand on to the version the repository just stored.
+    pub(crate) fn bump_version(&mut self) {
+        self.version += 1;
+    }
+
+    /// Wins needed to take the series.
+    pub fn win_threshold(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    /// Seating for the next game; the first seat rolls first and rotates every game.
+    pub fn next_turn_order(&self) -> Vec<PlayerId> {
+        let mut order = self.players.clone();
+        order.rotate_left(self.games.len() % self.players.len());
+        order
+    }
+
+    //  --- Public mutators ---
+    pub fn add_game(&mut self, game_id: GameId) -> Result<(), GameError> {
+        if self.status != MatchStatus::InProgress {
+            return Err(GameError::GameFinished);
+        }
+        self.games.push(game_id);
+        Ok(())
+    }
+
+    /// Call off a series that cannot go on. Nobody wins it.
+    pub fn void(&mut self) -> Result<(), GameError> {
+        if self.status != MatchStatus::InProgress {
+            return Err(GameError::GameFinished);
+        }
+        self.status = MatchStatus::Voided;
+        Ok(())
+    }
+
+    /// Credit the winner of the current game. Returns the series winner once someone reaches the threshold.
+    #[tracing::instrument(skip(self))]
+    pub fn record_result(&mut self, game_id: GameId, winner_id: PlayerId) -> Result<Option<PlayerId>, GameError> {
+        if self.status != MatchStatus::InProgress {
+            return Err(GameError::GameFinished);
+        }
+        if self.get_current_game() != Some(game_id) {
+            return Err(GameError::NotCurrentMatchGame { game_id });
+        }
+        let wins = self.wins.get_mut(&winner_id).ok_or(GameError::NotAParticipant)?;
+        *wins += 1;
+
+        if *wins >= self.win_threshold() {
+            self.status = MatchStatus::Finished(winner_id);
+            return Ok(Some(winner_id));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_match(best_of: u32) -> (Match, PlayerId, PlayerId) {
+        let (a, b) = (PlayerId::new(), PlayerId::new());
+        (Match::new(vec![a, b], best_of, GameSettings::default()).unwrap(), a, b)
+    }
+
+    #[test]
+    fn test_new_match_validation() {
+        let a = PlayerId::new();
+        assert_eq!(
+            Match::new(vec![a, PlayerId::new()], 4, GameSettings::default()),
+            Err(GameError::InvalidBestOf)
+        );
+        assert_eq!(
+            Match::new(vec![a, a], 3, GameSettings::default()),
+            Err(GameError::NotEnoughPlayers)
+        );
+        assert_eq!(
+            Match::new(vec![a], 3, GameSettings::default()),
+            Err(GameError::NotEnoughPlayers)
+        );
+        assert_eq!(
+            Match::new(
+                vec![a, PlayerId::new()],
+                3,
+                GameSettings { teams: 2, ..Default::default() }
+            ),
+            Err(GameError::TeamsInMatch)
+        );
+    }
+
+    #[test]
+    fn test_turn_order_alternates() {
+        let (mut series, a, b) = setup_match(3);
+
+        assert_eq!(series.next_turn_order(), vec![a, b]);
+        series.add_game(GameId::new()).unwrap();
+        assert_eq!(series.next_turn_order(), vec![b, a]);
+        series.add_game(GameId::new()).unwrap();
+        assert_eq!(series.next_turn_order(), vec![a, b]);
+    }
+
+    #[test]
+    fn test_series_ends_at_threshold() {
+        let (mut series, a, b) = setup_match(5);
+        assert_eq!(series.win_threshold(), 3);
+
+        for winner in [a, b, a, b] {
+            let game_id = GameId::new();
+            series.add_game(game_id).unwrap();
+            assert_eq!(series.record_result(game_id, winner), Ok(None));
+        }
+
+        let game_id = GameId::new();
+        series.add_game(game_id).unwrap();
+        assert_eq!(series.record_result(game_id, b), Ok(Some(b)));
+        assert_eq!(*series.get_status(), MatchStatus::Finished(b));
+        assert_eq!(series.get_wins()[&a], 2);
+        assert_eq!(series.get_wins()[&b], 3);
+
+        assert_eq!(series.add_game(GameId::new()), Err(GameError::GameFinished));
+    }
+
+    #[test]
+    fn test_results_only_count_for_current_game() {
+        let (mut series, a, _) = setup_match(3);
+        let first = GameId::new();
+        series.add_game(first).unwrap();
+        series.add_game(GameId::new()).unwrap();
+
+        assert_eq!(
+            series.record_result(first, a),
+            Err(GameError::NotCurrentMatchGame { game_id: first })
+        );
+    }
+
+    #[test]
+    fn test_voided_series_takes_no_more_games() {
+        let (mut series, a, _) = setup_match(3);
+        let game_id = GameId::new();
+        series.add_game(game_id).unwrap();
+
+        assert_eq!(series.void(), Ok(()));
+        assert_eq!(*series.get_status(), MatchStatus::Voided);
+        assert_eq!(series.void(), Err(GameError::GameFinished));
+        assert_eq!(series.record_result(game_id, a), Err(GameError::GameFinished));
+        assert_eq!(series.add_game(GameId::new()), Err(GameError::GameFinished));
+    }
+}
@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+
+use super::rules::RuleSet;
+use super::types::GameError;
+use crate::config::GameConfig;
+
+pub const DEFAULT_MAX_PLAYERS: usize = 2;
+pub const DEFAULT_STARTING_MAX: u32 = 1000;
+pub const MIN_PLAYERS: usize = 2;
+pub const DEFAULT_TURN_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_RECONNECT_GRACE_SECS: u64 = 60;
+pub const DEFAULT_MAX_SPECTATORS: usize = 50;
+
+/// What the server does for a player who lets their turn timer run out.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimeoutAction {
+    /// Roll on the player's behalf.
+    #[default]
+    AutoRoll,
+    /// Knock the player out of the game.
+    Forfeit,
+}
+
+/// How the seating is decided when the host starts the game.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TurnOrder {
+    /// Keep the join order, so the host rolls first.
+    #[default]
+    HostFirst,
+    /// Shuffle the seats.
+    Random,
+    /// Everyone rolls once; the highest roll goes first and ties roll again.
+    RollOff,
+}
+
+/// Per-table options chosen by the host when the game is created.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GameSettings {
+    pub max_players: usize,
+    /// The opening roll is `1..=starting_max`.
+    pub starting_max: u32,
+    /// Gold every player stakes; the winner takes the pot. Zero means no wager.
+    #[serde(default)]
+    pub wager: u64,
+    /// Seconds each player has to roll. Zero turns the timer off.
+    #[serde(default = "default_turn_timeout_secs")]
+    pub turn_timeout_secs: u64,
+    #[serde(default)]
+    pub on_timeout: TimeoutAction,
+    /// Seconds a disconnected player has to come back before they forfeit. Zero waits forever.
+    #[serde(default = "default_reconnect_grace_secs")]
+    pub reconnect_grace_secs: u64,
+    #[serde(default)]
+    pub rules: RuleSet,
+    #[serde(default)]
+    pub turn_order: TurnOrder,
+    /// How many people may watch at once. Zero turns spectating off.
+    #[serde(default = "default_max_spectators")]
+    pub max_spectators: usize,
+    /// How many teams play against each other. Zero is every player for themselves.
+    #[serde(default)]
+    pub teams: usize,
+}
+
+// Settings saved without these fields get the same values as a new table, not zero, which would turn
+// the feature off.
+fn default_turn_timeout_secs() -> u64 {
+    DEFAULT_TURN_TIMEOUT_SECS
+}
+
+fn default_reconnect_grace_secs() -> u64 {
+    DEFAULT_RECONNECT_GRACE_SECS
+}
+
+fn default_max_spectators() -> usize {
+    DEFAULT_MAX_SPECTATORS
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            max_players: DEFAULT_MAX_PLAYERS,
+            starting_max: DEFAULT_STARTING_MAX,
+            wager: 0,
+            turn_timeout_secs: DEFAULT_TURN_TIMEOUT_SECS,
+            on_timeout: TimeoutAction::default(),
+            reconnect_grace_secs: DEFAULT_RECONNECT_GRACE_SECS,
+            rules: RuleSet::default(),
+            turn_order: TurnOrder::default(),
+            max_spectators: DEFAULT_MAX_SPECTATORS,
+            teams: 0,
+        }
+    }
+}
+
+impl GameSettings {
+    /// Check the requested settings against the server-side limits.
+    pub fn validate(&self, limits: &GameConfig) -> Result<(), GameError> {
+        if self.max_players < MIN_PLAYERS || self.max_players > limits.max_players {
+            return Err(GameError::InvalidPlayerCap { min: MIN_PLAYERS, max: limits.max_players });
+        }
+        if self.starting_max < limits.min_starting_max || self.starting_max > limits.max_starting_max {
+            return Err(GameError::InvalidStartingMax { min: limits.min_starting_max, max: limits.max_starting_max });
+        }
+        let timeout = self.turn_timeout_secs;
+        if timeout != 0 && (timeout < limits.min_turn_timeout_secs || timeout > limits.max_turn_timeout_secs) {
+            return Err(GameError::InvalidTurnTimeout {
+                min: limits.min_turn_timeout_secs,
+                max: limits.max_turn_timeout_secs,
+            });
+        }
+        if self.reconnect_grace_secs > limits.max_reconnect_grace_secs {
+            return Err(GameError::InvalidReconnectGrace { max: limits.max_reconnect_grace_secs });
+        }
+        if self.max_spectators > limits.max_spectators {
+            return Err(GameError::InvalidSpectatorLimit { max: limits.max_spectators });
+        }
+        if self.wager > limits.max_wager {
+            return Err(GameError::InvalidWager { max: limits.max_wager });
+        }
+        if self.teams == 1 || self.teams > self.max_players {
+            return Err(GameError::InvalidTeamCount { max: self.max_players });
+        }
+        self.rules.rules().validate(self.starting_max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(max_players: usize, starting_max: u32) -> GameSettings {
+        GameSettings { max_players, starting_max, ..Default::default() }
+    }
+
+    #[test]
+    fn test_missing_fields_take_the_defaults() {
+        let settings: GameSettings = serde_json::from_str(r#"{"max_players":2,"starting_max":1000}"#).unwrap();
+        assert_eq!(settings, GameSettings::default());
+    }
+
+    #[test]
+    fn test_validate_player_cap() {
+        let limits = GameConfig::default();
+
+        assert!(settings(2, 1000).validate(&limits).is_ok());
+        assert!(settings(10, 1000).validate(&limits).is_ok());
+        assert_eq!(
+            settings(1, 1000).validate(&limits),
+            Err(GameError::InvalidPlayerCap { min: 2, max: 10 })
+        );
+        assert_eq!(
+            settings(11, 1000).validate(&limits),
+            Err(GameError::InvalidPlayerCap { min: 2, max: 10 })
+        );
+    }
+
+    #[test]
+    fn test_validate_starting_max() {
+        let limits = GameConfig::default();
+
+        assert!(settings(2, 100).validate(&limits).is_ok());
+        assert!(settings(2, 1_000_000).validate(&limits).is_ok());
+        assert_eq!(
+            settings(2, 1).validate(&limits),
+            Err(GameError::InvalidStartingMax { min: 2, max: 1_000_000 })
+        );
+        assert_eq!(
+            settings(2, 1_000_001).validate(&limits),
+            Err(GameError::InvalidStartingMax { min: 2, max: 1_000_000 })
+        );
+    }
+
+    #[test]
+    fn test_validate_turn_timeout() {
+        let limits = GameConfig::default();
+        let timeout = |turn_timeout_secs| GameSettings { turn_timeout_secs, ..Default::default() };
+
+        assert!(timeout(0).validate(&limits).is_ok());
+        assert!(timeout(30).validate(&limits).is_ok());
+        assert_eq!(
+            timeout(1).validate(&limits),
+            Err(GameError::InvalidTurnTimeout { min: 5, max: 600 })
+        );
+        assert_eq!(
+            timeout(601).validate(&limits),
+            Err(GameError::InvalidTurnTimeout { min: 5, max: 600 })
+        );
+    }
+
+    #[test]
+    fn test_validate_spectator_limit() {
+        let limits = GameConfig::default();
+        let spectators = |max_spectators| GameSettings { max_spectators, ..Default::default() };
+
+        assert!(spectators(0).validate(&limits).is_ok());
+        assert!(spectators(500).validate(&limits).is_ok());
+        assert_eq!(
+            spectators(501).validate(&limits),
+            Err(GameError::InvalidSpectatorLimit { max: 500 })
+        );
+    }
+
+    #[test]
+    fn test_validate_team_count() {
+        let limits = GameConfig::default();
+        let teams = |teams| GameSettings { max_players: 4, teams, ..Default::default() };
+
+        assert!(teams(0).validate(&limits).is_ok());
+        assert!(teams(2).validate(&limits).is_ok());
+        assert!(teams(4).validate(&limits).is_ok());
+        assert_eq!(teams(1).validate(&limits), Err(GameError::InvalidTeamCount { max: 4 }));
+        assert_eq!(teams(5).validate(&limits), Err(GameError::InvalidTeamCount { max: 4 }));
+    }
+
+    #[test]
+    fn test_validate_reconnect_grace() {
+        let limits = GameConfig::default();
+        let grace = |reconnect_grace_secs| GameSettings { reconnect_grace_secs, ..Default::default() };
+
+        assert!(grace(0).validate(&limits).is_ok());
+        assert!(grace(600).validate(&limits).is_ok());
+        assert_eq!(
+            grace(601).validate(&limits),
+            Err(GameError::InvalidReconnectGrace { max: 600 })
+        );
+    }
+
+    #[test]
+    fn test_validate_wager() {
+        let limits = GameConfig::default();
+        let wager = |wager| GameSettings { wager, ..Default::default() };
+
+        assert!(wager(0).validate(&limits).is_ok());
+        assert!(wager(1_000_000_000).validate(&limits).is_ok());
+        assert_eq!(
+            wager(1_000_000_001).validate(&limits),
+            Err(GameError::InvalidWager { max: 1_000_000_000 })
+        );
+        assert_eq!(
+            wager(u64::MAX).validate(&limits),
+            Err(GameError::InvalidWager { max: 1_000_000_000 })
+        );
+    }
+}
@@ -1,5 +1,12 @@
 use super::*;
-use crate::game::{roller::Roller, types::GameEvent};
+use crate::game::{
+    roller::{Roller, SeededRoller},
+    rules::{RuleSet, Threshold},
+    settings::TurnOrder,
+    types::{GameEvent, RecordedEvent, RollRecord},
+    GameCommand, GameSettings, Team, TeamId,
+};
+use chrono::{Duration, Utc};
 
 struct MockRoller {
     value_to_return: u32,
@@ -9,6 +16,14 @@ impl Roller for MockRoller {
     fn roll_in_range(&mut self, _max: u32) -> u32 {
         self.value_to_return
     }
+
+    fn roll_between(&mut self, _min: u32, _max: u32) -> u32 {
+        self.value_to_return
+    }
+}
+
+fn invalid(from: GameStatus, action: GameCommand) -> GameError {
+    GameError::InvalidTransition { from, action }
 }
 
 fn setup_game() -> (Game, PlayerId) {
@@ -16,6 +31,16 @@ fn setup_game() -> (Game, PlayerId) {
     (Game::new(host_id), host_id)
 }
 
+/// Join `guests`, ready them up and have the host start the game.
+fn seat(game: &mut Game, guests: &[PlayerId]) {
+    for guest_id in guests {
+        game.join(*guest_id).unwrap();
+        game.set_ready(*guest_id, true).unwrap();
+    }
+    game.start(game.get_host(), &mut MockRoller { value_to_return: 1 })
+        .unwrap();
+}
+
 #[test]
 fn test_new_game_initial_state() {
     let (game, host_id) = setup_game();
@@ -27,25 +52,206 @@ fn test_new_game_initial_state() {
 
 #[test]
 fn test_join_game() {
-    let (mut game, host_id) = setup_game();
+    let (mut game, _host_id) = setup_game();
     let guest_id = PlayerId::new();
 
     // 1. Join successfully
     assert!(game.join(guest_id).is_ok());
     assert_eq!(game.get_players().len(), 2);
-    assert_eq!(*game.get_status(), GameStatus::InProgress);
+    assert_eq!(*game.get_status(), GameStatus::WaitingForPlayers);
 
     // 2. Join failed (Game Full)
     let intruder_id = PlayerId::new();
     let err = game.join(intruder_id);
     assert_eq!(err, Err(GameError::GameFull));
+
+    // 3. Join failed (Already In)
+    assert_eq!(game.join(guest_id), Err(GameError::AlreadyJoined));
+    assert_eq!(game.get_players().len(), 2);
 }
 
 #[test]
-fn test_roll_flow() {
+fn test_host_starts_once_everyone_is_ready() {
     let (mut game, host_id) = setup_game();
     let guest_id = PlayerId::new();
+    let mut roller = MockRoller { value_to_return: 1 };
+
+    assert_eq!(game.start(host_id, &mut roller), Err(GameError::NotEnoughPlayers));
     game.join(guest_id).unwrap();
+    assert_eq!(game.start(host_id, &mut roller), Err(GameError::PlayersNotReady));
+
+    game.set_ready(guest_id, true).unwrap();
+    assert!(game.all_ready());
+    assert_eq!(game.start(guest_id, &mut roller), Err(GameError::NotHost));
+    assert_eq!(game.get_turn_deadline(), None);
+
+    game.start(host_id, &mut roller).unwrap();
+    assert_eq!(*game.get_status(), GameStatus::InProgress);
+    assert!(game.get_turn_deadline().is_some());
+    assert_eq!(
+        game.start(host_id, &mut roller),
+        Err(invalid(GameStatus::InProgress, GameCommand::Start))
+    );
+    assert_eq!(
+        game.set_ready(guest_id, false),
+        Err(invalid(GameStatus::InProgress, GameCommand::Ready))
+    );
+}
+
+#[test]
+fn test_unready_blocks_start() {
+    let (mut game, host_id) = setup_game();
+    let guest_id = PlayerId::new();
+    let mut roller = MockRoller { value_to_return: 1 };
+    game.join(guest_id).unwrap();
+
+    game.set_ready(guest_id, true).unwrap();
+    game.set_ready(guest_id, false).unwrap();
+    assert!(!game.is_ready(guest_id));
+    assert_eq!(game.start(host_id, &mut roller), Err(GameError::PlayersNotReady));
+    assert_eq!(game.set_ready(PlayerId::new(), true), Err(GameError::NotAParticipant));
+}
+
+#[test]
+fn test_host_leaving_passes_hosting_on() {
+    let (mut game, players) = lobby_with_order(TurnOrder::HostFirst, 2);
+    game.set_ready(players[2], true).unwrap();
+
+    game.leave(players[0]).unwrap();
+    assert_eq!(game.get_players(), &players[1..]);
+    assert_eq!(game.get_host(), players[1]);
+    assert_eq!(game.get_created_by(), players[0]);
+    assert_eq!(
+        game.start(players[0], &mut MockRoller { value_to_return: 1 }),
+        Err(GameError::NotHost)
+    );
+    game.start(players[1], &mut MockRoller { value_to_return: 1 }).unwrap();
+    assert_eq!(game.get_current_player(), Some(&players[1]));
+
+    assert_eq!(
+        game.leave(players[2]),
+        Err(invalid(GameStatus::InProgress, GameCommand::Leave))
+    );
+}
+
+#[test]
+fn test_last_player_out_leaves_an_open_lobby() {
+    let (mut game, host_id) = setup_game();
+    game.leave(host_id).unwrap();
+    assert!(game.get_players().is_empty());
+    assert_eq!(game.leave(host_id), Err(GameError::NotAParticipant));
+
+    let newcomer = PlayerId::new();
+    game.join(newcomer).unwrap();
+    assert_eq!(game.get_host(), newcomer);
+}
+
+#[test]
+fn test_kicked_player_cannot_rejoin() {
+    let (mut game, players) = lobby_with_order(TurnOrder::HostFirst, 2);
+
+    assert_eq!(game.kick(players[1], players[2]), Err(GameError::NotHost));
+    assert_eq!(game.kick(players[0], players[0]), Err(GameError::CannotKickSelf));
+    assert_eq!(game.kick(players[0], PlayerId::new()), Err(GameError::NotAParticipant));
+
+    game.kick(players[0], players[2]).unwrap();
+    assert_eq!(game.get_players(), &players[..2]);
+    assert!(game.is_kicked(players[2]));
+    assert!(!game.is_ready(players[2]));
+    assert_eq!(game.join(players[2]), Err(GameError::Kicked));
+    game.join(PlayerId::new()).unwrap();
+}
+
+fn lobby_with_order(turn_order: TurnOrder, guests: usize) -> (Game, Vec<PlayerId>) {
+    let host_id = PlayerId::new();
+    let settings = GameSettings { max_players: guests + 1, turn_order, ..Default::default() };
+    let mut game = Game::with_settings(host_id, settings);
+    let mut players = vec![host_id];
+    for _ in 0..guests {
+        let guest_id = PlayerId::new();
+        game.join(guest_id).unwrap();
+        game.set_ready(guest_id, true).unwrap();
+        players.push(guest_id);
+    }
+    (game, players)
+}
+
+#[test]
+fn test_host_first_keeps_join_order() {
+    let (mut game, players) = lobby_with_order(TurnOrder::HostFirst, 2);
+    game.start(players[0], &mut SeededRoller::new([1; 32], 0)).unwrap();
+
+    let outcome = game.get_turn_order().unwrap();
+    assert_eq!(outcome.order, players);
+    assert!(outcome.rolls.is_empty());
+    assert_eq!(game.get_current_player(), Some(&players[0]));
+    assert_eq!(game.get_fairness().get_nonce(), 0);
+}
+
+#[test]
+fn test_random_order_shuffles_seats() {
+    let (mut game, players) = lobby_with_order(TurnOrder::Random, 3);
+    // Each draw picks the seat to swap in; always drawing 1 rotates the host to the back.
+    game.start(players[0], &mut MockRoller { value_to_return: 1 }).unwrap();
+
+    let outcome = game.get_turn_order().unwrap();
+    assert_eq!(outcome.order, vec![players[1], players[2], players[3], players[0]]);
+    assert_eq!(game.get_players(), outcome.order.as_slice());
+    assert_eq!(outcome.rolls.iter().map(|r| r.max).collect::<Vec<_>>(), vec![4, 3, 2]);
+    assert_eq!(game.get_current_player(), Some(&players[1]));
+    assert_eq!(game.get_host(), players[0]);
+    assert_eq!(game.get_fairness().get_nonce(), 3);
+}
+
+#[test]
+fn test_roll_off_puts_highest_roller_first() {
+    let (mut game, players) = lobby_with_order(TurnOrder::RollOff, 3);
+    game.start(players[0], &mut SeededRoller::new([9; 32], 0)).unwrap();
+
+    let outcome = game.get_turn_order().unwrap().clone();
+    assert!(outcome.rolls.len() >= players.len());
+    assert!(outcome.rolls.iter().all(|r| (r.min, r.max) == (1, 1000)));
+
+    // The winner topped the opening round, and the seats rotate to put them first.
+    let first = outcome.order[0];
+    let opening = &outcome.rolls[..players.len()];
+    let top = opening.iter().map(|r| r.value).max().unwrap();
+    assert!(opening.iter().any(|r| r.player_id == first && r.value == top));
+    let seat = players.iter().position(|p| *p == first).unwrap();
+    let mut rotated = players.clone();
+    rotated.rotate_left(seat);
+    assert_eq!(outcome.order, rotated);
+    assert_eq!(game.get_current_player(), Some(&first));
+}
+
+#[test]
+fn test_roll_off_ties_roll_again() {
+    struct Scripted(Vec<u32>);
+    impl Roller for Scripted {
+        fn roll_in_range(&mut self, _max: u32) -> u32 {
+            self.0.remove(0)
+        }
+    }
+
+    let (mut game, players) = lobby_with_order(TurnOrder::RollOff, 2);
+    // Seats 1 and 2 tie on 900; seat 2 wins the re-roll.
+    game.start(players[0], &mut Scripted(vec![500, 900, 900, 100, 700]))
+        .unwrap();
+
+    let outcome = game.get_turn_order().unwrap();
+    assert_eq!(outcome.rolls.len(), 5);
+    assert_eq!(
+        outcome.rolls[3..].iter().map(|r| r.player_id).collect::<Vec<_>>(),
+        vec![players[1], players[2]]
+    );
+    assert_eq!(outcome.order, vec![players[2], players[0], players[1]]);
+}
+
+#[test]
+fn test_roll_flow() {
+    let (mut game, host_id) = setup_game();
+    let guest_id = PlayerId::new();
+    seat(&mut game, &[guest_id]);
 
     // 1. Fail: Wrong Turn
     let mut roller = MockRoller { value_to_return: 500 };
@@ -56,7 +262,7 @@ fn test_roll_flow() {
     let events = game.roll(host_id, &mut roller).unwrap();
     assert_eq!(events.len(), 1);
     match events[0] {
-        GameEvent::Rolled { player_id, value } => {
+        GameEvent::Rolled(RollRecord { player_id, value, .. }) => {
             assert_eq!(player_id, host_id);
             assert_eq!(value, 500);
         }
@@ -72,15 +278,20 @@ fn test_roll_flow() {
 fn test_game_over_loss() {
     let (mut game, host_id) = setup_game();
     let guest_id = PlayerId::new();
-    game.join(guest_id).unwrap();
+    seat(&mut game, &[guest_id]);
 
     let mut roller = MockRoller { value_to_return: 1 }; // Rolling 1 = Loss
 
     let events = game.roll(host_id, &mut roller).unwrap();
 
-    // Should have Rolled AND GameOver events
-    assert_eq!(events.len(), 2);
-    assert!(matches!(events[1], GameEvent::GameOver { winner_id: _, loser_id: _ }));
+    // Should have Rolled, PlayerEliminated AND GameOver events
+    assert_eq!(events.len(), 3);
+    assert!(matches!(events[1], GameEvent::PlayerEliminated { player_id, .. } if player_id == host_id));
+    assert!(matches!(
+        &events[2],
+        GameEvent::GameOver { winning_team, losing_team, .. }
+            if winning_team.players == [guest_id] && losing_team.players == [host_id]
+    ));
 
     match *game.get_status() {
         GameStatus::PlayerLost(pid) => assert_eq!(pid, host_id),
@@ -89,14 +300,51 @@ fn test_game_over_loss() {
 
     // Attempt to roll after game over
     let err = game.roll(guest_id, &mut roller);
-    assert_eq!(err, Err(GameError::GameFinished));
+    assert_eq!(err, Err(invalid(GameStatus::PlayerLost(host_id), GameCommand::Roll)));
+}
+
+#[test]
+fn test_rematch_needs_every_player() {
+    let (mut game, players) = setup_full_game(3);
+    assert_eq!(
+        game.accept_rematch(players[0]),
+        Err(invalid(GameStatus::InProgress, GameCommand::Rematch))
+    );
+
+    let mut roller = MockRoller { value_to_return: 1 };
+    game.roll(players[0], &mut roller).unwrap();
+    game.roll(players[1], &mut roller).unwrap();
+    assert_eq!(game.get_loser(), Some(players[1]));
+
+    assert_eq!(game.accept_rematch(players[0]), Ok(false));
+    assert_eq!(game.accept_rematch(players[0]), Ok(false));
+    assert_eq!(game.accept_rematch(PlayerId::new()), Err(GameError::NotAParticipant));
+    assert_eq!(game.accept_rematch(players[1]), Ok(false));
+    assert_eq!(game.accept_rematch(players[2]), Ok(true));
+    assert_eq!(game.accept_rematch(players[2]), Ok(false));
+
+    // The loser opens the rematch; the rotation is kept.
+    assert_eq!(game.get_rematch_order(), vec![players[1], players[2], players[0]]);
+
+    let rematch_id = GameId::new();
+    game.set_rematch_id(rematch_id);
+    // A rematch that failed to open can be voted for again.
+    assert_eq!(game.cancel_rematch(GameId::new()), Err(GameError::NotAParticipant));
+    assert_eq!(game.cancel_rematch(rematch_id), Ok(()));
+    assert!(game.get_rematch_votes().is_empty());
+    assert_eq!(game.accept_rematch(players[0]), Ok(false));
+    game.set_rematch_id(rematch_id);
+    assert_eq!(
+        game.accept_rematch(players[0]),
+        Err(GameError::RematchAlreadyCreated { game_id: rematch_id })
+    );
 }
 
 #[test]
 fn test_disconnect_and_reconnect() {
     let (mut game, host_id) = setup_game();
     let guest_id = PlayerId::new();
-    game.join(guest_id).unwrap();
+    seat(&mut game, &[guest_id]);
 
     // 1. Pause Game
     game.pause_game(host_id).unwrap();
@@ -105,13 +353,469 @@ fn test_disconnect_and_reconnect() {
     // 2. Attempt Roll while Paused (Should Fail)
     let mut roller = MockRoller { value_to_return: 500 };
     let err = game.roll(guest_id, &mut roller);
-    assert_eq!(err, Err(GameError::GamePaused));
+    assert_eq!(
+        err,
+        Err(invalid(GameStatus::PausedForReconnect(host_id), GameCommand::Roll))
+    );
 
     // 3. Wrong Player Reconnect (Should Fail)
     let err = game.reconnect(guest_id);
-    assert_eq!(err, Err(GameError::GameFull)); // Or specific error if logic changed
+    assert_eq!(err, Err(GameError::NotDisconnected));
+    assert_eq!(game.reconnect(PlayerId::new()), Err(GameError::NotAParticipant));
 
     // 4. Correct Player Reconnect
     game.reconnect(host_id).unwrap();
     assert_eq!(*game.get_status(), GameStatus::InProgress);
 }
+
+fn setup_full_game(max_players: usize) -> (Game, Vec<PlayerId>) {
+    let host_id = PlayerId::new();
+    let mut game = Game::with_settings(host_id, GameSettings { max_players, ..Default::default() });
+    let mut players = vec![host_id];
+    players.extend((1..max_players).map(|_| PlayerId::new()));
+    seat(&mut game, &players[1..]);
+    (game, players)
+}
+
+#[test]
+fn test_join_respects_player_cap() {
+    let host_id = PlayerId::new();
+    let mut game = Game::with_settings(host_id, GameSettings { max_players: 4, ..Default::default() });
+
+    game.join(PlayerId::new()).unwrap();
+    game.join(PlayerId::new()).unwrap();
+    assert_eq!(*game.get_status(), GameStatus::WaitingForPlayers);
+
+    game.join(PlayerId::new()).unwrap();
+    assert_eq!(game.get_players().len(), 4);
+    assert_eq!(*game.get_status(), GameStatus::WaitingForPlayers);
+
+    assert_eq!(game.join(PlayerId::new()), Err(GameError::GameFull));
+}
+
+#[test]
+fn test_elimination_continues_with_remaining_players() {
+    let (mut game, players) = setup_full_game(3);
+    let mut roller = MockRoller { value_to_return: 500 };
+
+    // P1 rolls 500, then P2 rolls a 1 and is eliminated.
+    game.roll(players[0], &mut roller).unwrap();
+    roller.value_to_return = 1;
+    let events = game.roll(players[1], &mut roller).unwrap();
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[1],
+        GameEvent::PlayerEliminated { player_id: players[1], remaining: vec![players[0], players[2]] }
+    );
+    assert_eq!(*game.get_status(), GameStatus::InProgress);
+    assert!(game.is_eliminated(players[1]));
+
+    // The next survivor rolls from a fresh range.
+    assert_eq!(game.get_current_player(), Some(&players[2]));
+    assert_eq!(game.get_current_max(), 1000);
+
+    // Eliminated players are skipped in the rotation.
+    roller.value_to_return = 400;
+    game.roll(players[2], &mut roller).unwrap();
+    assert_eq!(game.get_current_player(), Some(&players[0]));
+    assert_eq!(game.roll(players[1], &mut roller), Err(GameError::NotYourTurn));
+}
+
+#[test]
+fn test_last_player_standing_wins() {
+    let (mut game, players) = setup_full_game(3);
+    let mut roller = MockRoller { value_to_return: 1 };
+
+    game.roll(players[0], &mut roller).unwrap();
+    let events = game.roll(players[1], &mut roller).unwrap();
+
+    assert_eq!(events.len(), 3);
+    assert_eq!(
+        events[2],
+        GameEvent::GameOver {
+            winning_team: Team { team_id: None, players: vec![players[2]] },
+            losing_team: Team { team_id: None, players: vec![players[1]] },
+            standings: vec![players[2], players[1], players[0]],
+            forfeited: false
+        }
+    );
+    assert_eq!(*game.get_status(), GameStatus::PlayerLost(players[1]));
+    assert_eq!(game.get_active_players(), vec![players[2]]);
+}
+
+#[test]
+fn test_custom_starting_max() {
+    let host_id = PlayerId::new();
+    let settings = GameSettings { max_players: 3, starting_max: 100, ..Default::default() };
+    let mut game = Game::with_settings(host_id, settings);
+    let p2 = PlayerId::new();
+    let p3 = PlayerId::new();
+    seat(&mut game, &[p2, p3]);
+
+    assert_eq!(game.get_starting_max(), 100);
+    assert_eq!(game.get_roll_range(), (1, 100));
+
+    let mut roller = MockRoller { value_to_return: 40 };
+    game.roll(host_id, &mut roller).unwrap();
+    assert_eq!(game.get_roll_range(), (1, 40));
+
+    // An elimination resets the range to the table's opening roll.
+    roller.value_to_return = 1;
+    game.roll(p2, &mut roller).unwrap();
+    assert_eq!(game.get_roll_range(), (1, 100));
+}
+
+#[test]
+fn test_roll_history_records_chain() {
+    let (mut game, host_id) = setup_game();
+    let guest_id = PlayerId::new();
+    seat(&mut game, &[guest_id]);
+
+    let mut roller = MockRoller { value_to_return: 600 };
+    game.roll(host_id, &mut roller).unwrap();
+    roller.value_to_return = 1;
+    game.roll(guest_id, &mut roller).unwrap();
+
+    let history = game.get_history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        (history[0].player_id, history[0].max, history[0].value),
+        (host_id, 1000, 600)
+    );
+    assert_eq!(
+        (history[1].player_id, history[1].max, history[1].value),
+        (guest_id, 600, 1)
+    );
+    assert_eq!((history[0].nonce, history[1].nonce), (0, 1));
+    assert!(history[0].rolled_at <= history[1].rolled_at);
+
+    // Rejected rolls leave no trace
+    let _ = game.roll(guest_id, &mut roller);
+    assert_eq!(game.get_history().len(), 2);
+}
+
+#[test]
+fn test_turn_timer_follows_the_turn() {
+    let (mut game, host_id) = setup_game();
+    assert_eq!(game.get_turn_deadline(), None);
+
+    let guest_id = PlayerId::new();
+    seat(&mut game, &[guest_id]);
+    assert!(game.get_turn_deadline().is_some());
+
+    // Each roll restarts the clock for the next player.
+    let rolled_at = Utc::now() + Duration::seconds(5);
+    let mut roller = MockRoller { value_to_return: 600 };
+    game.roll_at(host_id, &mut roller, rolled_at).unwrap();
+    assert_eq!(game.get_turn_deadline(), Some(rolled_at + Duration::seconds(30)));
+    assert!(!game.is_turn_expired(rolled_at));
+    assert!(game.is_turn_expired(rolled_at + Duration::seconds(30)));
+    assert_eq!(
+        game.get_turn_time_remaining(rolled_at + Duration::seconds(10)),
+        Some(Duration::seconds(20))
+    );
+
+    // The clock stops while the game is paused and when it ends.
+    game.pause_game(guest_id).unwrap();
+    assert_eq!(game.get_turn_deadline(), None);
+    game.reconnect(guest_id).unwrap();
+    assert!(game.get_turn_deadline().is_some());
+
+    roller.value_to_return = 1;
+    game.roll(guest_id, &mut roller).unwrap();
+    assert_eq!(game.get_turn_deadline(), None);
+}
+
+#[test]
+fn test_untimed_game_has_no_deadline() {
+    let host_id = PlayerId::new();
+    let mut game = Game::with_settings(host_id, GameSettings { turn_timeout_secs: 0, ..Default::default() });
+    seat(&mut game, &[PlayerId::new()]);
+
+    assert_eq!(game.get_turn_deadline(), None);
+    assert!(!game.is_turn_expired(Utc::now()));
+}
+
+#[test]
+fn test_forfeit_eliminates_without_a_roll() {
+    let (mut game, players) = setup_full_game(3);
+    let mut roller = MockRoller { value_to_return: 500 };
+    game.roll(players[0], &mut roller).unwrap();
+
+    // Forfeiting out of turn leaves the current turn alone.
+    let now = Utc::now();
+    let events = game.forfeit_at(players[2], now).unwrap();
+    assert_eq!(
+        events,
+        vec![GameEvent::PlayerForfeited {
+            player_id: players[2],
+            winner_id: None,
+            remaining: vec![players[0], players[1]],
+            forfeited_at: now,
+        }]
+    );
+    assert_eq!(game.get_current_player(), Some(&players[1]));
+    assert_eq!(game.get_current_max(), 500);
+    assert_eq!(game.forfeit(players[2]), Err(GameError::NotAParticipant));
+
+    let events = game.forfeit_at(players[1], now).unwrap();
+    assert_eq!(
+        events[0],
+        GameEvent::PlayerForfeited {
+            player_id: players[1],
+            winner_id: Some(players[0]),
+            remaining: vec![players[0]],
+            forfeited_at: now,
+        }
+    );
+    assert!(matches!(
+        events.last(),
+        Some(GameEvent::GameOver { winning_team, forfeited: true, .. }) if winning_team.players == [players[0]]
+    ));
+    assert_eq!(*game.get_status(), GameStatus::Forfeited(players[1]));
+    assert_eq!(game.get_history().len(), 1);
+}
+
+#[test]
+fn test_reconnect_grace_ends_in_forfeit() {
+    let (mut game, host_id) = setup_game();
+    let guest_id = PlayerId::new();
+    seat(&mut game, &[guest_id]);
+
+    game.pause_game(guest_id).unwrap();
+    let deadline = game.get_reconnect_deadline().unwrap();
+    assert_eq!(game.get_next_deadline(), Some(deadline));
+    assert_eq!(game.get_expired_reconnect(deadline - Duration::seconds(1)), None);
+    assert_eq!(game.get_expired_reconnect(deadline), Some(guest_id));
+
+    let events = game.forfeit(guest_id).unwrap();
+    assert_eq!(
+        events.last(),
+        Some(&GameEvent::GameOver {
+            winning_team: Team { team_id: None, players: vec![host_id] },
+            losing_team: Team { team_id: None, players: vec![guest_id] },
+            standings: vec![host_id, guest_id],
+            forfeited: true
+        })
+    );
+    assert_eq!(*game.get_status(), GameStatus::Forfeited(guest_id));
+    assert!(game.is_finished());
+    assert_eq!(game.get_next_deadline(), None);
+}
+
+#[test]
+fn test_reconnect_clears_grace_window() {
+    let (mut game, host_id) = setup_game();
+    let guest_id = PlayerId::new();
+    seat(&mut game, &[guest_id]);
+
+    game.pause_game(host_id).unwrap();
+    assert!(game.get_reconnect_deadline().is_some());
+    game.reconnect(host_id).unwrap();
+
+    assert_eq!(game.get_reconnect_deadline(), None);
+    assert_eq!(game.get_expired_reconnect(Utc::now() + Duration::days(1)), None);
+}
+
+#[test]
+fn test_threshold_rules_eliminate_low_rolls() {
+    let host_id = PlayerId::new();
+    let rules = RuleSet::Threshold(Threshold { lose_at: 10 });
+    let mut game = Game::with_settings(host_id, GameSettings { rules, ..Default::default() });
+    let guest_id = PlayerId::new();
+    seat(&mut game, &[guest_id]);
+
+    let mut roller = MockRoller { value_to_return: 11 };
+    game.roll(host_id, &mut roller).unwrap();
+    assert_eq!(game.get_roll_range(), (1, 11));
+
+    roller.value_to_return = 10;
+    game.roll(guest_id, &mut roller).unwrap();
+    assert_eq!(*game.get_status(), GameStatus::PlayerLost(guest_id));
+}
+
+#[test]
+fn test_reverse_rules_climb_toward_ceiling() {
+    let host_id = PlayerId::new();
+    let settings = GameSettings { starting_max: 100, rules: RuleSet::Reverse, ..Default::default() };
+    let mut game = Game::with_settings(host_id, settings);
+    let guest_id = PlayerId::new();
+    seat(&mut game, &[guest_id]);
+
+    let mut roller = MockRoller { value_to_return: 60 };
+    game.roll(host_id, &mut roller).unwrap();
+    assert_eq!(game.get_roll_range(), (60, 100));
+
+    roller.value_to_return = 100;
+    game.roll(guest_id, &mut roller).unwrap();
+    assert_eq!(*game.get_status(), GameStatus::PlayerLost(guest_id));
+}
+
+#[test]
+fn test_spectator_limit() {
+    let host_id = PlayerId::new();
+    let mut game = Game::with_settings(host_id, GameSettings { max_spectators: 2, ..Default::default() });
+
+    game.check_spectator_seat(1).unwrap();
+    assert_eq!(game.check_spectator_seat(2), Err(GameError::SpectatorsFull));
+
+    assert_eq!(game.set_max_spectators(PlayerId::new(), 0), Err(GameError::NotHost));
+    game.set_max_spectators(host_id, 0).unwrap();
+    assert_eq!(game.check_spectator_seat(0), Err(GameError::SpectatingDisabled));
+}
+
+#[test]
+fn test_event_stream_rebuilds_every_state() {
+    let host_id = PlayerId::new();
+    let settings = GameSettings { turn_order: TurnOrder::Random, turn_timeout_secs: 30, ..Default::default() };
+    let mut game = Game::with_settings(host_id, settings);
+    let guest_id = PlayerId::new();
+    seat(&mut game, &[guest_id]);
+    game.pause_game(guest_id).unwrap();
+    game.reconnect(guest_id).unwrap();
+    let mut roller = MockRoller { value_to_return: 400 };
+    let current = *game.get_current_player().unwrap();
+    game.roll(current, &mut roller).unwrap();
+    game.forfeit(current).unwrap();
+
+    // Events survive storage as JSON.
+    let json = serde_json::to_string(game.get_uncommitted_events()).unwrap();
+    let events: Vec<RecordedEvent> = serde_json::from_str(&json).unwrap();
+    assert_eq!(events, game.get_uncommitted_events());
+    assert!(matches!(events[0].event, GameEvent::Created { .. }));
+    assert_eq!(events.last().unwrap().sequence, game.get_version());
+
+    assert_eq!(Game::from_events(&events), Some(game.clone()));
+
+    // Any prefix is the game as it stood at that point.
+    let started = events
+        .iter()
+        .position(|e| matches!(e.event, GameEvent::GameStarted { .. }))
+        .unwrap();
+    let at_start = Game::from_events(&events[..=started]).unwrap();
+    assert_eq!(*at_start.get_status(), GameStatus::InProgress);
+    assert!(at_start.get_history().is_empty());
+    assert_eq!(at_start.get_version(), started as u64 + 1);
+
+    // A snapshot catches up with whatever was recorded after it.
+    let mut snapshot = Game::from_events(&events[..=started]).unwrap();
+    snapshot.catch_up(&events);
+    assert_eq!(snapshot, game);
+    assert_eq!(Game::from_events(&events[1..]), None);
+}
+
+#[test]
+fn test_transition_table() {
+    let (mut game, host_id) = setup_game();
+    let guest_id = PlayerId::new();
+
+    // Nothing to pause or forfeit in a lobby.
+    assert_eq!(
+        game.pause_game(host_id),
+        Err(invalid(GameStatus::WaitingForPlayers, GameCommand::Pause))
+    );
+    assert_eq!(
+        game.forfeit(host_id),
+        Err(invalid(GameStatus::WaitingForPlayers, GameCommand::Forfeit))
+    );
+    assert_eq!(
+        game.reconnect(host_id),
+        Err(invalid(GameStatus::WaitingForPlayers, GameCommand::Reconnect))
+    );
+
+    seat(&mut game, &[guest_id]);
+    assert_eq!(
+        game.join(PlayerId::new()),
+        Err(invalid(GameStatus::InProgress, GameCommand::Join))
+    );
+    assert_eq!(game.pause_game(PlayerId::new()), Err(GameError::NotAParticipant));
+    game.pause_game(guest_id).unwrap();
+    let paused = GameStatus::PausedForReconnect(guest_id);
+    assert_eq!(
+        game.pause_game(host_id),
+        Err(invalid(paused.clone(), GameCommand::Pause))
+    );
+    assert_eq!(game.kick(host_id, guest_id), Err(invalid(paused, GameCommand::Kick)));
+
+    game.forfeit(guest_id).unwrap();
+    let over = GameStatus::Forfeited(guest_id);
+    assert!(over.accepts(GameCommand::Rematch));
+    assert_eq!(game.forfeit(host_id), Err(invalid(over.clone(), GameCommand::Forfeit)));
+    assert_eq!(
+        invalid(over, GameCommand::Roll).to_string(),
+        "Cannot roll while the game is over by forfeit."
+    );
+}
+
+#[test]
+fn test_team_assignment_in_lobby() {
+    let host_id = PlayerId::new();
+    let mut game = Game::with_settings(host_id, GameSettings { max_players: 4, teams: 2, ..Default::default() });
+    let guests = [PlayerId::new(), PlayerId::new(), PlayerId::new()];
+    for guest_id in guests {
+        game.join(guest_id).unwrap();
+        game.set_ready(guest_id, true).unwrap();
+    }
+
+    // Joining players fill the smallest team.
+    assert_eq!(game.get_team_members(TeamId(0)), vec![host_id, guests[1]]);
+    assert_eq!(game.get_team_members(TeamId(1)), vec![guests[0], guests[2]]);
+
+    assert_eq!(
+        game.assign_team(guests[0], guests[1], TeamId(1)),
+        Err(GameError::NotHost)
+    );
+    assert_eq!(
+        game.assign_team(guests[0], guests[0], TeamId(2)),
+        Err(GameError::NoSuchTeam { team_id: TeamId(2), max: 1 })
+    );
+    game.assign_team(host_id, guests[0], TeamId(0)).unwrap();
+    game.assign_team(guests[2], guests[2], TeamId(0)).unwrap();
+    assert_eq!(game.get_team(guests[2]), Some(TeamId(0)));
+    assert_eq!(
+        game.start(host_id, &mut MockRoller { value_to_return: 1 }),
+        Err(GameError::EmptyTeam { team_id: TeamId(1) })
+    );
+
+    let (mut solo, solo_host) = setup_game();
+    assert_eq!(
+        solo.assign_team(solo_host, solo_host, TeamId(0)),
+        Err(GameError::NotATeamGame)
+    );
+}
+
+#[test]
+fn test_team_turns_alternate_and_the_team_loses_together() {
+    let host_id = PlayerId::new();
+    let mut game = Game::with_settings(host_id, GameSettings { max_players: 3, teams: 2, ..Default::default() });
+    let (rival, partner) = (PlayerId::new(), PlayerId::new());
+    seat(&mut game, &[rival, partner]);
+    assert_eq!(game.get_team(partner), Some(TeamId(0)));
+    assert_eq!(game.get_players(), [host_id, rival, partner]);
+
+    // The lone rival rolls every other turn; the pair take turns between them.
+    let mut roller = MockRoller { value_to_return: 10 };
+    for expected in [host_id, rival, partner, rival, host_id, rival] {
+        assert_eq!(game.get_current_player(), Some(&expected));
+        game.roll(expected, &mut roller).unwrap();
+    }
+
+    let events = game.roll(partner, &mut MockRoller { value_to_return: 1 }).unwrap();
+    assert_eq!(
+        events[1..],
+        [
+            GameEvent::PlayerEliminated { player_id: partner, remaining: vec![rival] },
+            GameEvent::TeamEliminated { team_id: TeamId(0), players: vec![host_id], remaining: vec![rival] },
+            GameEvent::GameOver {
+                winning_team: Team { team_id: Some(TeamId(1)), players: vec![rival] },
+                losing_team: Team { team_id: Some(TeamId(0)), players: vec![partner, host_id] },
+                standings: vec![rival, partner, host_id],
+                forfeited: false,
+            },
+        ]
+    );
+    assert_eq!(*game.get_status(), GameStatus::PlayerLost(partner));
+    assert!(game.is_eliminated(host_id));
+    assert_eq!(Game::from_events(game.get_uncommitted_events()), Some(game));
+}
@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+
+use super::roller::Roller;
+use super::settings::GameSettings;
+use super::types::{GameError, GameId, PlayerId, TournamentId};
+
+/// How entrants are placed in the bracket.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Seeding {
+    /// Shuffle the entrants.
+    #[default]
+    Random,
+    /// Highest rating first; unrated entrants go last, in registration order.
+    Rating,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TournamentStatus {
+    Registration,
+    InProgress,
+    Finished(PlayerId),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Entrant {
+    pub player_id: PlayerId,
+    pub rating: Option<u32>,
+}
+
+/// One game of the bracket. A slot is empty until the game feeding it is decided, or for good
+/// when it is a first-round bye.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Pairing {
+    pub players: [Option<PlayerId>; 2],
+    pub game_id: Option<GameId>,
+    pub winner_id: Option<PlayerId>,
+}
+
+impl Pairing {
+    /// Both players are known and nobody has started their game yet.
+    pub fn is_ready(&self) -> bool {
+        self.players.iter().all(Option::is_some) && self.game_id.is_none() && self.winner_id.is_none()
+    }
+}
+
+/// A single-elimination bracket. Round 0 is the first round; its winners meet in round 1, and
+/// so on up to the final.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Tournament {
+    id: TournamentId,
+    host_id: PlayerId,
+    max_entrants: usize,
+    seeding: Seeding,
+    settings: GameSettings,
+    // In registration order.
+    entrants: Vec<Entrant>,
+    // Entrants from the top seed down, once the bracket is drawn.
+    seeds: Vec<PlayerId>,
+    rounds: Vec<Vec<Pairing>>,
+    status: TournamentStatus,
+    // Saves so far; a save only goes through from the version it was loaded at.
+    #[serde(default)]
+    version: u64,
+}
+
+impl Tournament {
+    #[tracing::instrument]
+    pub fn new(
+        host_id: PlayerId,
+        max_entrants: usize,
+        seeding: Seeding,
+        settings: GameSettings,
+    ) -> Result<Self, GameError> {
+        if settings.teams > 0 {
+            return Err(GameError::TeamsInMatch);
+        }
+        Ok(Self {
+            id: TournamentId::new(),
+            host_id,
+            max_entrants,
+            seeding,
+            // Every pairing is one against one.
+            settings: GameSettings { max_players: 2, ..settings },
+            entrants: vec![],
+            seeds: vec![],
+            rounds: vec![],
+            status: TournamentStatus::Registration,
+            version: 0,
+        })
+    }
+
+    // Getters
+    pub fn get_id(&self) -> TournamentId {
+        self.id
+    }
+
+    pub fn get_host(&self) -> PlayerId {
+        self.host_id
+    }
+
+    pub fn get_settings(&self) -> &GameSettings {
+        &self.settings
+    }
+
+    pub fn get_version(&self) -> u64 {
+        self.version
+    }
+
+    /// Move the copy in hand on to the version the repository just stored.
+    pub(crate) fn bump_version(&mut self) {
+        self.version += 1;
+    }
+
+    pub fn get_entrants(&self) -> &[Entrant] {
+        &self.entrants
+    }
+
+    pub fn get_seeds(&self) -> &[PlayerId] {
+        &self.seeds
+    }
+
+    pub fn get_rounds(&self) -> &[Vec<Pairing>] {
+        &self.rounds
+    }
+
+    pub fn get_status(&self) -> &TournamentStatus {
+        &self.status
+    }
+
+    /// The pairings whose game can be started now, as `(round, index)`.
+    pub fn get_ready_pairings(&self) -> Vec<(usize, usize)> {
+        self.rounds
+            .iter()
+            .enumerate()
+            .flat_map(|(round, pairings)| {
+                pairings
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, pairing)| pairing.is_ready())
+                    .map(move |(index, _)| (round, index))
+            })
+            .collect()
+    }
+
+    pub fn get_pairing(&self, round: usize, index: usize) -> Option<&Pairing> {
+        self.rounds.get(round)?.get(index)
+    }
+
+    //  --- Public mutators ---
+    #[tracing::instrument(skip(self))]
+    pub fn register(&mut self, player_id: PlayerId, rating: Option<u32>) -> Result<(), GameError> {
+        if self.status != TournamentStatus::Registration {
+            return Err(GameError::RegistrationClosed);
+        }
+        if self.entrants.iter().any(|e| e.player_id == player_id) {
+            return Err(GameError::AlreadyJoined);
+        }
+        if self.entrants.len() >= self.max_entrants {
+            return Err(GameError::TournamentFull { max: self.max_entrants });
+        }
+        self.entrants.push(Entrant { player_id, rating });
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn withdraw(&mut self, player_id: PlayerId) -> Result<(), GameError> {
+        if self.status != TournamentStatus::Registration {
+            return Err(GameError::RegistrationClosed);
+        }
+        let seat = self
+            .entrants
+            .iter()
+            .position(|e| e.player_id == player_id)
+            .ok_or(GameError::NotAParticipant)?;
+        self.entrants.remove(seat);
+        Ok(())
+    }
+
+    /// Host-only: close registration and draw the bracket. The top seeds get the byes.
+    #[tracing::instrument(skip(self, roller))]
+    pub fn start(&mut self, host_id: PlayerId, roller: &mut impl Roller) -> Result<(), GameError> {
+        if self.status != TournamentStatus::Registration {
+            return Err(GameError::RegistrationClosed);
+        }
+        if host_id != self.host_id {
+            return Err(GameError::NotHost);
+        }
+        if self.entrants.len() < 2 {
+            return Err(GameError::NotEnoughPlayers);
+        }
+
+        self.seeds = self.seed(roller);
+        let size = self.seeds.len().next_power_of_two();
+        let slots: Vec<Option<PlayerId>> = bracket_order(size)
+            .into_iter()
+            .map(|seed| self.seeds.get(seed - 1).copied())
+            .collect();
+
+        let mut rounds = vec![slots
+            .chunks(2)
+            .map(|pair| Pairing { players: [pair[0], pair[1]], ..Default::default() })
+            .collect::<Vec<_>>()];
+        while rounds.last().is_some_and(|round| round.len() > 1) {
+            let next = rounds.last().map_or(0, Vec::len) / 2;
+            rounds.push(vec![Pairing::default(); next]);
+        }
+        self.rounds = rounds;
+        self.status = TournamentStatus::InProgress;
+
+        // Byes go straight through to the second round.
+        for index in 0..self.rounds[0].len() {
+            if let [Some(player_id), None] | [None, Some(player_id)] = self.rounds[0][index].players {
+                self.advance(0, index, player_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Link the game being played for a pairing.
+    pub fn set_game(&mut self, round: usize, index: usize, game_id: GameId) -> Result<(), GameError> {
+        let pairing = self
+            .rounds
+            .get_mut(round)
+            .and_then(|pairings| pairings.get_mut(index))
+            .ok_or(GameError::NotAParticipant)?;
+        if !pairing.is_ready() {
+            return Err(GameError::GameFinished);
+        }
+        pairing.game_id = Some(game_id);
+        Ok(())
+    }
+
+    /// Credit the winner of a bracket game and move them on. Returns the champion once the final is decided.
+    #[tracing::instrument(skip(self))]
+    pub fn record_result(&mut self, game_id: GameId, winner_id: PlayerId) -> Result<Option<PlayerId>, GameError> {
+        if self.status != TournamentStatus::InProgress {
+            return Err(GameError::GameFinished);
+        }
+        let (round, index) = self
+            .rounds
+            .iter()
+            .enumerate()
+            .find_map(|(round, pairings)| {
+                let index = pairings.iter().position(|p| p.game_id == Some(game_id))?;
+                Some((round, index))
+            })
+            .ok_or(GameError::NotAParticipant)?;
+
+        let pairing = &self.rounds[round][index];
+        if pairing.winner_id.is_some() {
+            return Err(GameError::GameFinished);
+        }
+        if !pairing.players.contains(&Some(winner_id)) {
+            return Err(GameError::NotAParticipant);
+        }
+        self.advance(round, index, winner_id);
+
+        match self.status {
+            TournamentStatus::Finished(champion) => Ok(Some(champion)),
+            _ => Ok(None),
+        }
+    }
+
+    //  --- Private helpers ---
+    fn seed(&self, roller: &mut impl Roller) -> Vec<PlayerId> {
+        let mut entrants = self.entrants.clone();
+        match self.seeding {
+            Seeding::Random => {
+                for seat in (1..entrants.len()).rev() {
+                    let draw = roller.roll_between(1, seat as u32 + 1) as usize;
+                    entrants.swap(seat, draw - 1);
+                }
+            }
+            // A stable sort keeps registration order among equal ratings.
+            Seeding::Rating => entrants.sort_by_key(|e| std::cmp::Reverse(e.rating)),
+        }
+        entrants.into_iter().map(|e| e.player_id).collect()
+    }
+
+    /// Decide a pairing and fill the winner's slot in the next round, or crown the champion.
+    fn advance(&mut self, round: usize, index: usize, winner_id: PlayerId) {
+        self.rounds[round][index].winner_id = Some(winner_id);
+        match self.rounds.get_mut(round + 1) {
+            Some(next) => next[index / 2].players[index % 2] = Some(winner_id),
+            None => self.status = TournamentStatus::Finished(winner_id),
+        }
+    }
+}
+
+/// Seeds in bracket order for a bracket of `size` slots, so that the top seeds only meet late:
+/// 1 v 8, 4 v 5, 2 v 7, 3 v 6 for eight.
+fn bracket_order(size: usize) -> Vec<usize> {
+    let mut order = vec![1];
+    while order.len() < size {
+        let mirror = order.len() * 2 + 1;
+        order = order.iter().flat_map(|&seed| [seed, mirror - seed]).collect();
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FirstPick;
+
+    impl Roller for FirstPick {
+        fn roll_in_range(&mut self, _max: u32) -> u32 {
+            1
+        }
+    }
+
+    fn setup_tournament(entrants: usize, seeding: Seeding) -> (Tournament, Vec<PlayerId>) {
+        let host_id = PlayerId::new();
+        let mut tournament = Tournament::new(host_id, 16, seeding, GameSettings::default()).unwrap();
+        let players: Vec<PlayerId> = (0..entrants).map(|_| PlayerId::new()).collect();
+        for (rank, player_id) in players.iter().enumerate() {
+            tournament.register(*player_id, Some(1000 - rank as u32)).unwrap();
+        }
+        tournament.start(host_id, &mut FirstPick).unwrap();
+        (tournament, players)
+    }
+
+    #[test]
+    fn test_bracket_order() {
+        assert_eq!(bracket_order(2), vec![1, 2]);
+        assert_eq!(bracket_order(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+    }
+
+    #[test]
+    fn test_registration() {
+        let host_id = PlayerId::new();
+        let mut tournament = Tournament::new(host_id, 2, Seeding::Random, GameSettings::default()).unwrap();
+        let (a, b) = (PlayerId::new(), PlayerId::new());
+
+        tournament.register(a, None).unwrap();
+        assert_eq!(tournament.register(a, None), Err(GameError::AlreadyJoined));
+        assert_eq!(
+            tournament.start(host_id, &mut FirstPick),
+            Err(GameError::NotEnoughPlayers)
+        );
+        tournament.register(b, None).unwrap();
+        assert_eq!(
+            tournament.register(PlayerId::new(), None),
+            Err(GameError::TournamentFull { max: 2 })
+        );
+        assert_eq!(tournament.start(a, &mut FirstPick), Err(GameError::NotHost));
+
+        tournament.start(host_id, &mut FirstPick).unwrap();
+        assert_eq!(
+            tournament.register(PlayerId::new(), None),
+            Err(GameError::RegistrationClosed)
+        );
+        assert_eq!(tournament.withdraw(a), Err(GameError::RegistrationClosed));
+        assert_eq!(tournament.get_ready_pairings(), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_byes_go_to_the_top_seeds() {
+        let (tournament, players) = setup_tournament(5, Seeding::Rating);
+
+        assert_eq!(tournament.get_seeds(), players);
+        assert_eq!(tournament.get_rounds().len(), 3);
+        // Seeds 1-3 have byes; only 4 v 5 is played in the first round.
+        assert_eq!(tournament.get_ready_pairings(), vec![(0, 1), (1, 1)]);
+        assert_eq!(
+            tournament.get_pairing(1, 1).unwrap().players,
+            [Some(players[1]), Some(players[2])]
+        );
+        assert_eq!(tournament.get_pairing(1, 0).unwrap().players, [Some(players[0]), None]);
+    }
+
+    #[test]
+    fn test_winners_advance_to_a_champion() {
+        let (mut tournament, players) = setup_tournament(3, Seeding::Rating);
+
+        // Seed 1 has a bye, so seeds 2 and 3 play first.
+        assert_eq!(tournament.get_ready_pairings(), vec![(0, 1)]);
+        let semi = GameId::new();
+        tournament.set_game(0, 1, semi).unwrap();
+        assert!(tournament.get_ready_pairings().is_empty());
+        assert_eq!(
+            tournament.record_result(semi, players[0]),
+            Err(GameError::NotAParticipant)
+        );
+        assert_eq!(tournament.record_result(semi, players[2]), Ok(None));
+        assert_eq!(tournament.record_result(semi, players[2]), Err(GameError::GameFinished));
+
+        let final_game = GameId::new();
+        tournament.set_game(1, 0, final_game).unwrap();
+        assert_eq!(tournament.record_result(final_game, players[2]), Ok(Some(players[2])));
+        assert_eq!(*tournament.get_status(), TournamentStatus::Finished(players[2]));
+    }
+
+    #[test]
+    fn test_random_seeding_uses_the_roller() {
+        let (tournament, players) = setup_tournament(4, Seeding::Random);
+
+        // Always drawing the first seat rotates the entrants one place.
+        assert_eq!(tournament.get_seeds(), [players[1], players[2], players[3], players[0]]);
+    }
+}
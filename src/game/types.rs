@@ -1,12 +1,19 @@
 use core::fmt;
+use std::str::FromStr;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+use super::fairness::ServerSeed;
+use super::settings::{GameSettings, TurnOrder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(transparent)] // Serialize directly as the inner UUID string
 pub struct PlayerId(Uuid);
 
+// Ids are random, so a `Default` would quietly hand out a fresh one each time.
+#[allow(clippy::new_without_default)]
 impl PlayerId {
     pub fn new() -> Self {
         Self(Uuid::new_v4())
@@ -19,10 +26,19 @@ impl fmt::Display for PlayerId {
     }
 }
 
+impl FromStr for PlayerId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct GameId(Uuid);
 
+#[allow(clippy::new_without_default)]
 impl GameId {
     pub fn new() -> Self {
         Self(Uuid::new_v4())
@@ -35,32 +51,452 @@ impl fmt::Display for GameId {
     }
 }
 
+impl FromStr for GameId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MatchId(Uuid);
+
+#[allow(clippy::new_without_default)]
+impl MatchId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl fmt::Display for MatchId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TournamentId(Uuid);
+
+#[allow(clippy::new_without_default)]
+impl TournamentId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl fmt::Display for TournamentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A side in a team game, numbered from zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TeamId(pub usize);
+
+impl fmt::Display for TeamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// One side of a finished game. In a free-for-all every player is a team of one, with no `team_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Team {
+    pub team_id: Option<TeamId>,
+    pub players: Vec<PlayerId>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum GameStatus {
     WaitingForPlayers,
     InProgress,
     PlayerLost(PlayerId),
+    /// The game ended because this player forfeited rather than rolling a 1.
+    Forfeited(PlayerId),
     PausedForReconnect(PlayerId),
 }
 
+impl GameStatus {
+    /// The transition table: which commands a game in this status accepts.
+    ///
+    /// - `WaitingForPlayers`: players join, leave, get kicked, pick teams and ready up; `Start` moves to
+    ///   `InProgress`.
+    /// - `InProgress`: players roll and forfeit, either of which can end the game; `Pause` moves to
+    ///   `PausedForReconnect`.
+    /// - `PausedForReconnect`: `Reconnect` moves back to `InProgress`; a forfeit can still end the game.
+    /// - `PlayerLost` and `Forfeited` are final, apart from voting for a rematch.
+    pub fn accepts(&self, command: GameCommand) -> bool {
+        use GameCommand::*;
+        match self {
+            GameStatus::WaitingForPlayers => matches!(command, Join | Leave | Kick | ChooseTeam | Ready | Start),
+            GameStatus::InProgress => matches!(command, Roll | Forfeit | Pause),
+            GameStatus::PausedForReconnect(_) => matches!(command, Reconnect | Forfeit),
+            GameStatus::PlayerLost(_) | GameStatus::Forfeited(_) => matches!(command, Rematch),
+        }
+    }
+
+    /// `accepts`, as an error naming the state and the rejected command.
+    pub fn check(&self, command: GameCommand) -> Result<(), GameError> {
+        if self.accepts(command) {
+            Ok(())
+        } else {
+            Err(GameError::InvalidTransition { from: self.clone(), action: command })
+        }
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameStatus::WaitingForPlayers => write!(f, "waiting for players"),
+            GameStatus::InProgress => write!(f, "in progress"),
+            GameStatus::PlayerLost(_) => write!(f, "over"),
+            GameStatus::Forfeited(_) => write!(f, "over by forfeit"),
+            GameStatus::PausedForReconnect(_) => write!(f, "paused for a reconnect"),
+        }
+    }
+}
+
+/// The commands whose legality depends on the game's status; see `GameStatus::accepts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GameCommand {
+    Join,
+    Leave,
+    Kick,
+    ChooseTeam,
+    Ready,
+    Start,
+    Roll,
+    Pause,
+    Reconnect,
+    Forfeit,
+    Rematch,
+}
+
+impl fmt::Display for GameCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = match self {
+            GameCommand::Join => "join",
+            GameCommand::Leave => "leave",
+            GameCommand::Kick => "kick a player",
+            GameCommand::ChooseTeam => "change teams",
+            GameCommand::Ready => "change ready state",
+            GameCommand::Start => "start",
+            GameCommand::Roll => "roll",
+            GameCommand::Pause => "pause",
+            GameCommand::Reconnect => "reconnect",
+            GameCommand::Forfeit => "forfeit",
+            GameCommand::Rematch => "ask for a rematch",
+        };
+        write!(f, "{}", verb)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, thiserror::Error)]
 pub enum GameError {
     #[error("The current game is already finished.")]
     GameFinished,
-    #[error("The game is full and cannot accept a third player.")]
+    #[error("Cannot {action} while the game is {from}.")]
+    InvalidTransition { from: GameStatus, action: GameCommand },
+    #[error("The game is full and cannot accept more players.")]
     GameFull,
+    #[error("The player has already joined this game.")]
+    AlreadyJoined,
     #[error("The game is waiting for another player to join.")]
     NotEnoughPlayers,
     #[error("It is not your turn to roll.")]
     NotYourTurn,
-    #[error("The game is currently paused, waiting for a player to reconnect.")]
-    GamePaused,
+    #[error("Only the disconnected player can reconnect.")]
+    NotDisconnected,
+    #[error("The player cap must be between {min} and {max}.")]
+    InvalidPlayerCap { min: usize, max: usize },
+    #[error("The starting maximum must be between {min} and {max}.")]
+    InvalidStartingMax { min: u32, max: u32 },
+    #[error("The player is not part of this game.")]
+    NotAParticipant,
+    #[error("Seeds can no longer be changed once the first roll has been made.")]
+    SeedsLocked,
+    #[error("The server seed does not match the published commitment.")]
+    SeedMismatch,
+    #[error("The server seed is only revealed once the game is over.")]
+    SeedNotRevealed,
+    #[error("This table plays for {wager} gold; the wager must be accepted to join.")]
+    WagerNotAccepted { wager: u64 },
+    #[error("Player {player_id} cannot cover the wager.")]
+    InsufficientFunds { player_id: PlayerId },
+    #[error("Turn timeout must be between {min} and {max} seconds, or zero for no timer.")]
+    InvalidTurnTimeout { min: u64, max: u64 },
+    #[error("Reconnect grace must be at most {max} seconds, or zero to wait indefinitely.")]
+    InvalidReconnectGrace { max: u64 },
+    #[error("The losing threshold must be between 1 and {max}.")]
+    InvalidThreshold { max: u32 },
+    #[error("The spectator limit must be at most {max}, or zero to turn spectating off.")]
+    InvalidSpectatorLimit { max: usize },
+    #[error("The wager must be at most {max}.")]
+    InvalidWager { max: u64 },
+    #[error("This game does not allow spectators.")]
+    SpectatingDisabled,
+    #[error("This game already has as many spectators as it allows.")]
+    SpectatorsFull,
+    #[error("Spectators can only watch.")]
+    Spectating,
+    #[error("You are already watching this game.")]
+    AlreadySpectating,
+    #[error("Only the host can do that.")]
+    NotHost,
+    #[error("Every player has to be ready before the game can start.")]
+    PlayersNotReady,
+    #[error("You were removed from this lobby and cannot join it again.")]
+    Kicked,
+    #[error("The host cannot kick themselves; leave the lobby instead.")]
+    CannotKickSelf,
+    #[error("A team game needs between 2 and {max} teams.")]
+    InvalidTeamCount { max: usize },
+    #[error("This game is not played in teams.")]
+    NotATeamGame,
+    #[error("There is no team {team_id}; teams are numbered from 0 to {max}.")]
+    NoSuchTeam { team_id: TeamId, max: usize },
+    #[error("Team {team_id} needs at least one player before the game can start.")]
+    EmptyTeam { team_id: TeamId },
+    #[error("Matches and tournaments are played every player for themselves.")]
+    TeamsInMatch,
+    #[error("Registration for this tournament has closed.")]
+    RegistrationClosed,
+    #[error("The tournament is full at {max} players.")]
+    TournamentFull { max: usize },
+    #[error("A tournament must allow between {min} and {max} players.")]
+    InvalidTournamentSize { min: usize, max: usize },
+    #[error("A match must be played over an odd number of games.")]
+    InvalidBestOf,
+    #[error("Game {game_id} is not the game the match is playing now.")]
+    NotCurrentMatchGame { game_id: GameId },
+    #[error("Games in a match or tournament carry on with it instead of a rematch.")]
+    RematchInMatch,
+    #[error("The rematch has already started as game {game_id}.")]
+    RematchAlreadyCreated { game_id: GameId },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Bumped whenever a `GameEvent` changes shape, so older streams can be told apart on load.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Everything that happens to a `Game`, in the order it happened. The event stream is the
+/// source of truth: folding it with `Game::apply` rebuilds the game at any point in its life.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum GameEvent {
-    Rolled { player_id: PlayerId, value: u32 },
-    GameOver { winner_id: PlayerId, loser_id: PlayerId },
+    /// Always the first event of a stream.
+    Created {
+        game_id: GameId,
+        host_id: PlayerId,
+        settings: GameSettings,
+    },
+    ServerSeedCommitted {
+        commitment: String,
+    },
+    ClientSeedSet {
+        player_id: PlayerId,
+        client_seed: String,
+    },
+    PlayerJoined {
+        player_id: PlayerId,
+    },
+    PlayerLeft {
+        player_id: PlayerId,
+    },
+    PlayerKicked {
+        host_id: PlayerId,
+        player_id: PlayerId,
+    },
+    ReadyChanged {
+        player_id: PlayerId,
+        ready: bool,
+    },
+    /// `assigned_by` is `None` when the game placed a joining player on the smallest team.
+    TeamAssigned {
+        player_id: PlayerId,
+        team_id: TeamId,
+        assigned_by: Option<PlayerId>,
+    },
+    /// `player_id` is the host who started the game.
+    GameStarted {
+        player_id: PlayerId,
+        turn_order: TurnOrderOutcome,
+        started_at: DateTime<Utc>,
+    },
+    Rolled(RollRecord),
+    /// A player rolled 1 and is out; `remaining` is the players still in the game.
+    PlayerEliminated {
+        player_id: PlayerId,
+        remaining: Vec<PlayerId>,
+    },
+    /// A losing roll or forfeit in a team game takes the rest of the team out with `player_id`.
+    TeamEliminated {
+        team_id: TeamId,
+        players: Vec<PlayerId>,
+        remaining: Vec<PlayerId>,
+    },
+    /// A player resigned or was forfeited by the server. `winner_id` is set when that ended a
+    /// free-for-all; team games name the winners in `GameOver`.
+    PlayerForfeited {
+        player_id: PlayerId,
+        winner_id: Option<PlayerId>,
+        remaining: Vec<PlayerId>,
+        forfeited_at: DateTime<Utc>,
+    },
+    /// `losing_team` starts with the player whose roll or forfeit ended the game. `standings` runs
+    /// from the winners down to the first player eliminated. `forfeited` is true when the game
+    /// ended on a forfeit instead of a losing roll.
+    GameOver {
+        winning_team: Team,
+        losing_team: Team,
+        standings: Vec<PlayerId>,
+        forfeited: bool,
+    },
+    /// `reconnect_deadline` is `None` when the table waits indefinitely.
+    GamePaused {
+        player_id: PlayerId,
+        reconnect_deadline: Option<DateTime<Utc>>,
+    },
+    PlayerReconnected {
+        player_id: PlayerId,
+        reconnected_at: DateTime<Utc>,
+    },
+    ServerSeedRevealed {
+        server_seed: ServerSeed,
+    },
+    SpectatorLimitChanged {
+        max_spectators: usize,
+    },
+    MatchLinked {
+        match_id: MatchId,
+    },
+    TournamentLinked {
+        tournament_id: TournamentId,
+    },
+    RematchAccepted {
+        player_id: PlayerId,
+    },
+    /// The rematch that followed this game.
+    RematchCreated {
+        game_id: GameId,
+    },
+    /// The rematch could not be opened, so the link and the votes are dropped and the table can vote again.
+    RematchCancelled {
+        game_id: GameId,
+    },
+    /// The game this one is a rematch of.
+    RematchOf {
+        game_id: GameId,
+    },
+}
+
+/// A `GameEvent` as stored in the game's stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Position in the stream, starting at 1. A game folded up to here has this `version`.
+    pub sequence: u64,
+    pub schema: u32,
+    pub recorded_at: DateTime<Utc>,
+    pub event: GameEvent,
+}
+
+/// One link in the roll chain: who rolled, in which range, and what came up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollRecord {
+    pub player_id: PlayerId,
+    pub min: u32,
+    pub max: u32,
+    pub value: u32,
+    /// Position of the roll in the game's seeded roll stream.
+    pub nonce: u64,
+    pub rolled_at: DateTime<Utc>,
+}
+
+/// How the seating was settled at the start of a game. `rolls` holds the draws behind a shuffle
+/// or roll-off, and is empty when the host simply goes first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnOrderOutcome {
+    pub policy: TurnOrder,
+    pub order: Vec<PlayerId>,
+    pub rolls: Vec<RollRecord>,
+}
+
+/// A state-changing input to a `Game`, recorded in order so the game can be replayed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PlayerAction {
+    Join {
+        player_id: PlayerId,
+    },
+    ClientSeed {
+        player_id: PlayerId,
+        client_seed: String,
+    },
+    Ready {
+        player_id: PlayerId,
+        ready: bool,
+    },
+    Team {
+        assigned_by: PlayerId,
+        player_id: PlayerId,
+        team_id: TeamId,
+    },
+    Start {
+        player_id: PlayerId,
+        started_at: DateTime<Utc>,
+    },
+    Leave {
+        player_id: PlayerId,
+    },
+    Kick {
+        host_id: PlayerId,
+        player_id: PlayerId,
+    },
+    Roll {
+        player_id: PlayerId,
+        rolled_at: DateTime<Utc>,
+    },
+    Disconnect {
+        player_id: PlayerId,
+        reconnect_deadline: Option<DateTime<Utc>>,
+    },
+    Reconnect {
+        player_id: PlayerId,
+        reconnected_at: DateTime<Utc>,
+    },
+    Forfeit {
+        player_id: PlayerId,
+        forfeited_at: DateTime<Utc>,
+    },
+    Rematch {
+        player_id: PlayerId,
+    },
+    SpectatorLimit {
+        host_id: PlayerId,
+        max_spectators: usize,
+    },
+    // Links the server makes rather than moves a player makes; replayed so the state ends up the same.
+    MatchLinked {
+        match_id: MatchId,
+    },
+    TournamentLinked {
+        tournament_id: TournamentId,
+    },
+    RematchCreated {
+        game_id: GameId,
+    },
+    RematchCancelled {
+        game_id: GameId,
+    },
+    RematchOf {
+        game_id: GameId,
+    },
 }
 
 #[cfg(test)]
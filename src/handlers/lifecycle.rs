@@ -0,0 +1,648 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    data::ServerMessage,
+    error::AppError,
+    game::{
+        fairness::{roll_draws, FairRoller, ServerSeed},
+        settings::TurnOrder,
+        types::{GameEvent, RecordedEvent},
+        Game, GameError, GameId, GameSettings, GameStatus, Match, MatchId, PlayerId, TeamId, Tournament, TournamentId,
+    },
+    handlers::ws::{
+        broadcast_bracket, broadcast_message, clear_spectator_sessions, remove_player_session, spectator_count,
+    },
+    leaderboards::LeaderboardUpdate,
+    state::SharedState,
+};
+
+// ==============================================================================
+// === Game lifecycle shared by the REST and WebSocket handlers
+// =============================================================================
+
+/// How many times `update_game`, `update_match` and `update_tournament` run a command before giving up on
+/// a record that keeps changing under it.
+const SAVE_ATTEMPTS: usize = 3;
+
+/// How many rolls ahead `prepare_rolls` stores draws for.
+const PREPARED_ROLLS: u64 = 64;
+
+/// Load the game, run `command` on it and save the result. If another request saved the game first,
+/// the command is run again on a fresh copy; after `SAVE_ATTEMPTS` the conflict is handed back.
+pub(crate) async fn update_game<T>(
+    state: &SharedState,
+    game_id: GameId,
+    mut command: impl FnMut(&mut Game) -> Result<T, AppError>,
+) -> Result<(Game, T), AppError> {
+    let mut attempt = 1;
+    loop {
+        let mut game = state.repository.load_game(game_id).await?;
+        let output = command(&mut game)?;
+        match state.repository.save_game(&game).await {
+            Ok(()) => return Ok((game, output)),
+            Err(AppError::VersionConflict { version, .. }) if attempt < SAVE_ATTEMPTS => {
+                tracing::debug!(game_id = %game_id, version = version, attempt = attempt, "Save conflicted; retrying.");
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Like `update_game`, for a match.
+pub(crate) async fn update_match<T>(
+    state: &SharedState,
+    match_id: MatchId,
+    mut command: impl FnMut(&mut Match) -> Result<T, AppError>,
+) -> Result<(Match, T), AppError> {
+    let mut attempt = 1;
+    loop {
+        let mut series = state.repository.load_match(match_id).await?;
+        let output = command(&mut series)?;
+        match state.repository.save_match(&mut series).await {
+            Ok(()) => return Ok((series, output)),
+            Err(AppError::MatchConflict { version, .. }) if attempt < SAVE_ATTEMPTS => {
+                tracing::debug!(
+                    match_id = %match_id, version = version, attempt = attempt,
+                    "Save conflicted; retrying."
+                );
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Like `update_game`, for a tournament.
+pub(crate) async fn update_tournament<T>(
+    state: &SharedState,
+    tournament_id: TournamentId,
+    mut command: impl FnMut(&mut Tournament) -> Result<T, AppError>,
+) -> Result<(Tournament, T), AppError> {
+    let mut attempt = 1;
+    loop {
+        let mut tournament = state.repository.load_tournament(tournament_id).await?;
+        let output = command(&mut tournament)?;
+        match state.repository.save_tournament(&mut tournament).await {
+            Ok(()) => return Ok((tournament, output)),
+            Err(AppError::TournamentConflict { version, .. }) if attempt < SAVE_ATTEMPTS => {
+                tracing::debug!(
+                    tournament_id = %tournament_id, version = version, attempt = attempt,
+                    "Save conflicted; retrying."
+                );
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// The game from the repository, or from the archive once the repository has let a finished game expire.
+pub(crate) async fn load_any_game(state: &SharedState, game_id: GameId) -> Result<Game, AppError> {
+    match state.repository.load_game(game_id).await {
+        Err(AppError::GameNotFound(_)) => state.archive.load_archived_game(game_id).await,
+        loaded => loaded,
+    }
+}
+
+/// Like `load_any_game`, for the event stream.
+pub(crate) async fn load_any_events(state: &SharedState, game_id: GameId) -> Result<Vec<RecordedEvent>, AppError> {
+    let events = state.repository.load_events(game_id, 0).await?;
+    if !events.is_empty() {
+        return Ok(events);
+    }
+    state.archive.load_archived_events(game_id).await
+}
+
+/// Create a game and commit to its server seed. The seed is stored; the game is left for the caller to save.
+pub(crate) async fn open_game(
+    state: &SharedState,
+    game_id: GameId,
+    host_id: PlayerId,
+    settings: GameSettings,
+    client_seed: Option<String>,
+) -> Result<Game, AppError> {
+    let mut game = Game::restore(game_id, host_id, settings);
+
+    // Commit to the server seed before anyone can roll.
+    let server_seed = ServerSeed::generate();
+    game.commit_server_seed(server_seed.commitment())?;
+    if let Some(client_seed) = client_seed {
+        game.set_client_seed(host_id, client_seed)?;
+    }
+
+    state.repository.save_server_seed(game.get_id(), &server_seed).await?;
+    Ok(game)
+}
+
+/// Open a game with `order` already seated on `teams` and start it, escrowing stakes if it has a
+/// wager. Everyone is already committed, so the lobby is skipped. The game is left for the caller to save.
+async fn open_seated_game(
+    state: &SharedState,
+    game_id: GameId,
+    order: &[PlayerId],
+    teams: &BTreeMap<PlayerId, TeamId>,
+    settings: GameSettings,
+) -> Result<Game, AppError> {
+    let mut game = open_game(state, game_id, order[0], settings, None).await?;
+    for player_id in &order[1..] {
+        game.join(*player_id)?;
+        game.set_ready(*player_id, true)?;
+    }
+    for (player_id, team_id) in teams {
+        game.assign_team(order[0], *player_id, *team_id)?;
+    }
+    let server_seed = state.repository.load_server_seed(game.get_id()).await?;
+    game.start(order[0], &mut FairRoller::new(server_seed.clone(), game.get_fairness()))?;
+    prepare_rolls(state, &game, &server_seed).await;
+
+    let wager = game.get_settings().wager;
+    if wager > 0 {
+        state
+            .repository
+            .escrow_stakes(game.get_id(), game.get_players(), wager)
+            .await?;
+    }
+    Ok(game)
+}
+
+/// Put a new game id on the match for its next game, and return it with the seating. Claimed in the same
+/// save as whatever made the game due, so the series always lists the game it is waiting on.
+pub(crate) fn claim_match_game(series: &mut Match) -> Result<(GameId, Vec<PlayerId>), GameError> {
+    let order = series.next_turn_order();
+    let game_id = GameId::new();
+    series.add_game(game_id)?;
+    Ok((game_id, order))
+}
+
+/// Seat and start the match game claimed as `game_id`, escrowing stakes if the match has a wager.
+pub(crate) async fn start_match_game(
+    state: &SharedState,
+    series: &Match,
+    game_id: GameId,
+    order: &[PlayerId],
+) -> Result<Game, AppError> {
+    let mut game = open_seated_game(state, game_id, order, &BTreeMap::new(), series.get_settings().clone()).await?;
+    game.set_match_id(series.get_id());
+    state.repository.save_game(&game).await?;
+
+    tracing::info!(match_id = %series.get_id(), game_id = %game_id, "Match game started.");
+    Ok(game)
+}
+
+/// Call off a match whose claimed game could not start; left as it is, it would wait forever on that game.
+pub(crate) async fn void_match(state: &SharedState, match_id: MatchId, reason: &AppError) -> Result<Match, AppError> {
+    tracing::warn!(match_id = %match_id, "Match game could not start; voiding the match: {}", reason);
+    let (series, ()) = update_match(state, match_id, |series| Ok(series.void()?)).await?;
+    Ok(series)
+}
+
+/// Put a new game id on every bracket pairing that is ready. Claimed in the same save as whatever made
+/// them ready, so no pairing is given two games.
+pub(crate) fn claim_ready_pairings(tournament: &mut Tournament) -> Result<Vec<(usize, usize, GameId)>, GameError> {
+    let mut claimed = vec![];
+    for (round, index) in tournament.get_ready_pairings() {
+        let game_id = GameId::new();
+        tournament.set_game(round, index, game_id)?;
+        claimed.push((round, index, game_id));
+    }
+    Ok(claimed)
+}
+
+/// Seat and start the games of the `claimed` pairings, then tell everyone following the tournament.
+pub(crate) async fn start_tournament_games(
+    state: &SharedState,
+    tournament: &Tournament,
+    claimed: Vec<(usize, usize, GameId)>,
+) -> Result<(), AppError> {
+    for (round, index, game_id) in claimed {
+        let order: Vec<PlayerId> = tournament
+            .get_pairing(round, index)
+            .map(|pairing| pairing.players.iter().flatten().copied().collect())
+            .unwrap_or_default();
+        let settings = tournament.get_settings().clone();
+        let mut game = open_seated_game(state, game_id, &order, &BTreeMap::new(), settings).await?;
+        game.set_tournament_id(tournament.get_id());
+        state.repository.save_game(&game).await?;
+
+        tracing::info!(
+            tournament_id = %tournament.get_id(), game_id = %game_id, round = round,
+            "Tournament game started."
+        );
+    }
+    publish_bracket(state, tournament).await;
+    Ok(())
+}
+
+/// Send the whole bracket to everyone following the tournament.
+pub(crate) async fn publish_bracket(state: &SharedState, tournament: &Tournament) {
+    broadcast_bracket(
+        state,
+        tournament.get_id(),
+        ServerMessage::BracketState(tournament.clone()),
+    )
+    .await;
+}
+
+/// Tell the table the game is under way, and who rolls first. The draws for the opening rolls are stored
+/// first, so the first roll can already take the repository's fast path.
+pub(crate) async fn announce_start(state: &SharedState, game: &Game) {
+    match state.repository.load_server_seed(game.get_id()).await {
+        Ok(server_seed) => prepare_rolls(state, game, &server_seed).await,
+        Err(e) => tracing::error!(game_id = %game.get_id(), "Failed to load server seed: {}", e),
+    }
+    broadcast_message(state, game.get_id(), ServerMessage::GameStarted { game: game.clone() }).await;
+    if let Some(outcome) = game.get_turn_order() {
+        let message = ServerMessage::TurnOrderDecided {
+            policy: outcome.policy,
+            order: outcome.order.clone(),
+            rolls: outcome.rolls.clone(),
+        };
+        broadcast_message(state, game.get_id(), message).await;
+    }
+    publish_state(state, game).await;
+}
+
+/// Record `player_id`'s vote for a rematch. Once everyone is in, open the rematch with the same
+/// settings and the loser rolling first, and point every socket on the old game at it.
+pub(crate) async fn accept_rematch(state: &SharedState, game_id: GameId, player_id: PlayerId) -> Result<(), AppError> {
+    // The vote that completes the set claims the rematch in the same save, so only one request opens it.
+    let rematch_id = GameId::new();
+    let (game, claimed) = update_game(state, game_id, |game| {
+        let everyone_in = game.accept_rematch(player_id)?;
+        if everyone_in {
+            game.set_rematch_id(rematch_id);
+        }
+        Ok(everyone_in)
+    })
+    .await?;
+    if !claimed {
+        let accepted = game.get_rematch_votes().iter().cloned().collect();
+        broadcast_message(state, game_id, ServerMessage::RematchVote { player_id, accepted }).await;
+        return Ok(());
+    }
+
+    let rematch = match open_rematch(state, &game, rematch_id).await {
+        Ok(rematch) => rematch,
+        Err(e) => {
+            // Hand back anything escrowed and let the table vote again rather than point at a game that is not there.
+            tracing::warn!(game_id = %game_id, error = %e, "Rematch could not be opened.");
+            refund_wager(state, rematch_id).await;
+            let cancelled = update_game(state, game_id, |game| Ok(game.cancel_rematch(rematch_id)?)).await;
+            if let Err(e) = cancelled {
+                tracing::error!(game_id = %game_id, "Failed to cancel the rematch: {}", e);
+            }
+            let message = ServerMessage::Error { message: format!("The rematch could not be started: {e}") };
+            broadcast_message(state, game_id, message).await;
+            return Err(e);
+        }
+    };
+
+    tracing::info!(game_id = %game_id, rematch_id = %rematch.get_id(), "Rematch started.");
+    let message = ServerMessage::RematchReady { previous_game_id: game_id, game_id: rematch.get_id() };
+    broadcast_message(state, game_id, message).await;
+    Ok(())
+}
+
+/// Open and save the rematch of `game` under `rematch_id`, with the loser in the first seat.
+async fn open_rematch(state: &SharedState, game: &Game, rematch_id: GameId) -> Result<Game, AppError> {
+    // The loser takes the first seat, so the table's seating policy does not apply. Teams stay together.
+    let settings = GameSettings { turn_order: TurnOrder::HostFirst, ..game.get_settings().clone() };
+    let mut rematch =
+        open_seated_game(state, rematch_id, &game.get_rematch_order(), game.get_teams(), settings).await?;
+    rematch.set_rematch_of(game.get_id());
+    state.repository.save_game(&rematch).await?;
+    Ok(rematch)
+}
+
+/// Take `player_id` out of the lobby, then save and tell the table who hosts now.
+pub(crate) async fn leave_lobby(state: &SharedState, game_id: GameId, player_id: PlayerId) -> Result<Game, AppError> {
+    let (game, ()) = update_game(state, game_id, |game| Ok(game.leave(player_id)?)).await?;
+
+    let host_id = game.get_host();
+    broadcast_message(state, game_id, ServerMessage::PlayerLeft { player_id, host_id }).await;
+    remove_player_session(state, game_id, player_id).await;
+    publish_state(state, &game).await;
+    Ok(game)
+}
+
+/// Have the host remove `player_id` from the lobby, then save and tell the table.
+pub(crate) async fn kick_from_lobby(
+    state: &SharedState,
+    game_id: GameId,
+    host_id: PlayerId,
+    player_id: PlayerId,
+) -> Result<Game, AppError> {
+    let (game, ()) = update_game(state, game_id, |game| Ok(game.kick(host_id, player_id)?)).await?;
+
+    // Sent before the session is dropped, so the kicked player hears about it too.
+    broadcast_message(state, game_id, ServerMessage::PlayerKicked { player_id }).await;
+    remove_player_session(state, game_id, player_id).await;
+    publish_state(state, &game).await;
+    Ok(game)
+}
+
+/// Put `player_id` on `team_id`, then save and tell the table. Only the host can move someone else.
+pub(crate) async fn choose_team(
+    state: &SharedState,
+    game_id: GameId,
+    assigned_by: PlayerId,
+    player_id: PlayerId,
+    team_id: TeamId,
+) -> Result<Game, AppError> {
+    let (game, ()) = update_game(state, game_id, |game| {
+        Ok(game.assign_team(assigned_by, player_id, team_id)?)
+    })
+    .await?;
+
+    broadcast_message(state, game_id, ServerMessage::TeamChanged { player_id, team_id }).await;
+    publish_state(state, &game).await;
+    Ok(game)
+}
+
+/// Host-only: change the spectator limit. Zero turns spectating off and drops everyone watching.
+pub(crate) async fn set_spectator_limit(
+    state: &SharedState,
+    game_id: GameId,
+    host_id: PlayerId,
+    max_spectators: usize,
+) -> Result<Game, AppError> {
+    let (game, ()) = update_game(state, game_id, |game| {
+        GameSettings { max_spectators, ..game.get_settings().clone() }.validate(&state.config.game)?;
+        Ok(game.set_max_spectators(host_id, max_spectators)?)
+    })
+    .await?;
+
+    if max_spectators == 0 {
+        clear_spectator_sessions(state, game_id).await;
+    }
+    publish_state(state, &game).await;
+    Ok(game)
+}
+
+/// Roll for `player_id` with the game's fair roller, then save and tell the table.
+pub(crate) async fn roll_for(state: &SharedState, game_id: GameId, player_id: PlayerId) -> Result<(), AppError> {
+    // Most rolls only pass the turn on, and the repository can take those in one step.
+    if let Some((game, events)) = state.repository.roll(game_id, player_id, Utc::now()).await? {
+        record_outcome(state, &game, events).await;
+        return Ok(());
+    }
+
+    let server_seed = state.repository.load_server_seed(game_id).await?;
+    let (game, events) = update_game(state, game_id, |game| {
+        let mut roller = FairRoller::new(server_seed.clone(), game.get_fairness());
+        let events = game.roll(player_id, &mut roller)?;
+        reveal_if_finished(game, &server_seed);
+        Ok(events)
+    })
+    .await?;
+    prepare_rolls(state, &game, &server_seed).await;
+    record_outcome(state, &game, events).await;
+    Ok(())
+}
+
+/// Store the draws for the game's next rolls, so the repository can take them without the server seed.
+/// Client seeds are only set while joining, so draws stored once the game starts stay good for the whole
+/// game. Called whenever a game is started or saved the slow way, so the fast path never runs out.
+async fn prepare_rolls(state: &SharedState, game: &Game, server_seed: &ServerSeed) {
+    if game.is_finished() {
+        return;
+    }
+    let fairness = game.get_fairness();
+    let first_nonce = fairness.get_nonce();
+    let draws: Vec<[u32; 2]> = (first_nonce..first_nonce + PREPARED_ROLLS)
+        .map(|nonce| roll_draws(server_seed, fairness.get_client_seeds(), nonce))
+        .collect();
+    if let Err(e) = state
+        .repository
+        .save_roll_draws(game.get_id(), first_nonce, &draws)
+        .await
+    {
+        tracing::error!(game_id = %game.get_id(), "Failed to store roll draws: {}", e);
+    }
+}
+
+/// Knock `player_id` out of the game, then save and tell the table.
+pub(crate) async fn forfeit_player(state: &SharedState, game_id: GameId, player_id: PlayerId) -> Result<(), AppError> {
+    let server_seed = state.repository.load_server_seed(game_id).await?;
+    let (game, events) = update_game(state, game_id, |game| {
+        let events = game.forfeit(player_id)?;
+        reveal_if_finished(game, &server_seed);
+        Ok(events)
+    })
+    .await?;
+    prepare_rolls(state, &game, &server_seed).await;
+    record_outcome(state, &game, events).await;
+    Ok(())
+}
+
+/// Reveal the seed once the game is over, so the finished game can be verified.
+fn reveal_if_finished(game: &mut Game, server_seed: &ServerSeed) {
+    if game.is_finished() {
+        if let Err(e) = game.reveal_server_seed(server_seed) {
+            tracing::error!("Failed to reveal server seed: {}", e);
+        }
+    }
+}
+
+async fn record_outcome(state: &SharedState, game: &Game, events: Vec<GameEvent>) {
+    publish_events(state, game, events).await;
+    publish_state(state, game).await;
+}
+
+/// Broadcast the full game state, followed by whichever clock is running.
+pub(crate) async fn publish_state(state: &SharedState, game: &Game) {
+    let game_id = game.get_id();
+    let remaining_ms = |deadline: DateTime<Utc>| (deadline - Utc::now()).num_milliseconds().max(0);
+    let timer = match (game.get_status(), game.get_current_player()) {
+        (GameStatus::PausedForReconnect(player_id), _) => {
+            game.get_reconnect_deadline()
+                .map(|deadline| ServerMessage::ReconnectTimer {
+                    player_id: *player_id,
+                    deadline,
+                    remaining_ms: remaining_ms(deadline),
+                })
+        }
+        (_, Some(player_id)) => game.get_turn_deadline().map(|deadline| ServerMessage::TurnTimer {
+            player_id: *player_id,
+            deadline,
+            remaining_ms: remaining_ms(deadline),
+        }),
+        _ => None,
+    };
+
+    let mut game = game.clone();
+    game.set_spectator_count(spectator_count(state, game_id).await);
+    broadcast_message(state, game_id, ServerMessage::GameState(game)).await;
+    if let Some(timer) = timer {
+        broadcast_message(state, game_id, timer).await;
+    }
+}
+
+/// Tell the table about each event, and wrap up the game when it is over.
+pub(crate) async fn publish_events(state: &SharedState, game: &Game, events: Vec<GameEvent>) {
+    let game_id = game.get_id();
+    for event in events {
+        match event {
+            GameEvent::Rolled(roll) => {
+                broadcast_message(
+                    state,
+                    game_id,
+                    ServerMessage::RollResult { player_id: roll.player_id, rolled_value: roll.value },
+                )
+                .await
+            }
+            GameEvent::PlayerEliminated { player_id, remaining } => {
+                broadcast_message(state, game_id, ServerMessage::PlayerEliminated { player_id, remaining }).await
+            }
+            GameEvent::TeamEliminated { team_id, players, remaining } => {
+                broadcast_message(
+                    state,
+                    game_id,
+                    ServerMessage::TeamEliminated { team_id, players, remaining },
+                )
+                .await
+            }
+            GameEvent::PlayerForfeited { player_id, winner_id, remaining, .. } => {
+                let message = ServerMessage::PlayerForfeited { player_id, winner_id, remaining };
+                broadcast_message(state, game_id, message).await
+            }
+            GameEvent::GameOver { winning_team, losing_team, standings, forfeited } => {
+                let winners = winning_team.players.clone();
+                let message = ServerMessage::GameOver { winning_team, losing_team, standings, forfeited };
+                broadcast_message(state, game_id, message).await;
+                finish_game(state, game, &winners).await;
+            }
+            // Lobby and bookkeeping events are announced by the handlers that record them.
+            _ => {}
+        }
+    }
+}
+
+async fn finish_game(state: &SharedState, game: &Game, winners: &[PlayerId]) {
+    match archive_game(state, game).await {
+        // The leaderboards are scored from the archive, so they only move once the game is in it.
+        Ok(()) => {
+            if let Err(e) = update_leaderboards(state, game).await {
+                tracing::error!(game_id = %game.get_id(), "Failed to update leaderboards: {}", e);
+            }
+        }
+        Err(e) => tracing::error!(game_id = %game.get_id(), "Failed to archive game: {}", e),
+    }
+    if game.get_settings().wager > 0 {
+        settle_wager(state, game.get_id(), winners).await;
+    }
+    // Matches and tournaments are played every player for themselves, so there is a single winner.
+    if let Err(e) = advance_match(state, game, winners[0]).await {
+        tracing::error!(game_id = %game.get_id(), "Failed to advance match: {}", e);
+    }
+    if let Err(e) = advance_tournament(state, game, winners[0]).await {
+        tracing::error!(game_id = %game.get_id(), "Failed to advance tournament: {}", e);
+    }
+}
+
+/// Copy the finished game and its event stream into the archive, where it outlives the repository's copy.
+async fn archive_game(state: &SharedState, game: &Game) -> Result<(), AppError> {
+    let events = state.repository.load_events(game.get_id(), 0).await?;
+    state.archive.archive_game(game, &events).await?;
+    tracing::info!(game_id = %game.get_id(), "Game archived.");
+    Ok(())
+}
+
+/// Rescore everyone who played the game from their lifetime stats, on every board at once.
+async fn update_leaderboards(state: &SharedState, game: &Game) -> Result<(), AppError> {
+    let min_games = state.config.game.leaderboard_min_games;
+    let mut updates = vec![];
+    for player_id in game.get_players() {
+        let stats = state.players.load_stats(*player_id).await?;
+        updates.extend(LeaderboardUpdate::for_player(*player_id, &stats, min_games));
+    }
+    state.repository.update_leaderboards(&updates).await
+}
+
+/// Pay the escrowed pot out to the winners and tell the table
+/// Pay the pot out to the winners, and tell the table.
+pub(crate) async fn settle_wager(state: &SharedState, game_id: GameId, winners: &[PlayerId]) {
+    match state.repository.settle_pot(game_id, winners).await {
+        Ok(0) => {}
+        Ok(amount) => {
+            tracing::info!(game_id = %game_id, winners = ?winners, amount = amount, "Pot settled.");
+            let message = ServerMessage::PotSettled { winners: winners.to_vec(), amount };
+            broadcast_message(state, game_id, message).await;
+        }
+        Err(e) => tracing::error!(game_id = %game_id, "Failed to settle pot: {}", e),
+    }
+}
+
+/// The winners named by the game over in a game's event stream; `None` if the game never ended.
+pub(crate) fn recorded_winners(events: &[RecordedEvent]) -> Option<Vec<PlayerId>> {
+    events.iter().rev().find_map(|recorded| match &recorded.event {
+        GameEvent::GameOver { winning_team, .. } => Some(winning_team.players.clone()),
+        _ => None,
+    })
+}
+
+/// Hand the escrowed stakes back to the players of a game that ended without a winner, and tell the table.
+pub(crate) async fn refund_wager(state: &SharedState, game_id: GameId) {
+    match state.repository.refund_pot(game_id).await {
+        Ok(0) => {}
+        Ok(amount) => {
+            tracing::info!(game_id = %game_id, amount = amount, "Pot refunded.");
+            broadcast_message(state, game_id, ServerMessage::PotRefunded { amount }).await;
+        }
+        Err(e) => tracing::error!(game_id = %game_id, "Failed to refund pot: {}", e),
+    }
+}
+
+/// Credit the game's winner on the series score and either start the next game or close the match. A match
+/// whose next game cannot start is voided, so it does not wait forever.
+async fn advance_match(state: &SharedState, game: &Game, winner_id: PlayerId) -> Result<(), AppError> {
+    let Some(match_id) = game.get_match_id() else {
+        return Ok(());
+    };
+    let (series, claimed) = update_match(state, match_id, |series| {
+        Ok(match series.record_result(game.get_id(), winner_id)? {
+            Some(_) => None,
+            None => Some(claim_match_game(series)?),
+        })
+    })
+    .await?;
+
+    let message = match claimed {
+        None => {
+            tracing::info!(match_id = %match_id, winner_id = %winner_id, "Match finished.");
+            ServerMessage::MatchOver { match_id, winner_id, wins: series.get_wins().clone() }
+        }
+        Some((next_game_id, order)) => match start_match_game(state, &series, next_game_id, &order).await {
+            Ok(_) => ServerMessage::MatchProgress { match_id, wins: series.get_wins().clone(), next_game_id },
+            Err(e) => {
+                let series = void_match(state, match_id, &e).await?;
+                ServerMessage::MatchVoided { match_id, wins: series.get_wins().clone(), reason: e.to_string() }
+            }
+        },
+    };
+    broadcast_message(state, game.get_id(), message).await;
+    Ok(())
+}
+
+/// Move the game's winner on in the bracket, start any pairing that is now ready, and show the
+/// table where the bracket stands.
+async fn advance_tournament(state: &SharedState, game: &Game, winner_id: PlayerId) -> Result<(), AppError> {
+    let Some(tournament_id) = game.get_tournament_id() else {
+        return Ok(());
+    };
+    let (tournament, (champion_id, claimed)) = update_tournament(state, tournament_id, |tournament| {
+        let champion_id = tournament.record_result(game.get_id(), winner_id)?;
+        Ok((champion_id, claim_ready_pairings(tournament)?))
+    })
+    .await?;
+
+    if let Some(champion_id) = champion_id {
+        tracing::info!(tournament_id = %tournament_id, champion_id = %champion_id, "Tournament finished.");
+    }
+    start_tournament_games(state, &tournament, claimed).await?;
+    broadcast_message(state, game.get_id(), ServerMessage::BracketState(tournament)).await;
+    Ok(())
+}
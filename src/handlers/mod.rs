@@ -1,5 +1,12 @@
+mod lifecycle;
 pub mod rest;
+pub mod timers;
 pub mod ws;
 
-pub use rest::{create_game_handler, get_game_handler, join_game_handler};
+pub use rest::{
+    create_game_handler, create_match_handler, get_balance_handler, get_events_handler, get_game_handler,
+    get_history_handler, get_ledger_handler, get_match_handler, join_game_handler, kick_player_handler,
+    leave_game_handler, spectator_limit_handler, start_game_handler, verify_game_handler,
+};
+pub use timers::spawn_deadline_sweeper;
 pub use ws::websocket_handler;
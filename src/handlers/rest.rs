@@ -1,15 +1,36 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use tracing::instrument;
 
 use crate::{
-    data::{CreateGameRequest, CreateGameResponse, JoinGameRequest},
+    data::{
+        AssignTeamRequest, BalanceResponse, CreateGameRequest, CreateGameResponse, CreateMatchRequest,
+        CreatePlayerRequest, CreateTournamentRequest, JoinGameRequest, KickPlayerRequest, LeaderboardQuery,
+        LeaderboardResponse, LeaveGameRequest, PlayerResponse, RegisterRequest, SpectatorLimitRequest,
+        StartGameRequest, WithdrawRequest,
+    },
     error::AppError,
-    game::{Game, GameId, GameStatus, PlayerId},
+    game::{
+        fairness::{FairRoller, FairnessReport},
+        roller::ThreadRngRoller,
+        series::DEFAULT_BEST_OF,
+        settings::TurnOrder,
+        types::{RecordedEvent, RollRecord},
+        Game, GameError, GameId, GameSettings, GameStatus, Match, MatchId, PlayerId, Tournament, TournamentId,
+    },
+    handlers::lifecycle::{
+        announce_start, choose_team, claim_match_game, claim_ready_pairings, kick_from_lobby, leave_lobby,
+        load_any_events, load_any_game, open_game, publish_bracket, refund_wager, set_spectator_limit,
+        start_match_game, start_tournament_games, update_game, update_tournament, void_match,
+    },
+    handlers::ws::spectator_count,
+    leaderboards::{LeaderboardKind, DEFAULT_LEADERBOARD_PAGE, MAX_LEADERBOARD_PAGE},
+    players::{PlayerProfile, PlayerStats},
     state::SharedState,
+    wallet::LedgerEntry,
 };
 
 // ==============================================================================
@@ -24,9 +45,23 @@ pub async fn create_game_handler(
     tracing::info!(host_id = ?payload.host_id, "Attempting to create game");
 
     let host_id = payload.host_id.unwrap_or_else(PlayerId::new);
-    let new_game = Game::new(host_id);
-    let game_id = new_game.get_id();
+    let defaults = GameSettings::default();
+    let settings = GameSettings {
+        max_players: payload.max_players.unwrap_or(defaults.max_players),
+        starting_max: payload.starting_max.unwrap_or(defaults.starting_max),
+        wager: payload.wager.unwrap_or(defaults.wager),
+        turn_timeout_secs: payload.turn_timeout_secs.unwrap_or(defaults.turn_timeout_secs),
+        on_timeout: payload.on_timeout.unwrap_or(defaults.on_timeout),
+        reconnect_grace_secs: payload.reconnect_grace_secs.unwrap_or(defaults.reconnect_grace_secs),
+        rules: payload.rules.unwrap_or(defaults.rules),
+        turn_order: payload.turn_order.unwrap_or(defaults.turn_order),
+        max_spectators: payload.max_spectators.unwrap_or(defaults.max_spectators),
+        teams: payload.teams.unwrap_or(defaults.teams),
+    };
+    settings.validate(&state.config.game)?;
 
+    let new_game = open_game(&state, GameId::new(), host_id, settings, payload.client_seed).await?;
+    let game_id = new_game.get_id();
     state.repository.save_game(&new_game).await?;
     let response = CreateGameResponse { game_id, host_id };
 
@@ -39,7 +74,8 @@ pub async fn get_game_handler(
     State(state): State<SharedState>,
     Path(game_id): Path<GameId>,
 ) -> Result<Json<Game>, AppError> {
-    let game = state.repository.load_game(game_id).await?;
+    let mut game = load_any_game(&state, game_id).await?;
+    game.set_spectator_count(spectator_count(&state, game_id).await);
     Ok(Json(game))
 }
 
@@ -49,41 +85,406 @@ pub async fn join_game_handler(
     Path(game_id): Path<GameId>,
     Json(payload): Json<JoinGameRequest>,
 ) -> Result<Json<Game>, AppError> {
-    let mut game = state.repository.load_game(game_id).await?;
-
     let joining_player = payload.player_id.unwrap_or_else(PlayerId::new);
+    // Read once up front, since the join below is run again if it races another request.
+    let balance = state.repository.get_balance(joining_player).await?;
 
-    match *game.get_status() {
-        GameStatus::WaitingForPlayers => {
-            if game.get_players().contains(&joining_player) {
-                tracing::info!(game_id = %game_id, player_id = %joining_player, "Host re-joined waiting lobby.");
-                Ok(())
-            } else {
-                game.join(joining_player).map_err(AppError::from)
+    let (game, ()) = update_game(&state, game_id, |game| {
+        let is_player = game.get_players().contains(&joining_player);
+        match (game.get_status().clone(), is_player) {
+            (GameStatus::WaitingForPlayers, false) => {
+                let wager = game.get_settings().wager;
+                if wager > 0 && !payload.accept_wager {
+                    return Err(GameError::WagerNotAccepted { wager }.into());
+                }
+                if wager > 0 && balance < wager {
+                    // Checked again when the stakes are escrowed at start; this just keeps the seat open.
+                    return Err(GameError::InsufficientFunds { player_id: joining_player }.into());
+                }
+                game.join(joining_player)?;
             }
+            // The game itself turns away a duplicate join, or a join once play has started.
+            (GameStatus::WaitingForPlayers, true) | (_, false) => game.join(joining_player)?,
+            // Players coming back to a game that is already under way.
+            (_, true) => game.reconnect(joining_player)?,
         }
-        _ => {
-            if game.get_players().contains(&joining_player) {
-                game.reconnect(joining_player).map_err(AppError::from)
-            } else {
-                // ALERT: Random player trying to join an active game
-                tracing::warn!(game_id = %game_id, intruder = %joining_player, "Unauthorized join attempt on active game.");
-                Err(AppError::Forbidden("Unauthorized".to_string()))
+
+        if let Some(client_seed) = &payload.client_seed {
+            game.set_client_seed(joining_player, client_seed.clone())?;
+        }
+        Ok(())
+    })
+    .await?;
+
+    tracing::info!(game_id = %game_id, player_id = %joining_player, "Player joined/reconnected successfully.");
+    Ok(Json(game))
+}
+
+/// Host-only: close the lobby once everyone is ready and begin play.
+#[instrument(skip(state))]
+pub async fn start_game_handler(
+    State(state): State<SharedState>,
+    Path(game_id): Path<GameId>,
+    Json(payload): Json<StartGameRequest>,
+) -> Result<Json<Game>, AppError> {
+    let server_seed = state.repository.load_server_seed(game_id).await?;
+    let lobby = state.repository.load_game(game_id).await?;
+    let staked = lobby.get_players().to_vec();
+
+    // Lock everyone's stake before the first roll. A start that would be refused moves no gold; escrow
+    // is idempotent, so a repeated start finds the pot already in place.
+    let wager = lobby.get_settings().wager;
+    if wager > 0 {
+        let mut roller = FairRoller::new(server_seed.clone(), lobby.get_fairness());
+        lobby.clone().start(payload.player_id, &mut roller)?;
+        state.repository.escrow_stakes(game_id, &staked, wager).await?;
+        tracing::info!(game_id = %game_id, wager = wager, "Stakes escrowed.");
+    }
+
+    let started = update_game(&state, game_id, |game| {
+        // Someone joined or left after the stakes were taken, so the pot no longer matches the table.
+        if game.get_players() != staked.as_slice() {
+            return Err(AppError::VersionConflict { game_id, version: game.get_version() });
+        }
+        let mut roller = FairRoller::new(server_seed.clone(), game.get_fairness());
+        Ok(game.start(payload.player_id, &mut roller)?)
+    })
+    .await;
+    let game = match started {
+        Ok((game, ())) => game,
+        Err(e) => {
+            if wager > 0 && still_in_lobby(&state, game_id).await {
+                refund_wager(&state, game_id).await;
             }
+            return Err(e);
         }
-    }?;
+    };
+    announce_start(&state, &game).await;
 
-    state.repository.save_game(&game).await?;
+    tracing::info!(game_id = %game_id, "Game started.");
+    Ok(Json(game))
+}
 
-    tracing::info!(game_id = %game_id, player_id = %joining_player, "Player joined/reconnected successfully.");
+/// Whether the game has yet to start, so stakes escrowed for it can go back.
+async fn still_in_lobby(state: &SharedState, game_id: GameId) -> bool {
+    match state.repository.load_game(game_id).await {
+        Ok(game) => *game.get_status() == GameStatus::WaitingForPlayers,
+        Err(_) => false,
+    }
+}
+
+/// Give up a lobby seat. Hosting passes to the next seat if the host leaves.
+#[instrument(skip(state))]
+pub async fn leave_game_handler(
+    State(state): State<SharedState>,
+    Path(game_id): Path<GameId>,
+    Json(payload): Json<LeaveGameRequest>,
+) -> Result<Json<Game>, AppError> {
+    let game = leave_lobby(&state, game_id, payload.player_id).await?;
+    tracing::info!(game_id = %game_id, player_id = %payload.player_id, "Player left the lobby.");
+    Ok(Json(game))
+}
+
+/// Host-only: remove a player from the lobby and bar them from joining again.
+#[instrument(skip(state))]
+pub async fn kick_player_handler(
+    State(state): State<SharedState>,
+    Path(game_id): Path<GameId>,
+    Json(payload): Json<KickPlayerRequest>,
+) -> Result<Json<Game>, AppError> {
+    let game = kick_from_lobby(&state, game_id, payload.host_id, payload.player_id).await?;
+    tracing::info!(game_id = %game_id, player_id = %payload.player_id, "Player kicked from the lobby.");
+    Ok(Json(game))
+}
+
+/// Move a lobby player to another team. The host can move anyone; everyone else only themselves.
+#[instrument(skip(state))]
+pub async fn assign_team_handler(
+    State(state): State<SharedState>,
+    Path(game_id): Path<GameId>,
+    Json(payload): Json<AssignTeamRequest>,
+) -> Result<Json<Game>, AppError> {
+    let assigned_by = payload.assigned_by.unwrap_or(payload.player_id);
+    let game = choose_team(&state, game_id, assigned_by, payload.player_id, payload.team_id).await?;
+    tracing::info!(game_id = %game_id, player_id = %payload.player_id, team_id = %payload.team_id, "Team changed.");
     Ok(Json(game))
 }
 
+#[instrument(skip(state))]
+pub async fn spectator_limit_handler(
+    State(state): State<SharedState>,
+    Path(game_id): Path<GameId>,
+    Json(payload): Json<SpectatorLimitRequest>,
+) -> Result<Json<Game>, AppError> {
+    let game = set_spectator_limit(&state, game_id, payload.host_id, payload.max_spectators).await?;
+    tracing::info!(game_id = %game_id, max_spectators = payload.max_spectators, "Spectator limit changed.");
+    Ok(Json(game))
+}
+
+/// Recompute every roll of a finished game from its revealed server seed.
+#[instrument(skip(state))]
+pub async fn verify_game_handler(
+    State(state): State<SharedState>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<FairnessReport>, AppError> {
+    let game = load_any_game(&state, game_id).await?;
+    let report = game.verify_rolls()?;
+    Ok(Json(report))
+}
+
+/// Everything that has happened to a game, oldest first.
+#[instrument(skip(state))]
+pub async fn get_events_handler(
+    State(state): State<SharedState>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<Vec<RecordedEvent>>, AppError> {
+    let events = load_any_events(&state, game_id).await?;
+    if events.is_empty() {
+        return Err(AppError::GameNotFound(game_id));
+    }
+    Ok(Json(events))
+}
+
+/// The full roll chain of a game, so late joiners can catch up.
+#[instrument(skip(state))]
+pub async fn get_history_handler(
+    State(state): State<SharedState>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<Vec<RollRecord>>, AppError> {
+    let game = load_any_game(&state, game_id).await?;
+    Ok(Json(game.get_history().to_vec()))
+}
+
+/// Start a best-of-N series and its first game. Every player is seated straight away.
+#[instrument(skip(state))]
+pub async fn create_match_handler(
+    State(state): State<SharedState>,
+    Json(payload): Json<CreateMatchRequest>,
+) -> Result<(StatusCode, Json<Match>), AppError> {
+    let defaults = GameSettings::default();
+    let settings = GameSettings {
+        max_players: payload.players.len(),
+        starting_max: payload.starting_max.unwrap_or(defaults.starting_max),
+        wager: payload.wager.unwrap_or(defaults.wager),
+        turn_timeout_secs: payload.turn_timeout_secs.unwrap_or(defaults.turn_timeout_secs),
+        on_timeout: payload.on_timeout.unwrap_or(defaults.on_timeout),
+        reconnect_grace_secs: payload.reconnect_grace_secs.unwrap_or(defaults.reconnect_grace_secs),
+        rules: payload.rules.unwrap_or(defaults.rules),
+        // The series rotates the seating itself.
+        turn_order: TurnOrder::HostFirst,
+        max_spectators: defaults.max_spectators,
+        teams: 0,
+    };
+    settings.validate(&state.config.game)?;
+
+    let mut series = Match::new(payload.players, payload.best_of.unwrap_or(DEFAULT_BEST_OF), settings)?;
+    let (game_id, order) = claim_match_game(&mut series)?;
+    state.repository.save_match(&mut series).await?;
+    if let Err(e) = start_match_game(&state, &series, game_id, &order).await {
+        void_match(&state, series.get_id(), &e).await?;
+        return Err(e);
+    }
+
+    tracing::info!(match_id = %series.get_id(), best_of = series.get_best_of(), "Match created successfully");
+    Ok((StatusCode::CREATED, Json(series)))
+}
+
+#[instrument(skip(state))]
+pub async fn get_match_handler(
+    State(state): State<SharedState>,
+    Path(match_id): Path<MatchId>,
+) -> Result<Json<Match>, AppError> {
+    let series = state.repository.load_match(match_id).await?;
+    Ok(Json(series))
+}
+
+/// Open registration for a single-elimination tournament. Every pairing is played one against one.
+#[instrument(skip(state))]
+pub async fn create_tournament_handler(
+    State(state): State<SharedState>,
+    Json(payload): Json<CreateTournamentRequest>,
+) -> Result<(StatusCode, Json<Tournament>), AppError> {
+    let limit = state.config.game.max_tournament_players;
+    if payload.max_players < 2 || payload.max_players > limit {
+        return Err(GameError::InvalidTournamentSize { min: 2, max: limit }.into());
+    }
+    let defaults = GameSettings::default();
+    let settings = GameSettings {
+        max_players: 2,
+        starting_max: payload.starting_max.unwrap_or(defaults.starting_max),
+        wager: 0,
+        turn_timeout_secs: payload.turn_timeout_secs.unwrap_or(defaults.turn_timeout_secs),
+        on_timeout: payload.on_timeout.unwrap_or(defaults.on_timeout),
+        reconnect_grace_secs: payload.reconnect_grace_secs.unwrap_or(defaults.reconnect_grace_secs),
+        rules: payload.rules.unwrap_or(defaults.rules),
+        // The bracket decides the seating.
+        turn_order: TurnOrder::HostFirst,
+        max_spectators: defaults.max_spectators,
+        teams: 0,
+    };
+    settings.validate(&state.config.game)?;
+
+    let mut tournament = Tournament::new(payload.host_id, payload.max_players, payload.seeding, settings)?;
+    state.repository.save_tournament(&mut tournament).await?;
+
+    tracing::info!(tournament_id = %tournament.get_id(), "Tournament created successfully");
+    Ok((StatusCode::CREATED, Json(tournament)))
+}
+
+#[instrument(skip(state))]
+pub async fn get_tournament_handler(
+    State(state): State<SharedState>,
+    Path(tournament_id): Path<TournamentId>,
+) -> Result<Json<Tournament>, AppError> {
+    let tournament = state.repository.load_tournament(tournament_id).await?;
+    Ok(Json(tournament))
+}
+
+#[instrument(skip(state))]
+pub async fn register_handler(
+    State(state): State<SharedState>,
+    Path(tournament_id): Path<TournamentId>,
+    Json(payload): Json<RegisterRequest>,
+) -> Result<Json<Tournament>, AppError> {
+    // Seeded from the player's archived record, never from anything the client claims.
+    let stats = state.players.load_stats(payload.player_id).await?;
+    let rating = stats.rating(state.config.game.leaderboard_min_games);
+    let (tournament, ()) = update_tournament(&state, tournament_id, |tournament| {
+        Ok(tournament.register(payload.player_id, rating)?)
+    })
+    .await?;
+    publish_bracket(&state, &tournament).await;
+
+    tracing::info!(tournament_id = %tournament_id, player_id = %payload.player_id, "Player registered.");
+    Ok(Json(tournament))
+}
+
+#[instrument(skip(state))]
+pub async fn withdraw_handler(
+    State(state): State<SharedState>,
+    Path(tournament_id): Path<TournamentId>,
+    Json(payload): Json<WithdrawRequest>,
+) -> Result<Json<Tournament>, AppError> {
+    let (tournament, ()) = update_tournament(&state, tournament_id, |tournament| {
+        Ok(tournament.withdraw(payload.player_id)?)
+    })
+    .await?;
+    publish_bracket(&state, &tournament).await;
+
+    tracing::info!(tournament_id = %tournament_id, player_id = %payload.player_id, "Player withdrew.");
+    Ok(Json(tournament))
+}
+
+/// Host-only: close registration, draw the bracket and start every first-round game.
+#[instrument(skip(state))]
+pub async fn start_tournament_handler(
+    State(state): State<SharedState>,
+    Path(tournament_id): Path<TournamentId>,
+    Json(payload): Json<StartGameRequest>,
+) -> Result<Json<Tournament>, AppError> {
+    let (tournament, claimed) = update_tournament(&state, tournament_id, |tournament| {
+        tournament.start(payload.player_id, &mut ThreadRngRoller::new())?;
+        Ok(claim_ready_pairings(tournament)?)
+    })
+    .await?;
+    start_tournament_games(&state, &tournament, claimed).await?;
+
+    tracing::info!(tournament_id = %tournament_id, "Tournament started.");
+    Ok(Json(tournament))
+}
+
+/// Give a player a public profile, under a new id unless they bring the one they already play under.
+#[instrument(skip(state))]
+pub async fn create_player_handler(
+    State(state): State<SharedState>,
+    Json(payload): Json<CreatePlayerRequest>,
+) -> Result<(StatusCode, Json<PlayerProfile>), AppError> {
+    let profile = PlayerProfile::new(payload.player_id.unwrap_or_else(PlayerId::new), &payload.display_name)?;
+    state.players.create_profile(&profile).await?;
+
+    tracing::info!(player_id = %profile.player_id, display_name = %profile.display_name, "Player profile created.");
+    Ok((StatusCode::CREATED, Json(profile)))
+}
+
+/// A player's profile with their lifetime stats.
+#[instrument(skip(state))]
+pub async fn get_player_handler(
+    State(state): State<SharedState>,
+    Path(player_id): Path<PlayerId>,
+) -> Result<Json<PlayerResponse>, AppError> {
+    let profile = state.players.load_profile(player_id).await?;
+    let stats = state.players.load_stats(player_id).await?;
+    Ok(Json(PlayerResponse { profile, stats }))
+}
+
+/// Like `get_player_handler`, for a player looked up by display name.
+#[instrument(skip(state))]
+pub async fn get_player_by_name_handler(
+    State(state): State<SharedState>,
+    Path(display_name): Path<String>,
+) -> Result<Json<PlayerResponse>, AppError> {
+    let profile = state.players.find_profile(&display_name).await?;
+    let stats = state.players.load_stats(profile.player_id).await?;
+    Ok(Json(PlayerResponse { profile, stats }))
+}
+
+/// Lifetime stats over every finished game; all zero for a player who has not finished one.
+#[instrument(skip(state))]
+pub async fn get_player_stats_handler(
+    State(state): State<SharedState>,
+    Path(player_id): Path<PlayerId>,
+) -> Result<Json<PlayerStats>, AppError> {
+    let stats = state.players.load_stats(player_id).await?;
+    Ok(Json(stats))
+}
+
+#[instrument(skip(state))]
+pub async fn get_balance_handler(
+    State(state): State<SharedState>,
+    Path(player_id): Path<PlayerId>,
+) -> Result<Json<BalanceResponse>, AppError> {
+    let balance = state.repository.get_balance(player_id).await?;
+    Ok(Json(BalanceResponse { player_id, balance }))
+}
+
+/// Every balance movement for a player, oldest first.
+#[instrument(skip(state))]
+pub async fn get_ledger_handler(
+    State(state): State<SharedState>,
+    Path(player_id): Path<PlayerId>,
+) -> Result<Json<Vec<LedgerEntry>>, AppError> {
+    let ledger = state.repository.load_ledger(player_id).await?;
+    Ok(Json(ledger))
+}
+
+/// A page of one leaderboard, best first, and optionally where one player stands on it.
+#[instrument(skip(state))]
+pub async fn get_leaderboard_handler(
+    State(state): State<SharedState>,
+    Path(kind): Path<LeaderboardKind>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<Json<LeaderboardResponse>, AppError> {
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LEADERBOARD_PAGE)
+        .clamp(1, MAX_LEADERBOARD_PAGE);
+    let page = state.repository.load_leaderboard(kind, offset, limit).await?;
+    let me = match query.player_id {
+        Some(player_id) => state.repository.load_leaderboard_rank(kind, player_id).await?,
+        None => None,
+    };
+    Ok(Json(LeaderboardResponse { kind, page, me }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::archive::SqliteArchive;
     use crate::config::Config;
-    use crate::data::MockGameRepository;
+    use crate::data::{GameRepository, MockGameRepository};
+    use crate::game::{GameCommand, GameError, TeamId};
+    use crate::handlers::ws::admit_spectator;
     use crate::state::{AppState, GameSessionManager};
     use axum::http::StatusCode;
     use std::sync::Arc;
@@ -92,17 +493,78 @@ mod tests {
         let repository = Arc::new(MockGameRepository::new());
         let config = Config {
             server: crate::config::ServerConfig { addr: "0,0,0,0:0".to_string() },
-            database: crate::config::DatabaseConfig { redis_url: "redis://mock".to_string() },
+            database: crate::config::DatabaseConfig {
+                redis_url: "redis://mock".to_string(),
+                sqlite_url: "sqlite::memory:".to_string(),
+            },
             logging: crate::config::LoggingConfig { level: "debug".to_string() },
+            game: crate::config::GameConfig::default(),
         };
 
-        Arc::new(AppState { repository, session_manager: GameSessionManager::default(), config: Arc::new(config) })
+        let archive = Arc::new(SqliteArchive::new(&config.database.sqlite_url).unwrap());
+        Arc::new(AppState {
+            repository,
+            archive: archive.clone(),
+            players: archive,
+            session_manager: GameSessionManager::default(),
+            config: Arc::new(config),
+        })
+    }
+
+    /// Archive `wins` games `player_id` won and `losses` they lost, each against a new opponent.
+    async fn archive_record(state: &SharedState, player_id: PlayerId, wins: usize, losses: usize) {
+        use crate::game::roller::Roller;
+
+        struct Ones;
+        impl Roller for Ones {
+            fn roll_in_range(&mut self, _max: u32) -> u32 {
+                1
+            }
+        }
+
+        let results = std::iter::repeat_n(true, wins).chain(std::iter::repeat_n(false, losses));
+        for won in results {
+            // The host rolls first, and a 1 loses.
+            let opponent_id = PlayerId::new();
+            let (host_id, guest_id) = if won {
+                (opponent_id, player_id)
+            } else {
+                (player_id, opponent_id)
+            };
+            let mut game = Game::new(host_id);
+            game.join(guest_id).unwrap();
+            game.set_ready(guest_id, true).unwrap();
+            game.start(host_id, &mut Ones).unwrap();
+            game.roll(host_id, &mut Ones).unwrap();
+            let events = game.take_uncommitted_events();
+            state.archive.archive_game(&game, &events).await.unwrap();
+        }
+    }
+
+    /// Mark `guests` ready (normally done over the WS) and have the host start the game.
+    async fn ready_and_start(
+        state: &SharedState,
+        game_id: GameId,
+        guests: &[PlayerId],
+    ) -> Result<Json<Game>, AppError> {
+        let mut game = state.repository.load_game(game_id).await?;
+        for guest_id in guests {
+            game.set_ready(*guest_id, true)?;
+        }
+        state.repository.save_game(&game).await?;
+        let host_id = game.get_host();
+        start_game_handler(
+            State(state.clone()),
+            Path(game_id),
+            Json(StartGameRequest { player_id: host_id }),
+        )
+        .await
     }
 
     #[tokio::test]
     async fn test_create_game_handler() {
         let state = setup_test_state().await;
-        let payload = CreateGameRequest { host_id: None };
+        let payload = CreateGameRequest::default();
 
         let result = create_game_handler(State(state.clone()), Json(payload)).await;
 
@@ -115,11 +577,45 @@ mod tests {
         assert!(game_in_redis.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_create_game_rejects_invalid_player_cap() {
+        let state = setup_test_state().await;
+        let payload = CreateGameRequest { max_players: Some(50), ..Default::default() };
+
+        let result = create_game_handler(State(state.clone()), Json(payload)).await;
+
+        match result {
+            Err(AppError::Game(GameError::InvalidPlayerCap { min, max })) => {
+                assert_eq!(min, 2);
+                assert_eq!(max, 10);
+            }
+            _ => panic!("Expected InvalidPlayerCap error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_game_with_starting_max() {
+        let state = setup_test_state().await;
+        let payload = CreateGameRequest { starting_max: Some(10_000), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+
+        let game = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(game.get_starting_max(), 10_000);
+        assert_eq!(game.get_current_max(), 10_000);
+
+        let payload = CreateGameRequest { starting_max: Some(0), ..Default::default() };
+        let result = create_game_handler(State(state.clone()), Json(payload)).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Game(GameError::InvalidStartingMax { .. }))
+        ));
+    }
+
     #[tokio::test]
     async fn test_get_game_handler_success() {
         let state = setup_test_state().await;
         let host_id = PlayerId::new();
-        let payload = CreateGameRequest { host_id: Some(host_id) };
+        let payload = CreateGameRequest { host_id: Some(host_id), ..Default::default() };
         let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
 
         let result = get_game_handler(State(state.clone()), Path(created.game_id)).await;
@@ -135,12 +631,12 @@ mod tests {
     async fn test_join_game_success() {
         let state = setup_test_state().await;
         let host_id = PlayerId::new();
-        let payload = CreateGameRequest { host_id: Some(host_id) };
+        let payload = CreateGameRequest { host_id: Some(host_id), ..Default::default() };
         let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
 
         // Join with new player
         let guest_id = PlayerId::new();
-        let join_payload = JoinGameRequest { player_id: Some(guest_id) };
+        let join_payload = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
 
         let result = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload)).await;
 
@@ -148,42 +644,784 @@ mod tests {
         let Json(game) = result.unwrap();
 
         assert_eq!(game.get_players().len(), 2);
+        assert_eq!(*game.get_status(), GameStatus::WaitingForPlayers);
+    }
+
+    #[tokio::test]
+    async fn test_start_game_handler() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload = CreateGameRequest { host_id: Some(host_id), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guest_id = PlayerId::new();
+        let join_payload = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload))
+            .await
+            .unwrap();
+
+        let start = |player_id| {
+            start_game_handler(
+                State(state.clone()),
+                Path(created.game_id),
+                Json(StartGameRequest { player_id }),
+            )
+        };
+        assert!(matches!(
+            start(host_id).await,
+            Err(AppError::Game(GameError::PlayersNotReady))
+        ));
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        game.set_ready(guest_id, true).unwrap();
+        state.repository.save_game(&game).await.unwrap();
+        assert!(matches!(start(guest_id).await, Err(AppError::Game(GameError::NotHost))));
+
+        let Json(game) = start(host_id).await.unwrap();
+        assert_eq!(*game.get_status(), GameStatus::InProgress);
+        let game = state.repository.load_game(created.game_id).await.unwrap();
         assert_eq!(*game.get_status(), GameStatus::InProgress);
     }
 
     #[tokio::test]
-    async fn test_join_full_game_fails() {
+    async fn test_first_roll_after_start_takes_the_fast_path() {
+        use crate::game::fairness::derive_roll;
+        use chrono::Utc;
+
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload = CreateGameRequest { host_id: Some(host_id), starting_max: Some(1_000_000), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guest_id = PlayerId::new();
+        let join = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join))
+            .await
+            .unwrap();
+        let Json(started) = ready_and_start(&state, created.game_id, &[guest_id]).await.unwrap();
+
+        // The draws were stored when the game started, so the repository takes the roll without the seed.
+        let (game, _) = state
+            .repository
+            .roll(created.game_id, host_id, Utc::now())
+            .await
+            .unwrap()
+            .expect("the first roll should not need the slow path");
+        let server_seed = state.repository.load_server_seed(created.game_id).await.unwrap();
+        let roll = &game.get_history()[0];
+        assert_eq!(roll.player_id, host_id);
+        assert_eq!(
+            roll.value,
+            derive_roll(
+                server_seed.as_str(),
+                started.get_fairness().get_client_seeds(),
+                roll.nonce,
+                1_000_000
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_leave_and_kick_handlers() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload = CreateGameRequest { host_id: Some(host_id), max_players: Some(3), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guests = [PlayerId::new(), PlayerId::new()];
+        for guest_id in guests {
+            let join_payload = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+            let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload))
+                .await
+                .unwrap();
+        }
+
+        let kick = KickPlayerRequest { host_id, player_id: guests[1] };
+        let Json(game) = kick_player_handler(State(state.clone()), Path(created.game_id), Json(kick))
+            .await
+            .unwrap();
+        assert_eq!(game.get_players(), &[host_id, guests[0]]);
+
+        // Kicked players stay out
+        let join_payload = JoinGameRequest { player_id: Some(guests[1]), ..Default::default() };
+        let result = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload)).await;
+        assert!(matches!(result, Err(AppError::Game(GameError::Kicked))));
+
+        let leave = LeaveGameRequest { player_id: host_id };
+        let Json(game) = leave_game_handler(State(state.clone()), Path(created.game_id), Json(leave))
+            .await
+            .unwrap();
+        assert_eq!(game.get_host(), guests[0]);
+        let stored = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(stored.get_host(), guests[0]);
+    }
+
+    #[tokio::test]
+    async fn test_spectator_limit_handler() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload = CreateGameRequest { host_id: Some(host_id), max_spectators: Some(5), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let spectator_id = PlayerId::new();
+        let (_, mut spectator_rx) = admit_spectator(&state, created.game_id, spectator_id).await.unwrap();
+        let Json(game) = get_game_handler(State(state.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        assert_eq!(game.get_spectator_count(), 1);
+
+        let not_host = SpectatorLimitRequest { host_id: spectator_id, max_spectators: 0 };
+        let result = spectator_limit_handler(State(state.clone()), Path(created.game_id), Json(not_host)).await;
+        assert!(matches!(result, Err(AppError::Game(GameError::NotHost))));
+        let too_many = SpectatorLimitRequest { host_id, max_spectators: 10_000 };
+        let result = spectator_limit_handler(State(state.clone()), Path(created.game_id), Json(too_many)).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Game(GameError::InvalidSpectatorLimit { .. }))
+        ));
+
+        // Turning spectating off sends everyone home
+        let off = SpectatorLimitRequest { host_id, max_spectators: 0 };
+        let Json(game) = spectator_limit_handler(State(state.clone()), Path(created.game_id), Json(off))
+            .await
+            .unwrap();
+        assert_eq!(game.get_settings().max_spectators, 0);
+        let sessions = state.session_manager.sessions.read().await;
+        assert!(sessions[&created.game_id].spectators.read().await.is_empty());
+        // With its sender gone, the spectator's channel closes, and their socket with it.
+        while spectator_rx.try_recv().is_ok() {}
+        assert!(matches!(
+            spectator_rx.try_recv(),
+            Err(tokio::sync::mpsc::error::TryRecvError::Disconnected)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_assign_team_handler() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload =
+            CreateGameRequest { host_id: Some(host_id), max_players: Some(4), teams: Some(2), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guests = [PlayerId::new(), PlayerId::new()];
+        for guest_id in guests {
+            let join = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+            let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join))
+                .await
+                .unwrap();
+        }
+
+        let not_host = AssignTeamRequest { player_id: guests[1], team_id: TeamId(1), assigned_by: Some(guests[0]) };
+        let result = assign_team_handler(State(state.clone()), Path(created.game_id), Json(not_host)).await;
+        assert!(matches!(result, Err(AppError::Game(GameError::NotHost))));
+
+        // Players switch themselves
+        let switch = AssignTeamRequest { player_id: guests[1], team_id: TeamId(1), assigned_by: None };
+        let Json(game) = assign_team_handler(State(state.clone()), Path(created.game_id), Json(switch))
+            .await
+            .unwrap();
+        assert_eq!(game.get_team_members(TeamId(1)), guests.to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_verify_finished_game() {
+        use crate::game::{fairness::FairRoller, GameError};
+
         let state = setup_test_state().await;
         let host_id = PlayerId::new();
-        let (_, Json(created)) =
-            create_game_handler(State(state.clone()), Json(CreateGameRequest { host_id: Some(host_id) }))
+        let payload =
+            CreateGameRequest { host_id: Some(host_id), client_seed: Some("host".into()), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guest_id = PlayerId::new();
+        let join_payload =
+            JoinGameRequest { player_id: Some(guest_id), client_seed: Some("guest".into()), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload))
+            .await
+            .unwrap();
+        let _ = ready_and_start(&state, created.game_id, &[guest_id]).await.unwrap();
+
+        // Nothing to verify until the game is over
+        let result = verify_game_handler(State(state.clone()), Path(created.game_id)).await;
+        assert!(matches!(result, Err(AppError::Game(GameError::SeedNotRevealed))));
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        let server_seed = state.repository.load_server_seed(created.game_id).await.unwrap();
+        assert_eq!(
+            game.get_fairness().get_server_seed_hash(),
+            Some(server_seed.commitment().as_str())
+        );
+        assert_eq!(game.get_fairness().get_client_seeds().len(), 2);
+
+        while !game.is_finished() {
+            let current = *game.get_current_player().unwrap();
+            let mut roller = FairRoller::new(server_seed.clone(), game.get_fairness());
+            game.roll(current, &mut roller).unwrap();
+        }
+        game.reveal_server_seed(&server_seed).unwrap();
+        state.repository.save_game(&game).await.unwrap();
+
+        let Json(report) = verify_game_handler(State(state.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        assert!(report.valid);
+        assert_eq!(report.server_seed, server_seed.as_str());
+        assert_eq!(report.rolls.len() as u64, game.get_fairness().get_nonce());
+    }
+
+    #[tokio::test]
+    async fn test_finished_game_outlives_the_repository() {
+        use crate::handlers::lifecycle::roll_for;
+
+        let state = setup_test_state().await;
+        let payload = CreateGameRequest { host_id: Some(PlayerId::new()), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guest_id = PlayerId::new();
+        let join_payload = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload))
+            .await
+            .unwrap();
+        let _ = ready_and_start(&state, created.game_id, &[guest_id]).await.unwrap();
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        while !game.is_finished() {
+            roll_for(&state, created.game_id, *game.get_current_player().unwrap())
                 .await
                 .unwrap();
+            game = state.repository.load_game(created.game_id).await.unwrap();
+        }
+
+        // The repository has let the game expire; the archive still has it.
+        let expired = Arc::new(AppState {
+            repository: Arc::new(MockGameRepository::new()),
+            archive: state.archive.clone(),
+            players: state.players.clone(),
+            session_manager: GameSessionManager::default(),
+            config: state.config.clone(),
+        });
+        let Json(archived) = get_game_handler(State(expired.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        assert_eq!(archived, game);
+        let Json(events) = get_events_handler(State(expired.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        assert_eq!(events.len() as u64, game.get_version());
+        let Json(report) = verify_game_handler(State(expired.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        assert!(report.valid);
+        let missing = get_game_handler(State(expired), Path(GameId::new())).await;
+        assert!(matches!(missing, Err(AppError::GameNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_player_stats_update_when_a_game_ends() {
+        use crate::handlers::lifecycle::roll_for;
+
+        let state = setup_test_state().await;
+        let create = |display_name: &str| CreatePlayerRequest { player_id: None, display_name: display_name.into() };
+        let (status, Json(host)) = create_player_handler(State(state.clone()), Json(create("Host")))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        let (_, Json(guest)) = create_player_handler(State(state.clone()), Json(create("Guest")))
+            .await
+            .unwrap();
+        let taken = create_player_handler(State(state.clone()), Json(create("host"))).await;
+        assert!(matches!(taken, Err(AppError::ProfileConflict(_))));
+
+        let payload = CreateGameRequest { host_id: Some(host.player_id), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let join_payload = JoinGameRequest { player_id: Some(guest.player_id), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload))
+            .await
+            .unwrap();
+        let _ = ready_and_start(&state, created.game_id, &[guest.player_id])
+            .await
+            .unwrap();
+
+        let Json(before) = get_player_stats_handler(State(state.clone()), Path(host.player_id))
+            .await
+            .unwrap();
+        assert_eq!(before, PlayerStats::default());
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        while !game.is_finished() {
+            roll_for(&state, created.game_id, *game.get_current_player().unwrap())
+                .await
+                .unwrap();
+            game = state.repository.load_game(created.game_id).await.unwrap();
+        }
+
+        let Json(response) = get_player_handler(State(state.clone()), Path(host.player_id))
+            .await
+            .unwrap();
+        assert_eq!(response.profile, host);
+        let stats = response.stats;
+        let host_won = game.get_loser() != Some(host.player_id);
+        assert_eq!(
+            (stats.games_played, stats.wins, stats.current_streak),
+            (1, host_won as u64, host_won as u64)
+        );
+        let host_rolls = game
+            .get_history()
+            .iter()
+            .filter(|roll| roll.player_id == host.player_id)
+            .count();
+        assert_eq!(stats.total_rolls, host_rolls as u64);
+
+        let missing = get_player_handler(State(state.clone()), Path(PlayerId::new())).await;
+        assert!(matches!(missing, Err(AppError::PlayerNotFound(_))));
+
+        let name = host.display_name.to_uppercase();
+        let Json(found) = get_player_by_name_handler(State(state.clone()), Path(name))
+            .await
+            .unwrap();
+        assert_eq!((found.profile, found.stats), (host, stats));
+    }
+
+    #[tokio::test]
+    async fn test_leaderboards_update_when_a_game_ends() {
+        use crate::handlers::lifecycle::roll_for;
+
+        let state = setup_test_state().await;
+        let (host_id, guest_id) = (PlayerId::new(), PlayerId::new());
+        let payload = CreateGameRequest { host_id: Some(host_id), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let join_payload = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload))
+            .await
+            .unwrap();
+        let _ = ready_and_start(&state, created.game_id, &[guest_id]).await.unwrap();
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        while !game.is_finished() {
+            roll_for(&state, created.game_id, *game.get_current_player().unwrap())
+                .await
+                .unwrap();
+            game = state.repository.load_game(created.game_id).await.unwrap();
+        }
+        let loser_id = game.get_loser().unwrap();
+        let winner_id = if loser_id == host_id { guest_id } else { host_id };
+
+        let query = LeaderboardQuery { player_id: Some(loser_id), ..Default::default() };
+        let Json(wins) = get_leaderboard_handler(State(state.clone()), Path(LeaderboardKind::Wins), Query(query))
+            .await
+            .unwrap();
+        assert_eq!(wins.page.total, 2);
+        assert_eq!(
+            (wins.page.entries[0].player_id, wins.page.entries[0].score),
+            (winner_id, 1.0)
+        );
+        assert_eq!(wins.me.map(|me| (me.rank, me.score)), Some((2, 0.0)));
+
+        // One game is short of the minimum for the win rate board.
+        let query = LeaderboardQuery { limit: Some(1000), player_id: Some(winner_id), ..Default::default() };
+        let Json(win_rate) =
+            get_leaderboard_handler(State(state.clone()), Path(LeaderboardKind::WinRate), Query(query))
+                .await
+                .unwrap();
+        assert_eq!((win_rate.page.total, win_rate.me), (0, None));
+    }
+
+    #[tokio::test]
+    async fn test_game_rebuilt_from_event_stream() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload = CreateGameRequest { host_id: Some(host_id), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let join_payload = JoinGameRequest { player_id: Some(PlayerId::new()), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload))
+            .await
+            .unwrap();
+
+        let Json(events) = get_events_handler(State(state.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        let game = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(events.len() as u64, game.get_version());
+        assert!(game.get_uncommitted_events().is_empty());
+
+        // Without a snapshot, the stream alone is enough.
+        let archive = MockGameRepository::new();
+        archive.append_events(created.game_id, &events).await.unwrap();
+        archive.append_events(created.game_id, &events[1..]).await.unwrap();
+        assert_eq!(archive.load_game(created.game_id).await.unwrap(), game);
+        assert_eq!(archive.load_events(created.game_id, 0).await.unwrap(), events);
+
+        // Appending past the end of the stream would leave a hole.
+        let gap = archive.append_events(GameId::new(), &events[1..]).await;
+        assert!(matches!(gap, Err(AppError::Internal(_))));
+        let missing = get_events_handler(State(state.clone()), Path(GameId::new())).await;
+        assert!(matches!(missing, Err(AppError::GameNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stale_save_is_a_version_conflict() {
+        let state = setup_test_state().await;
+        let payload = CreateGameRequest { max_players: Some(3), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let (guest_id, rival_id) = (PlayerId::new(), PlayerId::new());
+
+        let mut first = state.repository.load_game(created.game_id).await.unwrap();
+        let loaded = first.get_version();
+        let (stale, mut second) = (first.clone(), first.clone());
+        first.join(guest_id).unwrap();
+        second.join(rival_id).unwrap();
+
+        state.repository.save_game(&first).await.unwrap();
+        // Saving the same copy twice is harmless; a copy that missed the first save is not.
+        state.repository.save_game(&first).await.unwrap();
+        let result = state.repository.save_game(&second).await;
+        assert!(matches!(result, Err(AppError::VersionConflict { version, .. }) if version == loaded));
+
+        // A stale copy with nothing new to record does not roll the snapshot back.
+        state.repository.save_game(&stale).await.unwrap();
+        let game = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(game.get_players(), &[created.host_id, guest_id]);
+
+        // Going through the handler reloads the game, so the rival's join lands.
+        let join = JoinGameRequest { player_id: Some(rival_id), ..Default::default() };
+        let Json(game) = join_game_handler(State(state.clone()), Path(created.game_id), Json(join))
+            .await
+            .unwrap();
+        assert_eq!(game.get_players(), &[created.host_id, guest_id, rival_id]);
+    }
+
+    #[tokio::test]
+    async fn test_get_history_handler() {
+        use crate::game::fairness::FairRoller;
+
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload = CreateGameRequest { host_id: Some(host_id), starting_max: Some(100), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guest_id = PlayerId::new();
+        let join_payload = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join_payload))
+            .await
+            .unwrap();
+        let _ = ready_and_start(&state, created.game_id, &[guest_id]).await.unwrap();
+
+        let Json(history) = get_history_handler(State(state.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        assert!(history.is_empty());
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        let server_seed = state.repository.load_server_seed(created.game_id).await.unwrap();
+        let mut roller = FairRoller::new(server_seed, game.get_fairness());
+        game.roll(host_id, &mut roller).unwrap();
+        state.repository.save_game(&game).await.unwrap();
+
+        let Json(history) = get_history_handler(State(state.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].player_id, host_id);
+        assert_eq!((history[0].min, history[0].max), (1, 100));
+        assert_eq!(history[0].nonce, 0);
+        assert!((1..=100).contains(&history[0].value));
+    }
+
+    #[tokio::test]
+    async fn test_wager_escrowed_when_game_starts() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let guest_id = PlayerId::new();
+        state.repository.deposit(host_id, 500).await.unwrap();
+        state.repository.deposit(guest_id, 80).await.unwrap();
+
+        let payload = CreateGameRequest { host_id: Some(host_id), wager: Some(100), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+
+        // The wager has to be accepted explicitly
+        let join = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+        let result = join_game_handler(State(state.clone()), Path(created.game_id), Json(join)).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Game(GameError::WagerNotAccepted { wager: 100 }))
+        ));
+
+        // The guest cannot cover it, so nothing moves and the seat stays open
+        let join = JoinGameRequest { player_id: Some(guest_id), accept_wager: true, ..Default::default() };
+        let result = join_game_handler(State(state.clone()), Path(created.game_id), Json(join)).await;
+        assert!(
+            matches!(result, Err(AppError::Game(GameError::InsufficientFunds { player_id })) if player_id == guest_id)
+        );
+        assert_eq!(state.repository.get_balance(host_id).await.unwrap(), 500);
+        let game = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(game.get_players().len(), 1);
+
+        // Topped up, the guest takes the seat; starting the game escrows both stakes
+        state.repository.deposit(guest_id, 20).await.unwrap();
+        let join = JoinGameRequest { player_id: Some(guest_id), accept_wager: true, ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join))
+            .await
+            .unwrap();
+        assert_eq!(state.repository.get_balance(guest_id).await.unwrap(), 100);
+        let Json(game) = ready_and_start(&state, created.game_id, &[guest_id]).await.unwrap();
+        assert_eq!(*game.get_status(), GameStatus::InProgress);
+
+        let Json(host_balance) = get_balance_handler(State(state.clone()), Path(host_id)).await.unwrap();
+        let Json(guest_balance) = get_balance_handler(State(state.clone()), Path(guest_id)).await.unwrap();
+        assert_eq!(host_balance.balance, 400);
+        assert_eq!(guest_balance.balance, 0);
+
+        // Settlement pays the whole pot once
+        assert_eq!(
+            state.repository.settle_pot(created.game_id, &[host_id]).await.unwrap(),
+            200
+        );
+        assert_eq!(
+            state.repository.settle_pot(created.game_id, &[host_id]).await.unwrap(),
+            0
+        );
+        assert_eq!(state.repository.get_balance(host_id).await.unwrap(), 600);
+
+        // The ledger reconciles with the balance
+        let Json(ledger) = get_ledger_handler(State(state.clone()), Path(host_id)).await.unwrap();
+        assert_eq!(ledger.len(), 3);
+        assert_eq!(ledger.iter().map(|e| e.amount).sum::<i64>(), 600);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_start_escrows_once() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let guest_id = PlayerId::new();
+        state.repository.deposit(host_id, 100).await.unwrap();
+        state.repository.deposit(guest_id, 100).await.unwrap();
+        let payload = CreateGameRequest { host_id: Some(host_id), wager: Some(60), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let join = JoinGameRequest { player_id: Some(guest_id), accept_wager: true, ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join))
+            .await
+            .unwrap();
+
+        // An earlier start took the stakes but never saved the game; starting again reuses the pot.
+        state
+            .repository
+            .escrow_stakes(created.game_id, &[host_id, guest_id], 60)
+            .await
+            .unwrap();
+        let Json(game) = ready_and_start(&state, created.game_id, &[guest_id]).await.unwrap();
+        assert_eq!(*game.get_status(), GameStatus::InProgress);
+
+        // A second start is refused, and the pot of the game under way stays put.
+        let start = StartGameRequest { player_id: host_id };
+        let result = start_game_handler(State(state.clone()), Path(created.game_id), Json(start)).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Game(GameError::InvalidTransition { .. }))
+        ));
+        assert_eq!(state.repository.get_balance(host_id).await.unwrap(), 40);
+        assert_eq!(state.repository.get_balance(guest_id).await.unwrap(), 40);
+        assert_eq!(
+            state.repository.settle_pot(created.game_id, &[host_id]).await.unwrap(),
+            120
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_full_game_fails() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let (_, Json(created)) = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest { host_id: Some(host_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
 
         // Add Player 2 (Game becomes Full)
         let p2_id = PlayerId::new();
         let _ = join_game_handler(
             State(state.clone()),
             Path(created.game_id),
-            Json(JoinGameRequest { player_id: Some(p2_id) }),
+            Json(JoinGameRequest { player_id: Some(p2_id), ..Default::default() }),
         )
         .await
         .unwrap();
 
-        // Try to add Player 3
+        // Still in the lobby, the seat count turns Player 3 away
         let intruder_id = PlayerId::new();
         let result = join_game_handler(
             State(state.clone()),
             Path(created.game_id),
-            Json(JoinGameRequest { player_id: Some(intruder_id) }),
+            Json(JoinGameRequest { player_id: Some(intruder_id), ..Default::default() }),
         )
         .await;
+        assert!(matches!(result, Err(AppError::Game(GameError::GameFull))));
 
-        // Expect Forbidden (because Player 3 is not part of the game)
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            AppError::Forbidden(_) => assert!(true),
-            _ => assert!(false, "Expected Forbidden error"),
+        // Try to add Player 3 once the game is under way
+        let _ = ready_and_start(&state, created.game_id, &[p2_id]).await.unwrap();
+        let result = join_game_handler(
+            State(state.clone()),
+            Path(created.game_id),
+            Json(JoinGameRequest { player_id: Some(intruder_id), ..Default::default() }),
+        )
+        .await;
+
+        // The lobby is closed, so Player 3 is turned away by the transition table
+        assert!(matches!(
+            result,
+            Err(AppError::Game(GameError::InvalidTransition {
+                from: GameStatus::InProgress,
+                action: GameCommand::Join,
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_match_starts_first_game() {
+        let state = setup_test_state().await;
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let payload = CreateMatchRequest { players: players.clone(), best_of: Some(3), ..Default::default() };
+
+        let (status, Json(series)) = create_match_handler(State(state.clone()), Json(payload)).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(series.get_games().len(), 1);
+
+        let game = state
+            .repository
+            .load_game(series.get_current_game().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(*game.get_status(), GameStatus::InProgress);
+        assert_eq!(game.get_players(), players.as_slice());
+        assert_eq!(game.get_match_id(), Some(series.get_id()));
+
+        let Json(loaded) = get_match_handler(State(state.clone()), Path(series.get_id()))
+            .await
+            .unwrap();
+        assert_eq!(loaded, series);
+
+        let payload = CreateMatchRequest { players, best_of: Some(2), ..Default::default() };
+        let result = create_match_handler(State(state.clone()), Json(payload)).await;
+        assert!(matches!(result, Err(AppError::Game(GameError::InvalidBestOf))));
+    }
+
+    #[tokio::test]
+    async fn test_tournament_runs_to_a_champion() {
+        use crate::data::ServerMessage;
+        use crate::game::{Seeding, TournamentStatus};
+        use crate::handlers::lifecycle::roll_for;
+        use crate::handlers::ws::register_bracket_session;
+
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload = CreateTournamentRequest {
+            host_id,
+            max_players: 0,
+            seeding: Seeding::Rating,
+            starting_max: Some(2),
+            turn_timeout_secs: None,
+            on_timeout: None,
+            reconnect_grace_secs: None,
+            rules: None,
+        };
+        let result = create_tournament_handler(State(state.clone()), Json(payload)).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Game(GameError::InvalidTournamentSize { min: 2, .. }))
+        ));
+
+        let payload = CreateTournamentRequest {
+            host_id,
+            max_players: 4,
+            seeding: Seeding::Rating,
+            starting_max: Some(2),
+            turn_timeout_secs: None,
+            on_timeout: None,
+            reconnect_grace_secs: None,
+            rules: None,
+        };
+        let (status, Json(tournament)) = create_tournament_handler(State(state.clone()), Json(payload))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        let tournament_id = tournament.get_id();
+        let (_tx, mut rx) = register_bracket_session(&state, tournament_id, host_id).await;
+
+        let players = [PlayerId::new(), PlayerId::new(), PlayerId::new(), PlayerId::new()];
+        // Ratings come from the archive: win rates of 60%, 30% and 100%, and no record at all.
+        for ((wins, losses), player_id) in [(6, 4), (3, 7), (10, 0)].into_iter().zip(players) {
+            archive_record(&state, player_id, wins, losses).await;
+        }
+        for player_id in &players[..3] {
+            let payload = RegisterRequest { player_id: *player_id };
+            let _ = register_handler(State(state.clone()), Path(tournament_id), Json(payload))
+                .await
+                .unwrap();
         }
+        let pushed: ServerMessage = serde_json::from_value(rx.recv().await.unwrap().payload).unwrap();
+        assert!(matches!(pushed, ServerMessage::BracketState(t) if t.get_entrants().len() == 1));
+        let payload = WithdrawRequest { player_id: players[3] };
+        let result = withdraw_handler(State(state.clone()), Path(tournament_id), Json(payload)).await;
+        assert!(matches!(result, Err(AppError::Game(GameError::NotAParticipant))));
+
+        let payload = StartGameRequest { player_id: players[0] };
+        let result = start_tournament_handler(State(state.clone()), Path(tournament_id), Json(payload)).await;
+        assert!(matches!(result, Err(AppError::Game(GameError::NotHost))));
+        let payload = StartGameRequest { player_id: host_id };
+        let Json(tournament) = start_tournament_handler(State(state.clone()), Path(tournament_id), Json(payload))
+            .await
+            .unwrap();
+
+        // The top seed has the bye; the other two are already playing.
+        assert_eq!(tournament.get_seeds(), &[players[2], players[0], players[1]]);
+        assert!(tournament.get_ready_pairings().is_empty());
+        let semi_final = tournament.get_rounds()[0]
+            .iter()
+            .find_map(|pairing| pairing.game_id)
+            .unwrap();
+        let result = register_handler(
+            State(state.clone()),
+            Path(tournament_id),
+            Json(RegisterRequest { player_id: players[3] }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Game(GameError::RegistrationClosed))));
+
+        // Play every game out until the bracket has a champion.
+        let mut game_id = semi_final;
+        loop {
+            let mut game = state.repository.load_game(game_id).await.unwrap();
+            assert_eq!(game.get_tournament_id(), Some(tournament_id));
+            while !game.is_finished() {
+                let current = *game.get_current_player().unwrap();
+                roll_for(&state, game_id, current).await.unwrap();
+                game = state.repository.load_game(game_id).await.unwrap();
+            }
+            let Json(tournament) = get_tournament_handler(State(state.clone()), Path(tournament_id))
+                .await
+                .unwrap();
+            match tournament.get_rounds().last().and_then(|round| round[0].game_id) {
+                Some(next) if next != game_id => game_id = next,
+                _ => break,
+            }
+        }
+
+        let Json(tournament) = get_tournament_handler(State(state.clone()), Path(tournament_id))
+            .await
+            .unwrap();
+        let final_pairing = &tournament.get_rounds().last().unwrap()[0];
+        assert!(final_pairing.players.contains(&Some(players[2])));
+        assert_eq!(
+            *tournament.get_status(),
+            TournamentStatus::Finished(final_pairing.winner_id.unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rule_set_visible_on_game() {
+        use crate::game::RuleSet;
+
+        let state = setup_test_state().await;
+        let payload = CreateGameRequest { rules: Some(RuleSet::Halving), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+
+        let Json(game) = get_game_handler(State(state.clone()), Path(created.game_id))
+            .await
+            .unwrap();
+        let json = serde_json::to_value(&game).unwrap();
+        assert_eq!(json["settings"]["rules"], serde_json::json!({ "type": "HALVING" }));
     }
 }
@@ -0,0 +1,333 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::task::JoinHandle;
+
+use crate::{
+    error::AppError,
+    game::{settings::TimeoutAction, GameId},
+    handlers::lifecycle::{forfeit_player, recorded_winners, refund_wager, roll_for, settle_wager},
+    state::SharedState,
+};
+
+/// How long stakes sit in escrow before a pot with no game behind it counts as abandoned. This covers
+/// the moment between escrowing the stakes and saving the game that holds them.
+const ABANDONED_POT_SECS: i64 = 60;
+
+// ==============================================================================
+// === Turn timers
+// =============================================================================
+
+/// Periodically act on every turn or reconnect window that ran out of time, and close the pots of
+/// games that are over or gone. Deadlines live in the repository, so they expire whether or not anyone still
+/// has a socket open.
+pub fn spawn_deadline_sweeper(state: SharedState, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            sweep_expired_deadlines(&state, Utc::now()).await;
+        }
+    })
+}
+
+pub(crate) async fn sweep_expired_deadlines(state: &SharedState, now: DateTime<Utc>) {
+    let game_ids = match state.repository.load_expired_deadlines(now).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!("Failed to load expired deadlines: {}", e);
+            return;
+        }
+    };
+
+    for game_id in game_ids {
+        expire_deadline(state, game_id, now).await;
+    }
+    close_abandoned_pots(state, now).await;
+}
+
+/// Close every pot left open for longer than a game takes to save. A game that finished had its payout
+/// fail, so the pot goes to the winners recorded in the repository or, once the game has expired, the
+/// archive; a pot whose game is gone without a result is refunded.
+async fn close_abandoned_pots(state: &SharedState, now: DateTime<Utc>) {
+    let before = now - chrono::Duration::seconds(ABANDONED_POT_SECS);
+    let game_ids = match state.repository.load_open_pots(before).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!("Failed to load open pots: {}", e);
+            return;
+        }
+    };
+
+    for game_id in game_ids {
+        let events = match state.repository.load_game(game_id).await {
+            Ok(game) if game.is_finished() => state.repository.load_events(game_id, 0).await,
+            Ok(_) => continue,
+            Err(AppError::GameNotFound(_)) => state.archive.load_archived_events(game_id).await,
+            Err(e) => Err(e),
+        };
+        match events.map(|events| recorded_winners(&events)) {
+            Ok(Some(winners)) => {
+                tracing::info!(game_id = %game_id, "Game is over with stakes still in escrow.");
+                settle_wager(state, game_id, &winners).await;
+            }
+            Ok(None) => {
+                tracing::info!(game_id = %game_id, "Game is gone with stakes still in escrow.");
+                refund_wager(state, game_id).await;
+            }
+            Err(e) => tracing::warn!(game_id = %game_id, "Skipping open pot: {}", e),
+        }
+    }
+}
+
+async fn expire_deadline(state: &SharedState, game_id: GameId, now: DateTime<Utc>) {
+    let game = match state.repository.load_game(game_id).await {
+        Ok(game) => game,
+        Err(e) => {
+            tracing::warn!(game_id = %game_id, "Skipping expired deadline: {}", e);
+            return;
+        }
+    };
+
+    if let Some(player_id) = game.get_expired_reconnect(now) {
+        tracing::info!(game_id = %game_id, player_id = %player_id, "Reconnect grace expired.");
+        if let Err(e) = forfeit_player(state, game_id, player_id).await {
+            tracing::error!(game_id = %game_id, "Failed to forfeit disconnected player: {}", e);
+        }
+        return;
+    }
+
+    // The index can lag behind the game; only act if the clock really ran out.
+    let Some(&player_id) = game.get_current_player().filter(|_| game.is_turn_expired(now)) else {
+        return;
+    };
+
+    let on_timeout = game.get_settings().on_timeout;
+    tracing::info!(game_id = %game_id, player_id = %player_id, action = ?on_timeout, "Turn timed out.");
+    let result = match on_timeout {
+        TimeoutAction::AutoRoll => roll_for(state, game_id, player_id).await,
+        TimeoutAction::Forfeit => forfeit_player(state, game_id, player_id).await,
+    };
+    if let Err(e) = result {
+        tracing::error!(game_id = %game_id, "Failed to act on expired turn: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        extract::{Path, State},
+        Json,
+    };
+
+    use super::*;
+    use crate::archive::SqliteArchive;
+    use crate::config::Config;
+    use crate::data::{CreateGameRequest, JoinGameRequest, MockGameRepository, StartGameRequest};
+    use crate::game::{Game, GameStatus, PlayerId};
+    use crate::handlers::{create_game_handler, join_game_handler, start_game_handler};
+    use crate::state::{AppState, GameSessionManager};
+
+    async fn setup_test_state() -> SharedState {
+        let repository = Arc::new(MockGameRepository::new());
+        let config = Config {
+            server: crate::config::ServerConfig { addr: "0,0,0,0:0".to_string() },
+            database: crate::config::DatabaseConfig {
+                redis_url: "redis://mock".to_string(),
+                sqlite_url: "sqlite::memory:".to_string(),
+            },
+            logging: crate::config::LoggingConfig { level: "debug".to_string() },
+            game: crate::config::GameConfig::default(),
+        };
+
+        let archive = Arc::new(SqliteArchive::new(&config.database.sqlite_url).unwrap());
+        Arc::new(AppState {
+            repository,
+            archive: archive.clone(),
+            players: archive,
+            session_manager: GameSessionManager::default(),
+            config: Arc::new(config),
+        })
+    }
+
+    async fn start_game(state: &SharedState, on_timeout: TimeoutAction) -> (GameId, PlayerId, PlayerId) {
+        let host_id = PlayerId::new();
+        let payload = CreateGameRequest {
+            host_id: Some(host_id),
+            turn_timeout_secs: Some(10),
+            on_timeout: Some(on_timeout),
+            ..Default::default()
+        };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guest_id = PlayerId::new();
+        let join = JoinGameRequest { player_id: Some(guest_id), ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join))
+            .await
+            .unwrap();
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        game.set_ready(guest_id, true).unwrap();
+        state.repository.save_game(&game).await.unwrap();
+        let start = StartGameRequest { player_id: host_id };
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id), Json(start))
+            .await
+            .unwrap();
+        (created.game_id, host_id, guest_id)
+    }
+
+    #[tokio::test]
+    async fn test_sweep_ignores_running_clocks() {
+        let state = setup_test_state().await;
+        let (game_id, _, _) = start_game(&state, TimeoutAction::AutoRoll).await;
+        let before = state.repository.load_game(game_id).await.unwrap();
+        assert!(before.get_turn_deadline().is_some());
+
+        sweep_expired_deadlines(&state, Utc::now()).await;
+
+        assert_eq!(state.repository.load_game(game_id).await.unwrap(), before);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_auto_rolls_for_idle_player() {
+        let state = setup_test_state().await;
+        let (game_id, host_id, _) = start_game(&state, TimeoutAction::AutoRoll).await;
+        let deadline = state
+            .repository
+            .load_game(game_id)
+            .await
+            .unwrap()
+            .get_turn_deadline()
+            .unwrap();
+
+        sweep_expired_deadlines(&state, deadline).await;
+
+        let game = state.repository.load_game(game_id).await.unwrap();
+        assert_eq!(game.get_history().len(), 1);
+        assert_eq!(game.get_history()[0].player_id, host_id);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_forfeits_idle_player() {
+        let state = setup_test_state().await;
+        let (game_id, host_id, guest_id) = start_game(&state, TimeoutAction::Forfeit).await;
+        let deadline = state
+            .repository
+            .load_game(game_id)
+            .await
+            .unwrap()
+            .get_turn_deadline()
+            .unwrap();
+
+        sweep_expired_deadlines(&state, deadline).await;
+
+        let game = state.repository.load_game(game_id).await.unwrap();
+        assert_eq!(*game.get_status(), GameStatus::Forfeited(host_id));
+        assert_eq!(game.get_active_players(), vec![guest_id]);
+        assert!(game.get_history().is_empty());
+        assert!(game.verify_rolls().unwrap().valid);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_forfeits_player_who_never_reconnects() {
+        use crate::data::ServerMessage;
+        use crate::handlers::ws::register_player_session;
+
+        let state = setup_test_state().await;
+        let (game_id, host_id, guest_id) = start_game(&state, TimeoutAction::AutoRoll).await;
+        let mut game = state.repository.load_game(game_id).await.unwrap();
+        game.pause_game(guest_id).unwrap();
+        state.repository.save_game(&game).await.unwrap();
+        let (_, mut host_rx) = register_player_session(&state, game_id, host_id).await;
+
+        sweep_expired_deadlines(&state, game.get_reconnect_deadline().unwrap()).await;
+
+        let game = state.repository.load_game(game_id).await.unwrap();
+        assert_eq!(*game.get_status(), GameStatus::Forfeited(guest_id));
+        let mut game_over = None;
+        while let Ok(msg) = host_rx.try_recv() {
+            if let Ok(ServerMessage::GameOver { winning_team, losing_team, .. }) = serde_json::from_value(msg.payload) {
+                game_over = Some((winning_team.players, losing_team.players));
+            }
+        }
+        assert_eq!(game_over, Some((vec![host_id], vec![guest_id])));
+    }
+
+    #[tokio::test]
+    async fn test_sweep_refunds_pots_of_games_that_are_gone() {
+        use crate::wallet::LedgerKind;
+
+        let state = setup_test_state().await;
+        let (game_id, host_id, guest_id) = start_game(&state, TimeoutAction::AutoRoll).await;
+        let (abandoned_id, players) = (GameId::new(), [host_id, guest_id]);
+        for player_id in players {
+            state.repository.deposit(player_id, 100).await.unwrap();
+        }
+        state.repository.escrow_stakes(game_id, &players, 40).await.unwrap();
+        state
+            .repository
+            .escrow_stakes(abandoned_id, &players, 60)
+            .await
+            .unwrap();
+
+        // Too soon: the game holding the stakes may not be saved yet.
+        sweep_expired_deadlines(&state, Utc::now()).await;
+        assert_eq!(state.repository.get_balance(host_id).await.unwrap(), 0);
+
+        sweep_expired_deadlines(&state, Utc::now() + chrono::Duration::seconds(ABANDONED_POT_SECS)).await;
+        for player_id in players {
+            assert_eq!(state.repository.get_balance(player_id).await.unwrap(), 60);
+            let ledger = state.repository.load_ledger(player_id).await.unwrap();
+            let refund = ledger.last().unwrap();
+            assert_eq!(
+                (refund.kind, refund.game_id, refund.amount),
+                (LedgerKind::Refund, Some(abandoned_id), 60)
+            );
+        }
+        // The game that is still running keeps its pot.
+        assert_eq!(state.repository.settle_pot(game_id, &[host_id]).await.unwrap(), 80);
+        assert_eq!(state.repository.refund_pot(abandoned_id).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_pays_out_pots_of_archived_games() {
+        use crate::game::{fairness::ServerSeed, roller::SeededRoller};
+
+        let state = setup_test_state().await;
+        let (host_id, guest_id) = (PlayerId::new(), PlayerId::new());
+        let server_seed = ServerSeed::generate();
+        let mut game = Game::new(host_id);
+        game.commit_server_seed(server_seed.commitment()).unwrap();
+        game.join(guest_id).unwrap();
+        game.set_ready(guest_id, true).unwrap();
+        let mut roller = SeededRoller::new([7; 32], 0);
+        game.start(host_id, &mut roller).unwrap();
+        while !game.is_finished() {
+            let player_id = *game.get_current_player().unwrap();
+            game.roll(player_id, &mut roller).unwrap();
+        }
+        game.reveal_server_seed(&server_seed).unwrap();
+        let events = game.take_uncommitted_events();
+        let players = [host_id, guest_id];
+        for player_id in players {
+            state.repository.deposit(player_id, 100).await.unwrap();
+        }
+        state
+            .repository
+            .escrow_stakes(game.get_id(), &players, 100)
+            .await
+            .unwrap();
+        assert!(state.repository.settle_pot(game.get_id(), &[]).await.is_err());
+
+        // The game was archived but its payout never happened, and it has since expired from the repository.
+        state.archive.archive_game(&game, &events).await.unwrap();
+        sweep_expired_deadlines(&state, Utc::now() + chrono::Duration::seconds(ABANDONED_POT_SECS)).await;
+
+        let loser_id = game.get_loser().unwrap();
+        let winner_id = if loser_id == host_id { guest_id } else { host_id };
+        assert_eq!(state.repository.get_balance(winner_id).await.unwrap(), 200);
+        assert_eq!(state.repository.get_balance(loser_id).await.unwrap(), 0);
+        assert_eq!(state.repository.refund_pot(game.get_id()).await.unwrap(), 0);
+    }
+}
@@ -11,8 +11,12 @@ use tracing::instrument;
 
 use crate::{
     data::{ClientMessage, ServerMessage},
-    game::{roller::ThreadRngRoller, types::GameEvent, GameId, GameStatus, PlayerId},
-    state::{GameMessage, GameSession, SharedState},
+    error::AppError,
+    game::{GameError, GameId, GameStatus, PlayerId, TeamId, TournamentId},
+    handlers::lifecycle::{
+        accept_rematch, choose_team, forfeit_player, kick_from_lobby, leave_lobby, publish_state, roll_for, update_game,
+    },
+    state::{GameMessage, GameSession, PlayerSender, SharedState},
 };
 
 // ==============================================================================
@@ -21,6 +25,14 @@ use crate::{
 #[derive(Deserialize, Debug)]
 pub struct WebSocketParams {
     pub player_id: PlayerId,
+    /// Watch the game instead of playing in it
+    #[serde(default)]
+    pub spectate: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BracketFeedParams {
+    pub player_id: PlayerId,
 }
 
 #[instrument(skip(ws, state))]
@@ -30,15 +42,19 @@ pub async fn websocket_handler(
     Query(params): Query<WebSocketParams>,
     State(state): State<SharedState>,
 ) -> impl IntoResponse {
-    tracing::info!(game_id = %game_id, player_id = %params.player_id, "WebSocket upgrade requested.");
-    ws.on_upgrade(move |socket| handle_socket(socket, game_id, params.player_id, state))
+    tracing::info!(
+        game_id = %game_id, player_id = %params.player_id, spectate = params.spectate,
+        "WebSocket upgrade requested."
+    );
+    ws.on_upgrade(move |socket| handle_socket(socket, game_id, params.player_id, params.spectate, state))
 }
 
-async fn broadcast_message(state: &SharedState, game_id: GameId, message: ServerMessage) {
+pub(crate) async fn broadcast_message(state: &SharedState, game_id: GameId, message: ServerMessage) {
     let sessions = state.session_manager.sessions.read().await;
     if let Some(session) = sessions.get(&game_id) {
         let players = session.players.read().await;
-        for (pid, sender) in players.iter() {
+        let spectators = session.spectators.read().await;
+        for (pid, sender) in players.iter().chain(spectators.iter()) {
             let internal_msg =
                 GameMessage { r#type: "SERVER_PUSH".to_string(), payload: serde_json::to_value(&message).unwrap() };
             let _ = sender.send(internal_msg);
@@ -47,27 +63,141 @@ async fn broadcast_message(state: &SharedState, game_id: GameId, message: Server
     }
 }
 
+/// Send a message to everyone following a tournament's bracket.
+pub(crate) async fn broadcast_bracket(state: &SharedState, tournament_id: TournamentId, message: ServerMessage) {
+    let brackets = state.session_manager.brackets.read().await;
+    if let Some(watchers) = brackets.get(&tournament_id) {
+        for (pid, sender) in watchers.iter() {
+            let internal_msg =
+                GameMessage { r#type: "SERVER_PUSH".to_string(), payload: serde_json::to_value(&message).unwrap() };
+            let _ = sender.send(internal_msg);
+            tracing::debug!(tournament_id = %tournament_id, to_player = %pid, "Broadcasted bracket");
+        }
+    }
+}
+
+/// A read-only feed of a tournament's bracket. Anyone may follow it.
+#[instrument(skip(ws, state))]
+pub async fn bracket_feed_handler(
+    ws: WebSocketUpgrade,
+    Path(tournament_id): Path<TournamentId>,
+    Query(params): Query<BracketFeedParams>,
+    State(state): State<SharedState>,
+) -> impl IntoResponse {
+    tracing::info!(tournament_id = %tournament_id, player_id = %params.player_id, "Bracket feed requested.");
+    ws.on_upgrade(move |socket| handle_bracket_socket(socket, tournament_id, params.player_id, state))
+}
+
+/// Register -> Push every bracket change -> Unregister once the client goes away
+async fn handle_bracket_socket(
+    mut socket: WebSocket,
+    tournament_id: TournamentId,
+    player_id: PlayerId,
+    state: SharedState,
+) {
+    let tournament = match state.repository.load_tournament(tournament_id).await {
+        Ok(tournament) => tournament,
+        Err(e) => {
+            tracing::warn!(tournament_id = %tournament_id, error = ?e, "Bracket feed rejected.");
+            let _ = socket.close().await;
+            return;
+        }
+    };
+
+    let (sender_tx, mut sender_rx) = register_bracket_session(&state, tournament_id, player_id).await;
+    let _ = sender_tx.send(GameMessage {
+        r#type: "SERVER_PUSH".into(),
+        payload: serde_json::to_value(ServerMessage::BracketState(tournament)).unwrap(),
+    });
+
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+    let send_task = tokio::spawn(async move {
+        while let Some(msg) = sender_rx.recv().await {
+            let json_str = serde_json::to_string(&msg.payload).unwrap_or_default();
+            if ws_sender.send(Message::Text(json_str.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // The feed is one-way; read only to notice the client leaving.
+    while let Some(Ok(_)) = ws_receiver.next().await {}
+
+    remove_bracket_session(&state, tournament_id, player_id).await;
+    send_task.abort();
+}
+
+/// Add a bracket follower to SessionManager and return their message receiver
+pub(crate) async fn register_bracket_session(
+    state: &SharedState,
+    tournament_id: TournamentId,
+    player_id: PlayerId,
+) -> (
+    tokio::sync::mpsc::UnboundedSender<GameMessage>,
+    tokio::sync::mpsc::UnboundedReceiver<GameMessage>,
+) {
+    let (sender_tx, sender_rx) = tokio::sync::mpsc::unbounded_channel::<GameMessage>();
+    let mut brackets = state.session_manager.brackets.write().await;
+    brackets
+        .entry(tournament_id)
+        .or_default()
+        .insert(player_id, sender_tx.clone());
+    (sender_tx, sender_rx)
+}
+
+async fn remove_bracket_session(state: &SharedState, tournament_id: TournamentId, player_id: PlayerId) {
+    let mut brackets = state.session_manager.brackets.write().await;
+    if let Some(watchers) = brackets.get_mut(&tournament_id) {
+        watchers.remove(&player_id);
+        if watchers.is_empty() {
+            brackets.remove(&tournament_id);
+        }
+    }
+}
+
 /// Orchestrates the WebSocket lifecycle: Connect -> Register -> Loop -> Disconnect
-async fn handle_socket(mut socket: WebSocket, game_id: GameId, player_id: PlayerId, state: SharedState) {
-    tracing::info!(game_id = %game_id, player_id = %player_id, "WebSocket connected.");
+async fn handle_socket(
+    mut socket: WebSocket,
+    game_id: GameId,
+    player_id: PlayerId,
+    spectate: bool,
+    state: SharedState,
+) {
+    tracing::info!(game_id = %game_id, player_id = %player_id, spectate, "WebSocket connected.");
 
-    // Verify connections
-    if !validate_connection(&state, game_id, player_id).await {
+    // Verify connections, then register the session
+    let admitted = if spectate {
+        match admit_spectator(&state, game_id, player_id).await {
+            Ok(channel) => Some(channel),
+            Err(e) => {
+                tracing::warn!(game_id = %game_id, player_id = %player_id, error = ?e, "Spectator rejected.");
+                None
+            }
+        }
+    } else if validate_connection(&state, game_id, player_id).await {
+        Some(register_player_session(&state, game_id, player_id).await)
+    } else {
+        None
+    };
+    let Some((sender_tx, mut sender_rx)) = admitted else {
         let _ = socket.close().await;
         return;
-    }
-
-    // Register Session & Notify
-    let (sender_tx, mut sender_rx) = register_player_session(&state, game_id, player_id).await;
+    };
 
     // Send initial state
-    if let Ok(game) = state.repository.load_game(game_id).await {
+    if let Ok(mut game) = state.repository.load_game(game_id).await {
+        game.set_spectator_count(spectator_count(&state, game_id).await);
         let msg = GameMessage {
             r#type: "SERVER_PUSH".into(),
             payload: serde_json::to_value(ServerMessage::GameState(game)).unwrap(),
         };
         let _ = sender_tx.send(msg);
     }
+    if spectate {
+        // The session now holds a spectator's only sender, so taking them out of it (e.g. when the host
+        // turns spectating off) ends the write task, and with it the socket.
+        drop(sender_tx);
+    }
 
     // Split Socket
     let (mut ws_sender, mut ws_receiver) = socket.split();
@@ -80,19 +210,28 @@ async fn handle_socket(mut socket: WebSocket, game_id: GameId, player_id: Player
                 break;
             }
         }
+        let _ = ws_sender.close().await;
     });
 
     // Read Loop (Client -> Server)
     while let Some(Ok(msg)) = ws_receiver.next().await {
         if let Message::Text(text) = msg {
             if let Ok(client_msg) = serde_json::from_str::<ClientMessage>(&text) {
-                process_client_message(client_msg, game_id, player_id, &state).await;
+                if spectate {
+                    reject_spectator_message(client_msg, game_id, player_id, &state).await;
+                } else {
+                    process_client_message(client_msg, game_id, player_id, &state).await;
+                }
             }
         }
     }
 
     // Cleanup on Disconnect
-    handle_disconnect(&state, game_id, player_id).await;
+    if spectate {
+        handle_spectator_disconnect(&state, game_id, player_id).await;
+    } else {
+        handle_disconnect(&state, game_id, player_id).await;
+    }
     send_task.abort();
 }
 
@@ -111,8 +250,43 @@ async fn validate_connection(state: &SharedState, game_id: GameId, player_id: Pl
     true
 }
 
+/// Seat a spectator in the game's session, if the host allows it, and return their message receiver.
+/// The session is the one record of who is watching, so the seat is counted and taken under its lock.
+pub(crate) async fn admit_spectator(
+    state: &SharedState,
+    game_id: GameId,
+    spectator_id: PlayerId,
+) -> Result<(PlayerSender, tokio::sync::mpsc::UnboundedReceiver<GameMessage>), AppError> {
+    let game = state.repository.load_game(game_id).await?;
+    let (sender_tx, sender_rx) = tokio::sync::mpsc::unbounded_channel::<GameMessage>();
+    {
+        let mut sessions = state.session_manager.sessions.write().await;
+        let session = sessions
+            .entry(game_id)
+            .or_insert_with(|| std::sync::Arc::new(GameSession::default()));
+        let mut spectators = session.spectators.write().await;
+        // A second socket under the same id would take over the first one's seat.
+        if spectators.contains_key(&spectator_id) {
+            return Err(GameError::AlreadySpectating.into());
+        }
+        game.check_spectator_seat(spectators.len())?;
+        spectators.insert(spectator_id, sender_tx.clone());
+    }
+
+    publish_state(state, &game).await;
+    Ok((sender_tx, sender_rx))
+}
+
+/// How many people are watching the game on this server
+pub(crate) async fn spectator_count(state: &SharedState, game_id: GameId) -> usize {
+    match state.session_manager.sessions.read().await.get(&game_id) {
+        Some(session) => session.spectators.read().await.len(),
+        None => 0,
+    }
+}
+
 /// Add player to SessionManager and return their message receiver
-async fn register_player_session(
+pub(crate) async fn register_player_session(
     state: &SharedState,
     game_id: GameId,
     player_id: PlayerId,
@@ -135,49 +309,138 @@ async fn register_player_session(
     (sender_tx, sender_rx)
 }
 
+/// Drop every spectator session, e.g. once the host turns spectating off. Their sockets close once
+/// their senders are gone.
+pub(crate) async fn clear_spectator_sessions(state: &SharedState, game_id: GameId) {
+    let sessions = state.session_manager.sessions.read().await;
+    if let Some(session) = sessions.get(&game_id) {
+        session.spectators.write().await.clear();
+    }
+}
+
+/// Stop broadcasting to a player, e.g. once their socket closes or they leave the lobby
+pub(crate) async fn remove_player_session(state: &SharedState, game_id: GameId, player_id: PlayerId) {
+    let sessions = state.session_manager.sessions.read().await;
+    if let Some(session) = sessions.get(&game_id) {
+        session.players.write().await.remove(&player_id);
+    }
+}
+
 /// Route incoming messages to logic
 async fn process_client_message(msg: ClientMessage, game_id: GameId, player_id: PlayerId, state: &SharedState) {
     tracing::debug!(game_id = %game_id, player_id = %player_id, "Received message: {:#?}", msg);
     match msg {
         ClientMessage::Connect { .. } => {} // No-op
         ClientMessage::Roll => handle_roll_command(game_id, player_id, state).await,
+        ClientMessage::Ready { ready } => handle_ready_command(game_id, player_id, ready, state).await,
+        ClientMessage::Leave => handle_leave_command(game_id, player_id, state).await,
+        ClientMessage::Kick { player_id: target } => handle_kick_command(game_id, player_id, target, state).await,
+        ClientMessage::Team { team_id, player_id: target } => {
+            handle_team_command(game_id, player_id, target.unwrap_or(player_id), team_id, state).await
+        }
+        ClientMessage::Forfeit => handle_forfeit_command(game_id, player_id, state).await,
+        ClientMessage::Rematch => handle_rematch_command(game_id, player_id, state).await,
     }
 }
 
-/// Execute the ROLL command logic
-async fn handle_roll_command(game_id: GameId, player_id: PlayerId, state: &SharedState) {
-    if let Ok(mut game) = state.repository.load_game(game_id).await {
-        let mut roller = ThreadRngRoller::new();
-
-        // Roll
-        match game.roll(player_id, &mut roller) {
-            Ok(events) => {
-                // Save
-                if let Err(e) = state.repository.save_game(&game).await {
-                    tracing::error!("Failed to save game state: {}", e);
-                    return;
-                }
+/// Spectators may only watch; every command gets an error back
+async fn reject_spectator_message(msg: ClientMessage, game_id: GameId, spectator_id: PlayerId, state: &SharedState) {
+    tracing::debug!(game_id = %game_id, spectator_id = %spectator_id, "Ignoring spectator message: {:#?}", msg);
+    if !matches!(msg, ClientMessage::Connect { .. }) {
+        send_error_to_player(state, game_id, spectator_id, &GameError::Spectating.to_string()).await;
+    }
+}
 
-                for event in events {
-                    match event {
-                        GameEvent::Rolled { player_id, value } => {
-                            broadcast_message(
-                                state,
-                                game_id,
-                                ServerMessage::RollResult { player_id, rolled_value: value },
-                            )
-                            .await
-                        }
-                        GameEvent::GameOver { winner_id, loser_id } => {
-                            broadcast_message(state, game_id, ServerMessage::GameOver { winner_id, loser_id }).await
-                        }
-                    }
-                }
-                broadcast_message(state, game_id, ServerMessage::GameState(game)).await;
+/// Execute the READY command logic
+async fn handle_ready_command(game_id: GameId, player_id: PlayerId, ready: bool, state: &SharedState) {
+    match update_game(state, game_id, |game| Ok(game.set_ready(player_id, ready)?)).await {
+        Ok(_) => broadcast_message(state, game_id, ServerMessage::PlayerReady { player_id, ready }).await,
+        Err(AppError::Game(e)) => send_error_to_player(state, game_id, player_id, &e.to_string()).await,
+        Err(e @ AppError::VersionConflict { .. }) => {
+            send_error_to_player(state, game_id, player_id, &e.to_string()).await
+        }
+        Err(e) => tracing::error!(game_id = %game_id, "Failed to update ready flag: {}", e),
+    }
+}
+
+/// Execute the LEAVE command logic
+async fn handle_leave_command(game_id: GameId, player_id: PlayerId, state: &SharedState) {
+    if let Err(e) = leave_lobby(state, game_id, player_id).await {
+        match e {
+            AppError::Game(e) => send_error_to_player(state, game_id, player_id, &e.to_string()).await,
+            e @ AppError::VersionConflict { .. } => {
+                send_error_to_player(state, game_id, player_id, &e.to_string()).await
             }
-            Err(e) => {
-                send_error_to_player(state, game_id, player_id, &e.to_string()).await;
+            e => tracing::error!(game_id = %game_id, "Failed to leave lobby: {}", e),
+        }
+    }
+}
+
+/// Execute the KICK command logic
+async fn handle_kick_command(game_id: GameId, host_id: PlayerId, player_id: PlayerId, state: &SharedState) {
+    if let Err(e) = kick_from_lobby(state, game_id, host_id, player_id).await {
+        match e {
+            AppError::Game(e) => send_error_to_player(state, game_id, host_id, &e.to_string()).await,
+            e @ AppError::VersionConflict { .. } => send_error_to_player(state, game_id, host_id, &e.to_string()).await,
+            e => tracing::error!(game_id = %game_id, "Failed to kick player: {}", e),
+        }
+    }
+}
+
+/// Execute the TEAM command logic
+async fn handle_team_command(
+    game_id: GameId,
+    assigned_by: PlayerId,
+    player_id: PlayerId,
+    team_id: TeamId,
+    state: &SharedState,
+) {
+    if let Err(e) = choose_team(state, game_id, assigned_by, player_id, team_id).await {
+        match e {
+            AppError::Game(e) => send_error_to_player(state, game_id, assigned_by, &e.to_string()).await,
+            e @ AppError::VersionConflict { .. } => {
+                send_error_to_player(state, game_id, assigned_by, &e.to_string()).await
+            }
+            e => tracing::error!(game_id = %game_id, "Failed to change teams: {}", e),
+        }
+    }
+}
+
+/// Execute the REMATCH command logic
+async fn handle_rematch_command(game_id: GameId, player_id: PlayerId, state: &SharedState) {
+    if let Err(e) = accept_rematch(state, game_id, player_id).await {
+        match e {
+            AppError::Game(e) => send_error_to_player(state, game_id, player_id, &e.to_string()).await,
+            e @ AppError::VersionConflict { .. } => {
+                send_error_to_player(state, game_id, player_id, &e.to_string()).await
+            }
+            e => tracing::error!(game_id = %game_id, "Failed to set up rematch: {}", e),
+        }
+    }
+}
+
+/// Execute the FORFEIT command logic
+async fn handle_forfeit_command(game_id: GameId, player_id: PlayerId, state: &SharedState) {
+    if let Err(e) = forfeit_player(state, game_id, player_id).await {
+        match e {
+            AppError::Game(e) => send_error_to_player(state, game_id, player_id, &e.to_string()).await,
+            e @ AppError::VersionConflict { .. } => {
+                send_error_to_player(state, game_id, player_id, &e.to_string()).await
+            }
+            e => tracing::error!(game_id = %game_id, "Failed to forfeit: {}", e),
+        }
+    }
+}
+
+/// Execute the ROLL command logic
+async fn handle_roll_command(game_id: GameId, player_id: PlayerId, state: &SharedState) {
+    if let Err(e) = roll_for(state, game_id, player_id).await {
+        match e {
+            AppError::Game(e) => send_error_to_player(state, game_id, player_id, &e.to_string()).await,
+            e @ AppError::VersionConflict { .. } => {
+                send_error_to_player(state, game_id, player_id, &e.to_string()).await
             }
+            e => tracing::error!(game_id = %game_id, "Failed to roll: {}", e),
         }
     }
 }
@@ -186,28 +449,46 @@ async fn handle_roll_command(game_id: GameId, player_id: PlayerId, state: &Share
 async fn handle_disconnect(state: &SharedState, game_id: GameId, player_id: PlayerId) {
     tracing::info!(game_id = %game_id, player_id = %player_id, "WebSocket disconnected.");
 
-    // Remove from session
-    {
-        let sessions = state.session_manager.sessions.read().await;
-        if let Some(session) = sessions.get(&game_id) {
-            session.players.write().await.remove(&player_id);
-        }
-    }
+    remove_player_session(state, game_id, player_id).await;
 
     // Update Redis state to Paused
-    if let Ok(mut game) = state.repository.load_game(game_id).await {
-        if *game.get_status() == GameStatus::InProgress {
+    let paused = update_game(state, game_id, |game| {
+        let in_play = *game.get_status() == GameStatus::InProgress && !game.is_eliminated(player_id);
+        if in_play {
             let _ = game.pause_game(player_id);
-            let _ = state.repository.save_game(&game).await;
-            broadcast_message(state, game_id, ServerMessage::GameState(game)).await;
         }
+        Ok(in_play)
+    })
+    .await;
+    if let Ok((game, true)) = paused {
+        publish_state(state, &game).await;
+    }
+}
+
+/// Cleanup when a spectator's socket closes
+async fn handle_spectator_disconnect(state: &SharedState, game_id: GameId, spectator_id: PlayerId) {
+    tracing::info!(game_id = %game_id, spectator_id = %spectator_id, "Spectator disconnected.");
+
+    // If the host already turned spectating off, the seat is gone
+    let was_watching = match state.session_manager.sessions.read().await.get(&game_id) {
+        Some(session) => session.spectators.write().await.remove(&spectator_id).is_some(),
+        None => false,
+    };
+    if !was_watching {
+        return;
+    }
+
+    if let Ok(game) = state.repository.load_game(game_id).await {
+        publish_state(state, &game).await;
     }
 }
 
-/// Send an error message to a specific player
+/// Send an error message to a specific player or spectator
 async fn send_error_to_player(state: &SharedState, game_id: GameId, player_id: PlayerId, msg: &str) {
     if let Some(session) = state.session_manager.sessions.read().await.get(&game_id) {
-        if let Some(sender) = session.players.read().await.get(&player_id) {
+        let players = session.players.read().await;
+        let spectators = session.spectators.read().await;
+        if let Some(sender) = players.get(&player_id).or_else(|| spectators.get(&player_id)) {
             let _ = sender.send(GameMessage {
                 r#type: "ERROR".into(),
                 payload: serde_json::to_value(ServerMessage::Error { message: msg.into() }).unwrap(),
@@ -223,31 +504,57 @@ mod ws_logic_tests {
     use axum::Json;
 
     use super::*;
+    use crate::archive::SqliteArchive;
     use crate::config::Config;
-    use crate::data::{CreateGameRequest, JoinGameRequest, MockGameRepository};
+    use crate::data::{CreateGameRequest, JoinGameRequest, MockGameRepository, StartGameRequest};
     use crate::game::GameStatus;
-    use crate::handlers::{create_game_handler, join_game_handler};
+    use crate::handlers::{create_game_handler, join_game_handler, start_game_handler};
     use crate::state::{AppState, GameSessionManager};
 
     async fn setup_test_state() -> SharedState {
         let repository = Arc::new(MockGameRepository::new());
         let config = Config {
             server: crate::config::ServerConfig { addr: "0,0,0,0:0".to_string() },
-            database: crate::config::DatabaseConfig { redis_url: "redis://mock".to_string() },
+            database: crate::config::DatabaseConfig {
+                redis_url: "redis://mock".to_string(),
+                sqlite_url: "sqlite::memory:".to_string(),
+            },
             logging: crate::config::LoggingConfig { level: "debug".to_string() },
+            game: crate::config::GameConfig::default(),
         };
 
-        Arc::new(AppState { repository, session_manager: GameSessionManager::default(), config: Arc::new(config) })
+        let archive = Arc::new(SqliteArchive::new(&config.database.sqlite_url).unwrap());
+        Arc::new(AppState {
+            repository,
+            archive: archive.clone(),
+            players: archive,
+            session_manager: GameSessionManager::default(),
+            config: Arc::new(config),
+        })
+    }
+
+    /// The guest readies up over the socket and the host starts the game.
+    async fn ready_and_start(state: &SharedState, game_id: GameId, host_id: PlayerId, guest_id: PlayerId) {
+        process_client_message(ClientMessage::Ready { ready: true }, game_id, guest_id, state).await;
+        let _ = start_game_handler(
+            State(state.clone()),
+            Path(game_id),
+            Json(StartGameRequest { player_id: host_id }),
+        )
+        .await
+        .unwrap();
     }
 
     #[tokio::test]
     async fn test_validate_connection() {
         let state = setup_test_state().await;
         let host_id = PlayerId::new();
-        let (_, Json(created)) =
-            create_game_handler(State(state.clone()), Json(CreateGameRequest { host_id: Some(host_id) }))
-                .await
-                .unwrap();
+        let (_, Json(created)) = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest { host_id: Some(host_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
 
         assert!(validate_connection(&state, created.game_id, host_id).await);
         let random_id = PlayerId::new();
@@ -271,18 +578,21 @@ mod ws_logic_tests {
     async fn test_handle_roll_command_flow() {
         let state = setup_test_state().await;
         let host_id = PlayerId::new();
-        let (_, Json(created)) =
-            create_game_handler(State(state.clone()), Json(CreateGameRequest { host_id: Some(host_id) }))
-                .await
-                .unwrap();
+        let (_, Json(created)) = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest { host_id: Some(host_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
         let guest_id = PlayerId::new();
         let _ = join_game_handler(
             State(state.clone()),
             Path(created.game_id),
-            Json(JoinGameRequest { player_id: Some(guest_id) }),
+            Json(JoinGameRequest { player_id: Some(guest_id), ..Default::default() }),
         )
         .await
         .unwrap();
+        ready_and_start(&state, created.game_id, host_id, guest_id).await;
 
         let (_, mut host_rx) = register_player_session(&state, created.game_id, host_id).await;
         let (_, mut guest_rx) = register_player_session(&state, created.game_id, guest_id).await;
@@ -327,24 +637,114 @@ mod ws_logic_tests {
         let _ = guest_rx.recv().await.expect("Guest missed message 2");
     }
 
+    #[tokio::test]
+    async fn test_ready_and_start_are_broadcast() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let (_, Json(created)) = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest { host_id: Some(host_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+        let guest_id = PlayerId::new();
+        let _ = join_game_handler(
+            State(state.clone()),
+            Path(created.game_id),
+            Json(JoinGameRequest { player_id: Some(guest_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+        let (_, mut host_rx) = register_player_session(&state, created.game_id, host_id).await;
+
+        // Rolling is refused while the lobby is open
+        handle_roll_command(created.game_id, host_id, &state).await;
+        let game = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(*game.get_status(), GameStatus::WaitingForPlayers);
+
+        ready_and_start(&state, created.game_id, host_id, guest_id).await;
+
+        let messages: Vec<ServerMessage> = std::iter::from_fn(|| host_rx.try_recv().ok())
+            .filter_map(|msg| serde_json::from_value(msg.payload).ok())
+            .collect();
+        assert!(messages
+            .iter()
+            .any(|m| matches!(m, ServerMessage::PlayerReady { player_id, ready: true } if *player_id == guest_id)));
+        assert!(messages
+            .iter()
+            .any(|m| matches!(m, ServerMessage::GameStarted { game } if *game.get_status() == GameStatus::InProgress)));
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            ServerMessage::TurnOrderDecided { order, .. } if *order == vec![host_id, guest_id]
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_leave_and_kick_commands() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let (_, Json(created)) = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest { host_id: Some(host_id), max_players: Some(3), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+        let guests = [PlayerId::new(), PlayerId::new()];
+        for guest_id in guests {
+            let _ = join_game_handler(
+                State(state.clone()),
+                Path(created.game_id),
+                Json(JoinGameRequest { player_id: Some(guest_id), ..Default::default() }),
+            )
+            .await
+            .unwrap();
+        }
+        let (_, mut kicked_rx) = register_player_session(&state, created.game_id, guests[1]).await;
+        let (_, mut guest_rx) = register_player_session(&state, created.game_id, guests[0]).await;
+
+        let kick = ClientMessage::Kick { player_id: guests[1] };
+        process_client_message(kick, created.game_id, host_id, &state).await;
+        process_client_message(ClientMessage::Leave, created.game_id, host_id, &state).await;
+
+        // The kicked player hears about it, then stops getting table updates
+        let kicked: Vec<ServerMessage> = std::iter::from_fn(|| kicked_rx.try_recv().ok())
+            .filter_map(|msg| serde_json::from_value(msg.payload).ok())
+            .collect();
+        assert!(matches!(
+            kicked.last(),
+            Some(ServerMessage::PlayerKicked { player_id }) if *player_id == guests[1]
+        ));
+
+        let messages: Vec<ServerMessage> = std::iter::from_fn(|| guest_rx.try_recv().ok())
+            .filter_map(|msg| serde_json::from_value(msg.payload).ok())
+            .collect();
+        assert!(messages.contains(&ServerMessage::PlayerLeft { player_id: host_id, host_id: guests[0] }));
+        let game = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(game.get_players(), &[guests[0]]);
+        assert!(!validate_connection(&state, created.game_id, guests[1]).await);
+    }
+
     #[tokio::test]
     async fn test_handle_disconnect_pauses_game() {
         let state = setup_test_state().await;
 
         // 1. Setup Game
         let host_id = PlayerId::new();
-        let (_, Json(created)) =
-            create_game_handler(State(state.clone()), Json(CreateGameRequest { host_id: Some(host_id) }))
-                .await
-                .unwrap();
+        let (_, Json(created)) = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest { host_id: Some(host_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
         let guest_id = PlayerId::new();
         let _ = join_game_handler(
             State(state.clone()),
             Path(created.game_id),
-            Json(JoinGameRequest { player_id: Some(guest_id) }),
+            Json(JoinGameRequest { player_id: Some(guest_id), ..Default::default() }),
         )
         .await
         .unwrap();
+        ready_and_start(&state, created.game_id, host_id, guest_id).await;
 
         // Register Guest
         let (_, mut guest_rx) = register_player_session(&state, created.game_id, guest_id).await;
@@ -382,4 +782,315 @@ mod ws_logic_tests {
             panic!("Expected GameState broadcast, got {:?}", final_msg);
         }
     }
+
+    #[tokio::test]
+    async fn test_game_over_settles_wager() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let guest_id = PlayerId::new();
+        state.repository.deposit(host_id, 100).await.unwrap();
+        state.repository.deposit(guest_id, 100).await.unwrap();
+
+        let payload =
+            CreateGameRequest { host_id: Some(host_id), starting_max: Some(2), wager: Some(50), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let join = JoinGameRequest { player_id: Some(guest_id), accept_wager: true, ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join))
+            .await
+            .unwrap();
+        ready_and_start(&state, created.game_id, host_id, guest_id).await;
+
+        let (_, mut host_rx) = register_player_session(&state, created.game_id, host_id).await;
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        while !game.is_finished() {
+            handle_roll_command(created.game_id, *game.get_current_player().unwrap(), &state).await;
+            game = state.repository.load_game(created.game_id).await.unwrap();
+        }
+
+        let winner_id = game.get_active_players()[0];
+        let loser_id = if winner_id == host_id { guest_id } else { host_id };
+        assert_eq!(state.repository.get_balance(winner_id).await.unwrap(), 150);
+        assert_eq!(state.repository.get_balance(loser_id).await.unwrap(), 50);
+
+        let mut settled = None;
+        while let Ok(msg) = host_rx.try_recv() {
+            if let Ok(ServerMessage::PotSettled { winners, amount }) = serde_json::from_value(msg.payload) {
+                settled = Some((winners, amount));
+            }
+        }
+        assert_eq!(settled, Some((vec![winner_id], 100)));
+    }
+
+    #[tokio::test]
+    async fn test_rematch_opens_linked_game() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let payload = CreateGameRequest { host_id: Some(host_id), starting_max: Some(2), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let guest_id = PlayerId::new();
+        let _ = join_game_handler(
+            State(state.clone()),
+            Path(created.game_id),
+            Json(JoinGameRequest { player_id: Some(guest_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+        ready_and_start(&state, created.game_id, host_id, guest_id).await;
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        while !game.is_finished() {
+            handle_roll_command(created.game_id, *game.get_current_player().unwrap(), &state).await;
+            game = state.repository.load_game(created.game_id).await.unwrap();
+        }
+        let loser_id = game.get_loser().unwrap();
+        let (_, mut host_rx) = register_player_session(&state, created.game_id, host_id).await;
+
+        process_client_message(ClientMessage::Rematch, created.game_id, host_id, &state).await;
+        process_client_message(ClientMessage::Rematch, created.game_id, guest_id, &state).await;
+        // A repeated vote must not open a second rematch.
+        process_client_message(ClientMessage::Rematch, created.game_id, guest_id, &state).await;
+
+        let old = state.repository.load_game(created.game_id).await.unwrap();
+        let rematch_id = old.get_rematch_id().expect("rematch should be linked");
+        let rematch = state.repository.load_game(rematch_id).await.unwrap();
+        assert_eq!(rematch.get_rematch_of(), Some(created.game_id));
+        assert_eq!(*rematch.get_status(), GameStatus::InProgress);
+        assert_eq!(rematch.get_current_player(), Some(&loser_id));
+        assert_eq!(rematch.get_settings().starting_max, 2);
+
+        let messages: Vec<ServerMessage> = std::iter::from_fn(|| host_rx.try_recv().ok())
+            .filter_map(|msg| serde_json::from_value(msg.payload).ok())
+            .collect();
+        assert!(messages.contains(&ServerMessage::RematchVote { player_id: host_id, accepted: vec![host_id] }));
+        let ready = ServerMessage::RematchReady { previous_game_id: created.game_id, game_id: rematch_id };
+        assert_eq!(messages.iter().filter(|m| **m == ready).count(), 1);
+        assert!(!messages
+            .iter()
+            .any(|m| matches!(m, ServerMessage::RematchReady { game_id, .. } if *game_id != rematch_id)));
+    }
+
+    #[tokio::test]
+    async fn test_failed_rematch_can_be_retried() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let guest_id = PlayerId::new();
+        state.repository.deposit(host_id, 100).await.unwrap();
+        state.repository.deposit(guest_id, 100).await.unwrap();
+        let payload =
+            CreateGameRequest { host_id: Some(host_id), starting_max: Some(2), wager: Some(100), ..Default::default() };
+        let (_, Json(created)) = create_game_handler(State(state.clone()), Json(payload)).await.unwrap();
+        let join = JoinGameRequest { player_id: Some(guest_id), accept_wager: true, ..Default::default() };
+        let _ = join_game_handler(State(state.clone()), Path(created.game_id), Json(join))
+            .await
+            .unwrap();
+        ready_and_start(&state, created.game_id, host_id, guest_id).await;
+
+        let mut game = state.repository.load_game(created.game_id).await.unwrap();
+        while !game.is_finished() {
+            handle_roll_command(created.game_id, *game.get_current_player().unwrap(), &state).await;
+            game = state.repository.load_game(created.game_id).await.unwrap();
+        }
+        let loser_id = game.get_loser().unwrap();
+
+        // The loser has nothing left to stake, so the rematch cannot open and the claim is dropped.
+        process_client_message(ClientMessage::Rematch, created.game_id, host_id, &state).await;
+        process_client_message(ClientMessage::Rematch, created.game_id, guest_id, &state).await;
+        let old = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(old.get_rematch_id(), None);
+        assert!(old.get_rematch_votes().is_empty());
+
+        state.repository.deposit(loser_id, 100).await.unwrap();
+        process_client_message(ClientMessage::Rematch, created.game_id, host_id, &state).await;
+        process_client_message(ClientMessage::Rematch, created.game_id, guest_id, &state).await;
+        let old = state.repository.load_game(created.game_id).await.unwrap();
+        let rematch = state.repository.load_game(old.get_rematch_id().unwrap()).await.unwrap();
+        assert_eq!(*rematch.get_status(), GameStatus::InProgress);
+        assert_eq!(state.repository.get_balance(loser_id).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_match_progresses_to_a_winner() {
+        use crate::data::CreateMatchRequest;
+        use crate::game::MatchStatus;
+        use crate::handlers::create_match_handler;
+
+        let state = setup_test_state().await;
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let payload = CreateMatchRequest {
+            players: players.clone(),
+            best_of: Some(3),
+            starting_max: Some(2),
+            ..Default::default()
+        };
+        let (_, Json(created)) = create_match_handler(State(state.clone()), Json(payload)).await.unwrap();
+
+        let mut series = created.clone();
+        let mut progress = 0;
+        while *series.get_status() == MatchStatus::InProgress {
+            let game_id = series.get_current_game().unwrap();
+            let mut game = state.repository.load_game(game_id).await.unwrap();
+            // Seating rotates, so the first roll alternates between games.
+            assert_eq!(game.get_host(), players[(series.get_games().len() - 1) % 2]);
+
+            let (_, mut rx) = register_player_session(&state, game_id, players[0]).await;
+            while !game.is_finished() {
+                handle_roll_command(game_id, *game.get_current_player().unwrap(), &state).await;
+                game = state.repository.load_game(game_id).await.unwrap();
+            }
+            while let Ok(msg) = rx.try_recv() {
+                match serde_json::from_value(msg.payload) {
+                    Ok(ServerMessage::MatchProgress { next_game_id, .. }) => {
+                        progress += 1;
+                        assert_ne!(next_game_id, game_id);
+                    }
+                    Ok(ServerMessage::MatchOver { winner_id, wins, .. }) => assert_eq!(wins[&winner_id], 2),
+                    _ => {}
+                }
+            }
+            series = state.repository.load_match(created.get_id()).await.unwrap();
+        }
+
+        let MatchStatus::Finished(winner_id) = *series.get_status() else {
+            unreachable!()
+        };
+        assert_eq!(series.get_wins()[&winner_id], 2);
+        assert_eq!(
+            series.get_games().len(),
+            series.get_wins().values().sum::<u32>() as usize
+        );
+        assert_eq!(progress, series.get_games().len() - 1);
+    }
+
+    #[tokio::test]
+    async fn test_match_is_voided_when_the_next_game_cannot_start() {
+        use crate::data::CreateMatchRequest;
+        use crate::game::MatchStatus;
+        use crate::handlers::create_match_handler;
+
+        let state = setup_test_state().await;
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        // Enough for one game each, so the loser of the first cannot cover the second.
+        for player_id in &players {
+            state.repository.deposit(*player_id, 50).await.unwrap();
+        }
+        let payload = CreateMatchRequest {
+            players: players.clone(),
+            best_of: Some(3),
+            starting_max: Some(2),
+            wager: Some(50),
+            ..Default::default()
+        };
+        let (_, Json(created)) = create_match_handler(State(state.clone()), Json(payload)).await.unwrap();
+
+        let game_id = created.get_current_game().unwrap();
+        let (_, mut rx) = register_player_session(&state, game_id, players[0]).await;
+        let mut game = state.repository.load_game(game_id).await.unwrap();
+        while !game.is_finished() {
+            handle_roll_command(game_id, *game.get_current_player().unwrap(), &state).await;
+            game = state.repository.load_game(game_id).await.unwrap();
+        }
+
+        let series = state.repository.load_match(created.get_id()).await.unwrap();
+        assert_eq!(*series.get_status(), MatchStatus::Voided);
+        // The next game was claimed before it failed to open, so it is listed but was never saved.
+        assert_eq!(series.get_games()[0], game_id);
+        assert!(matches!(
+            state.repository.load_game(series.get_games()[1]).await,
+            Err(AppError::GameNotFound(_))
+        ));
+        let voided = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|msg| serde_json::from_value(msg.payload).ok())
+            .find_map(|msg| match msg {
+                ServerMessage::MatchVoided { match_id, wins, .. } => Some((match_id, wins.values().sum::<u32>())),
+                _ => None,
+            });
+        assert_eq!(voided, Some((created.get_id(), 1)));
+    }
+
+    #[tokio::test]
+    async fn test_forfeit_command_ends_game() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let (_, Json(created)) = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest { host_id: Some(host_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+        let guest_id = PlayerId::new();
+        let _ = join_game_handler(
+            State(state.clone()),
+            Path(created.game_id),
+            Json(JoinGameRequest { player_id: Some(guest_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+        ready_and_start(&state, created.game_id, host_id, guest_id).await;
+        let (_, mut host_rx) = register_player_session(&state, created.game_id, host_id).await;
+
+        process_client_message(ClientMessage::Forfeit, created.game_id, guest_id, &state).await;
+
+        let game = state.repository.load_game(created.game_id).await.unwrap();
+        assert_eq!(*game.get_status(), GameStatus::Forfeited(guest_id));
+        assert!(game.verify_rolls().unwrap().valid);
+
+        let messages: Vec<ServerMessage> = std::iter::from_fn(|| host_rx.try_recv().ok())
+            .filter_map(|msg| serde_json::from_value(msg.payload).ok())
+            .collect();
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            ServerMessage::PlayerForfeited { player_id, winner_id: Some(winner_id), .. }
+                if *player_id == guest_id && *winner_id == host_id
+        )));
+        assert!(messages.iter().any(
+            |m| matches!(m, ServerMessage::GameOver { losing_team, forfeited: true, .. }
+                if losing_team.players == [guest_id])
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_spectator_watches_but_cannot_roll() {
+        let state = setup_test_state().await;
+        let host_id = PlayerId::new();
+        let (_, Json(created)) = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest { host_id: Some(host_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+        let guest_id = PlayerId::new();
+        let _ = join_game_handler(
+            State(state.clone()),
+            Path(created.game_id),
+            Json(JoinGameRequest { player_id: Some(guest_id), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+
+        // Anyone may watch, player or not
+        let spectator_id = PlayerId::new();
+        let (_, mut spectator_rx) = admit_spectator(&state, created.game_id, spectator_id).await.unwrap();
+        // A second socket under the same id does not get a seat of its own.
+        let again = admit_spectator(&state, created.game_id, spectator_id).await;
+        assert!(matches!(again, Err(AppError::Game(GameError::AlreadySpectating))));
+        ready_and_start(&state, created.game_id, host_id, guest_id).await;
+
+        reject_spectator_message(ClientMessage::Roll, created.game_id, spectator_id, &state).await;
+        let game = state.repository.load_game(created.game_id).await.unwrap();
+        assert!(game.get_history().is_empty());
+        assert_eq!(spectator_count(&state, created.game_id).await, 1);
+
+        let messages: Vec<ServerMessage> = std::iter::from_fn(|| spectator_rx.try_recv().ok())
+            .filter_map(|msg| serde_json::from_value(msg.payload).ok())
+            .collect();
+        assert!(messages.contains(&ServerMessage::PlayerReady { player_id: guest_id, ready: true }));
+        assert!(messages
+            .iter()
+            .any(|msg| matches!(msg, ServerMessage::GameStarted { .. })));
+        assert!(messages.contains(&ServerMessage::Error { message: GameError::Spectating.to_string() }));
+
+        handle_spectator_disconnect(&state, created.game_id, spectator_id).await;
+        assert_eq!(spectator_count(&state, created.game_id).await, 0);
+    }
 }
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::PlayerId;
+use crate::players::PlayerStats;
+
+/// Entries per leaderboard page when the request does not say.
+pub const DEFAULT_LEADERBOARD_PAGE: u64 = 20;
+/// Largest page of a leaderboard a single request may ask for.
+pub const MAX_LEADERBOARD_PAGE: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardKind {
+    Wins,
+    WinRate,
+    Streak,
+}
+
+impl LeaderboardKind {
+    pub const ALL: [LeaderboardKind; 3] = [Self::Wins, Self::WinRate, Self::Streak];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Wins => "wins",
+            Self::WinRate => "win_rate",
+            Self::Streak => "streak",
+        }
+    }
+
+    /// Where a player with these stats scores on this board, or `None` if they are not on it:
+    /// the win rate board only takes players with at least `min_games` games behind them.
+    pub fn score(&self, stats: &PlayerStats, min_games: u64) -> Option<f64> {
+        match self {
+            Self::Wins => Some(stats.wins as f64),
+            Self::WinRate if stats.games_played >= min_games.max(1) => Some(stats.win_rate),
+            Self::WinRate => None,
+            Self::Streak => Some(stats.longest_streak as f64),
+        }
+    }
+}
+
+/// A player's new score on one board; `None` takes them off it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaderboardUpdate {
+    pub kind: LeaderboardKind,
+    pub player_id: PlayerId,
+    pub score: Option<f64>,
+}
+
+impl LeaderboardUpdate {
+    /// A player's place on every board, from their lifetime stats.
+    pub fn for_player(player_id: PlayerId, stats: &PlayerStats, min_games: u64) -> Vec<Self> {
+        LeaderboardKind::ALL
+            .iter()
+            .map(|kind| Self { kind: *kind, player_id, score: kind.score(stats, min_games) })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    /// One-based; players on the same score are ordered by player id, highest first.
+    pub rank: u64,
+    pub player_id: PlayerId,
+    pub score: f64,
+}
+
+/// Part of a leaderboard, best first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardPage {
+    pub entries: Vec<LeaderboardEntry>,
+    /// How many players are on the whole board.
+    pub total: u64,
+}
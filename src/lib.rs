@@ -1,9 +1,13 @@
+pub mod archive;
 pub mod config;
 pub mod data;
 pub mod error;
 pub mod game;
 pub mod handlers;
+pub mod leaderboards;
+pub mod players;
 pub mod state;
+pub mod wallet;
 
 use axum::{
     http::Method,
@@ -13,20 +17,31 @@ use axum::{
 use config::Config;
 use handlers::{rest, ws};
 use state::{AppState, GameSessionManager};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::{DefaultMakeSpan, TraceLayer},
 };
 
+use crate::archive::SqliteArchive;
 use crate::data::RedisRepository;
 
 pub fn create_app(config: Config) -> Router {
     let client = redis::Client::open(config.database.redis_url.clone()).expect("Invalid Redis URL");
 
     let repository = Arc::new(RedisRepository::new(client.clone()));
-    let state =
-        Arc::new(AppState { repository, session_manager: GameSessionManager::default(), config: Arc::new(config) });
+    let archive = Arc::new(SqliteArchive::new(&config.database.sqlite_url).expect("Invalid SQLite URL"));
+    let state = Arc::new(AppState {
+        repository,
+        archive: archive.clone(),
+        players: archive,
+        session_manager: GameSessionManager::default(),
+        config: Arc::new(config),
+    });
+    handlers::spawn_deadline_sweeper(
+        state.clone(),
+        Duration::from_millis(state.config.game.deadline_sweep_interval_ms),
+    );
 
     let cors = CorsLayer::new()
         .allow_methods([Method::GET, Method::POST])
@@ -38,7 +53,30 @@ pub fn create_app(config: Config) -> Router {
         .route("/game", post(rest::create_game_handler))
         .route("/game/{id}", get(rest::get_game_handler))
         .route("/game/{id}/join", post(rest::join_game_handler))
+        .route("/game/{id}/start", post(rest::start_game_handler))
+        .route("/game/{id}/leave", post(rest::leave_game_handler))
+        .route("/game/{id}/kick", post(rest::kick_player_handler))
+        .route("/game/{id}/team", post(rest::assign_team_handler))
+        .route("/game/{id}/spectators", post(rest::spectator_limit_handler))
+        .route("/game/{id}/history", get(rest::get_history_handler))
+        .route("/game/{id}/events", get(rest::get_events_handler))
+        .route("/game/{id}/verify", get(rest::verify_game_handler))
+        .route("/match", post(rest::create_match_handler))
+        .route("/match/{id}", get(rest::get_match_handler))
+        .route("/tournament", post(rest::create_tournament_handler))
+        .route("/tournament/{id}", get(rest::get_tournament_handler))
+        .route("/tournament/{id}/register", post(rest::register_handler))
+        .route("/tournament/{id}/withdraw", post(rest::withdraw_handler))
+        .route("/tournament/{id}/start", post(rest::start_tournament_handler))
+        .route("/players", post(rest::create_player_handler))
+        .route("/players/{id}", get(rest::get_player_handler))
+        .route("/players/by-name/{name}", get(rest::get_player_by_name_handler))
+        .route("/players/{id}/stats", get(rest::get_player_stats_handler))
+        .route("/players/{id}/balance", get(rest::get_balance_handler))
+        .route("/players/{id}/ledger", get(rest::get_ledger_handler))
+        .route("/leaderboards/{kind}", get(rest::get_leaderboard_handler))
         .route("/ws/game/{id}", get(ws::websocket_handler))
+        .route("/ws/tournament/{id}", get(ws::bracket_feed_handler))
         .with_state(state)
         .layer(TraceLayer::new_for_http().make_span_with(DefaultMakeSpan::default().include_headers(true)))
         .layer(cors)
@@ -47,7 +85,7 @@ pub fn create_app(config: Config) -> Router {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{Config, DatabaseConfig, LoggingConfig, ServerConfig};
+    use crate::config::{Config, DatabaseConfig, GameConfig, LoggingConfig, ServerConfig};
     use axum::{
         body::Body,
         http::{Request, StatusCode},
@@ -57,8 +95,12 @@ mod tests {
     fn test_config() -> Config {
         Config {
             server: ServerConfig { addr: "0.0.0.0:0".to_string() },
-            database: DatabaseConfig { redis_url: "redis://127.0.0.1:6379/".to_string() },
+            database: DatabaseConfig {
+                redis_url: "redis://127.0.0.1:6379/".to_string(),
+                sqlite_url: "sqlite::memory:".to_string(),
+            },
             logging: LoggingConfig { level: "info".to_string() },
+            game: GameConfig::default(),
         }
     }
 
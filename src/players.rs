@@ -0,0 +1,314 @@
+use async_trait::async_trait;
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+use crate::archive::{timestamp, SqliteArchive};
+use crate::error::AppError;
+use crate::game::PlayerId;
+use crate::leaderboards::LeaderboardKind;
+
+/// Longest display name a player may pick, in characters.
+pub const MAX_DISPLAY_NAME_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub player_id: PlayerId,
+    pub display_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PlayerProfile {
+    /// A fresh profile. The name is trimmed, and must be non-empty and at most `MAX_DISPLAY_NAME_LEN` long.
+    pub fn new(player_id: PlayerId, display_name: &str) -> Result<Self, AppError> {
+        let display_name = display_name.trim();
+        if display_name.is_empty()
+            || display_name.chars().count() > MAX_DISPLAY_NAME_LEN
+            || display_name.chars().any(char::is_control)
+        {
+            return Err(AppError::InvalidProfile(format!(
+                "Display names must be 1 to {} printable characters",
+                MAX_DISPLAY_NAME_LEN
+            )));
+        }
+        // Cut to the microsecond, the precision it is stored at.
+        let created_at = Utc::now().trunc_subsecs(6);
+        Ok(Self { player_id, display_name: display_name.to_string(), created_at })
+    }
+}
+
+/// A player's lifetime record over every finished game they played.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub games_played: u64,
+    pub wins: u64,
+    pub losses: u64,
+    /// Wins over games played; zero before the first game.
+    pub win_rate: f64,
+    /// Wins in a row up to the latest game.
+    pub current_streak: u64,
+    /// The most wins in a row so far.
+    pub longest_streak: u64,
+    /// Wins and losses in games decided by a forfeit rather than a losing roll. They are counted
+    /// in `wins` and `losses` as well.
+    pub forfeit_wins: u64,
+    pub forfeit_losses: u64,
+    pub total_rolls: u64,
+    /// The lowest roll the player survived, if they have survived one.
+    pub lowest_surviving_roll: Option<u32>,
+}
+
+impl PlayerStats {
+    /// Where the player seeds in a tournament: their win rate in hundredths of a percent, once they
+    /// qualify for the win rate leaderboard with `min_games` games. Unrated players seed last.
+    pub fn rating(&self, min_games: u64) -> Option<u32> {
+        LeaderboardKind::WinRate
+            .score(self, min_games)
+            .map(|win_rate| (win_rate * 10_000.0).round() as u32)
+    }
+
+    /// Tally a player's results, oldest first; `true` is a win.
+    pub fn from_results(results: impl IntoIterator<Item = bool>) -> Self {
+        let mut stats = Self::default();
+        for won in results {
+            stats.games_played += 1;
+            if won {
+                stats.wins += 1;
+                stats.current_streak += 1;
+                stats.longest_streak = stats.longest_streak.max(stats.current_streak);
+            } else {
+                stats.losses += 1;
+                stats.current_streak = 0;
+            }
+        }
+        if stats.games_played > 0 {
+            stats.win_rate = stats.wins as f64 / stats.games_played as f64;
+        }
+        stats
+    }
+}
+
+#[async_trait]
+pub trait PlayerRepository: Send + Sync {
+    /// Fails with `AppError::ProfileConflict` if the player already has a profile or the name is taken.
+    async fn create_profile(&self, profile: &PlayerProfile) -> Result<(), AppError>;
+    async fn load_profile(&self, player_id: PlayerId) -> Result<PlayerProfile, AppError>;
+    /// Display names are unique regardless of case, so this finds at most one profile.
+    async fn find_profile(&self, display_name: &str) -> Result<PlayerProfile, AppError>;
+    /// Worked out from the player's archived games, so a game counts from the moment it is archived,
+    /// and archiving it again never counts it twice.
+    async fn load_stats(&self, player_id: PlayerId) -> Result<PlayerStats, AppError>;
+}
+
+#[async_trait]
+impl PlayerRepository for SqliteArchive {
+    async fn create_profile(&self, profile: &PlayerProfile) -> Result<(), AppError> {
+        let pool = self.pool().await?;
+        let existing: Option<String> = sqlx::query_scalar("SELECT player_id FROM players WHERE player_id = ?")
+            .bind(profile.player_id.to_string())
+            .fetch_optional(pool)
+            .await?;
+        if existing.is_some() {
+            return Err(AppError::ProfileConflict(format!(
+                "Player {} already has a profile",
+                profile.player_id
+            )));
+        }
+
+        let inserted = sqlx::query("INSERT INTO players (player_id, display_name, created_at) VALUES (?, ?, ?)")
+            .bind(profile.player_id.to_string())
+            .bind(&profile.display_name)
+            .bind(timestamp(profile.created_at))
+            .execute(pool)
+            .await;
+        match inserted {
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(AppError::ProfileConflict(format!(
+                "The display name {} is taken",
+                profile.display_name
+            ))),
+            inserted => inserted.map(|_| ()).map_err(AppError::from),
+        }
+    }
+
+    async fn load_profile(&self, player_id: PlayerId) -> Result<PlayerProfile, AppError> {
+        let row = sqlx::query("SELECT player_id, display_name, created_at FROM players WHERE player_id = ?")
+            .bind(player_id.to_string())
+            .fetch_optional(self.pool().await?)
+            .await?
+            .ok_or(AppError::PlayerNotFound(player_id))?;
+        profile_from_row(&row)
+    }
+
+    async fn find_profile(&self, display_name: &str) -> Result<PlayerProfile, AppError> {
+        let row = sqlx::query("SELECT player_id, display_name, created_at FROM players WHERE display_name = ?")
+            .bind(display_name.trim())
+            .fetch_optional(self.pool().await?)
+            .await?
+            .ok_or_else(|| AppError::PlayerNameNotFound(display_name.trim().to_string()))?;
+        profile_from_row(&row)
+    }
+
+    async fn load_stats(&self, player_id: PlayerId) -> Result<PlayerStats, AppError> {
+        let pool = self.pool().await?;
+        let results: Vec<(bool, bool)> = sqlx::query_as(
+            "SELECT game_players.won, games.forfeited FROM game_players JOIN games USING (game_id) \
+             WHERE game_players.player_id = ? ORDER BY games.finished_at, games.rowid",
+        )
+        .bind(player_id.to_string())
+        .fetch_all(pool)
+        .await?;
+        let (total_rolls, lowest_surviving_roll): (i64, Option<i64>) = sqlx::query_as(
+            "SELECT COUNT(*), MIN(CASE WHEN lost THEN NULL ELSE value END) FROM rolls WHERE player_id = ?",
+        )
+        .bind(player_id.to_string())
+        .fetch_one(pool)
+        .await?;
+
+        let forfeits = |won: bool| results.iter().filter(|&&result| result == (won, true)).count() as u64;
+        Ok(PlayerStats {
+            forfeit_wins: forfeits(true),
+            forfeit_losses: forfeits(false),
+            total_rolls: total_rolls as u64,
+            lowest_surviving_roll: lowest_surviving_roll.map(|value| value as u32),
+            ..PlayerStats::from_results(results.iter().map(|(won, _)| *won))
+        })
+    }
+}
+
+fn profile_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<PlayerProfile, AppError> {
+    let player_id: String = row.try_get("player_id")?;
+    let player_id: PlayerId = player_id
+        .parse()
+        .map_err(|e| AppError::Internal(format!("Bad player id {}: {}", player_id, e)))?;
+    let created_at: String = row.try_get("created_at")?;
+    let created_at = DateTime::parse_from_rfc3339(&created_at)
+        .map_err(|e| AppError::Internal(format!("Bad created_at for player {}: {}", player_id, e)))?;
+    Ok(PlayerProfile { player_id, display_name: row.try_get("display_name")?, created_at: created_at.into() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::GameArchive;
+    use crate::game::{roller::Roller, Game};
+
+    /// Rolls through `values` in order.
+    struct ScriptedRoller(std::vec::IntoIter<u32>);
+
+    impl Roller for ScriptedRoller {
+        fn roll_in_range(&mut self, _max: u32) -> u32 {
+            self.0.next().unwrap()
+        }
+    }
+
+    /// Play a two-player game with `values` as the rolls, host first, and archive it.
+    async fn archive_game(archive: &SqliteArchive, host_id: PlayerId, guest_id: PlayerId, values: Vec<u32>) {
+        let mut game = Game::new(host_id);
+        game.join(guest_id).unwrap();
+        game.set_ready(guest_id, true).unwrap();
+        let mut roller = ScriptedRoller(values.into_iter());
+        game.start(host_id, &mut roller).unwrap();
+        while !game.is_finished() {
+            let player_id = *game.get_current_player().unwrap();
+            game.roll(player_id, &mut roller).unwrap();
+        }
+        let events = game.take_uncommitted_events();
+        archive.archive_game(&game, &events).await.unwrap();
+    }
+
+    #[test]
+    fn test_stats_from_results() {
+        let stats = PlayerStats::from_results([true, true, false, true, true, true, false, true]);
+        assert_eq!((stats.games_played, stats.wins, stats.losses), (8, 6, 2));
+        assert_eq!(stats.win_rate, 0.75);
+        assert_eq!((stats.current_streak, stats.longest_streak), (1, 3));
+        assert_eq!(PlayerStats::from_results([]).win_rate, 0.0);
+    }
+
+    #[test]
+    fn test_display_name_is_checked() {
+        assert_eq!(
+            PlayerProfile::new(PlayerId::new(), "  Lucky  ").unwrap().display_name,
+            "Lucky"
+        );
+        assert!(matches!(
+            PlayerProfile::new(PlayerId::new(), "   "),
+            Err(AppError::InvalidProfile(_))
+        ));
+        let long = "x".repeat(MAX_DISPLAY_NAME_LEN + 1);
+        assert!(matches!(
+            PlayerProfile::new(PlayerId::new(), &long),
+            Err(AppError::InvalidProfile(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_profiles_are_unique() {
+        let archive = SqliteArchive::new("sqlite::memory:").unwrap();
+        let profile = PlayerProfile::new(PlayerId::new(), "Lucky").unwrap();
+        archive.create_profile(&profile).await.unwrap();
+        assert_eq!(archive.load_profile(profile.player_id).await.unwrap(), profile);
+
+        let again = archive
+            .create_profile(&PlayerProfile::new(profile.player_id, "Other").unwrap())
+            .await;
+        assert!(matches!(again, Err(AppError::ProfileConflict(_))));
+        let taken = archive
+            .create_profile(&PlayerProfile::new(PlayerId::new(), "LUCKY").unwrap())
+            .await;
+        assert!(matches!(taken, Err(AppError::ProfileConflict(_))));
+        let missing = archive.load_profile(PlayerId::new()).await;
+        assert!(matches!(missing, Err(AppError::PlayerNotFound(_))));
+
+        assert_eq!(archive.find_profile("lucky").await.unwrap(), profile);
+        let missing = archive.find_profile("Nobody").await;
+        assert!(matches!(missing, Err(AppError::PlayerNameNotFound(name)) if name == "Nobody"));
+    }
+
+    #[tokio::test]
+    async fn test_stats_come_from_archived_games() {
+        let archive = SqliteArchive::new("sqlite::memory:").unwrap();
+        let (host_id, guest_id) = (PlayerId::new(), PlayerId::new());
+
+        // The host survives a 2 and the guest rolls out; then the host rolls out first.
+        archive_game(&archive, host_id, guest_id, vec![2, 1]).await;
+        archive_game(&archive, host_id, guest_id, vec![1]).await;
+        archive_game(&archive, host_id, guest_id, vec![500, 40, 1]).await;
+
+        let host = archive.load_stats(host_id).await.unwrap();
+        assert_eq!((host.games_played, host.wins, host.losses), (3, 1, 2));
+        assert_eq!((host.current_streak, host.longest_streak), (0, 1));
+        assert_eq!(host.total_rolls, 4);
+        assert_eq!(host.lowest_surviving_roll, Some(2));
+
+        let guest = archive.load_stats(guest_id).await.unwrap();
+        assert_eq!((guest.wins, guest.current_streak, guest.longest_streak), (2, 2, 2));
+        assert_eq!(guest.lowest_surviving_roll, Some(40));
+
+        assert_eq!(
+            archive.load_stats(PlayerId::new()).await.unwrap(),
+            PlayerStats::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats_count_forfeits() {
+        let archive = SqliteArchive::new("sqlite::memory:").unwrap();
+        let (host_id, guest_id) = (PlayerId::new(), PlayerId::new());
+        archive_game(&archive, host_id, guest_id, vec![2, 1]).await;
+
+        // The guest gives up before anyone rolls.
+        let mut game = Game::new(host_id);
+        game.join(guest_id).unwrap();
+        game.set_ready(guest_id, true).unwrap();
+        game.start(host_id, &mut ScriptedRoller(vec![].into_iter())).unwrap();
+        game.forfeit(guest_id).unwrap();
+        let events = game.take_uncommitted_events();
+        archive.archive_game(&game, &events).await.unwrap();
+
+        let host = archive.load_stats(host_id).await.unwrap();
+        assert_eq!((host.wins, host.forfeit_wins, host.forfeit_losses), (2, 1, 0));
+        let guest = archive.load_stats(guest_id).await.unwrap();
+        assert_eq!((guest.losses, guest.forfeit_wins, guest.forfeit_losses), (2, 0, 1));
+    }
+}
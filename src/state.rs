@@ -2,9 +2,11 @@ use serde_json::Value;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{mpsc, RwLock};
 
+use crate::archive::GameArchive;
 use crate::config::Config;
 use crate::data::GameRepository;
-use crate::game::{GameId, PlayerId};
+use crate::game::{GameId, PlayerId, TournamentId};
+use crate::players::PlayerRepository;
 
 #[derive(Debug, Clone)]
 pub struct GameMessage {
@@ -18,21 +20,28 @@ pub type PlayerSender = mpsc::UnboundedSender<GameMessage>;
 pub struct GameSession {
     // Maps PlayerId to their WebSocket sender channel
     pub players: RwLock<HashMap<PlayerId, PlayerSender>>,
+    // Watch-only connections; they get every broadcast but cannot act.
+    pub spectators: RwLock<HashMap<PlayerId, PlayerSender>>,
 }
 
 pub struct GameSessionManager {
     // Maps GameId to the in-memory GameSession struct.
     pub sessions: RwLock<HashMap<GameId, Arc<GameSession>>>,
+    // Connections following a tournament's bracket, by who is watching.
+    pub brackets: RwLock<HashMap<TournamentId, HashMap<PlayerId, PlayerSender>>>,
 }
 
 impl Default for GameSessionManager {
     fn default() -> Self {
-        Self { sessions: RwLock::new(HashMap::new()) }
+        Self { sessions: RwLock::new(HashMap::new()), brackets: RwLock::new(HashMap::new()) }
     }
 }
 
 pub struct AppState {
     pub repository: Arc<dyn GameRepository>,
+    // Where finished games are kept once the repository lets them expire.
+    pub archive: Arc<dyn GameArchive>,
+    pub players: Arc<dyn PlayerRepository>,
     pub session_manager: GameSessionManager,
     pub config: Arc<Config>,
 }
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::game::{GameId, PlayerId};
+
+/// Each winner's share of a pot: equal shares, with the odd coins going to the first winners.
+pub fn split_pot(pot: u64, winners: usize) -> Vec<u64> {
+    let winners = winners.max(1) as u64;
+    (0..winners)
+        .map(|i| pot / winners + u64::from(i < pot % winners))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LedgerKind {
+    Deposit,
+    Escrow,
+    Payout,
+    Refund,
+}
+
+/// A single balance movement. Summing a player's entries gives their balance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub id: Uuid,
+    pub player_id: PlayerId,
+    pub game_id: Option<GameId>,
+    pub kind: LedgerKind,
+    /// Signed change to the player's balance.
+    pub amount: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl LedgerEntry {
+    pub fn deposit(player_id: PlayerId, amount: u64) -> Self {
+        Self::new(player_id, None, LedgerKind::Deposit, amount as i64)
+    }
+
+    pub fn escrow(player_id: PlayerId, game_id: GameId, amount: u64) -> Self {
+        Self::new(player_id, Some(game_id), LedgerKind::Escrow, -(amount as i64))
+    }
+
+    pub fn payout(player_id: PlayerId, game_id: GameId, amount: u64) -> Self {
+        Self::new(player_id, Some(game_id), LedgerKind::Payout, amount as i64)
+    }
+
+    /// A stake handed back from a pot that was never won.
+    pub fn refund(player_id: PlayerId, game_id: GameId, amount: u64) -> Self {
+        Self::new(player_id, Some(game_id), LedgerKind::Refund, amount as i64)
+    }
+
+    fn new(player_id: PlayerId, game_id: Option<GameId>, kind: LedgerKind, amount: i64) -> Self {
+        Self { id: Uuid::new_v4(), player_id, game_id, kind, amount, created_at: Utc::now() }
+    }
+}